@@ -0,0 +1,395 @@
+use crate::{
+    GameState, InGameSet, finished_run,
+    menu::spawn_menu_button,
+    prompts::{MenuAction, MenuInput, Prompt},
+    recorded_run,
+    stats::RunStats,
+    storage::Storage,
+    toast::ShowBadgeToast,
+};
+use bevy::{prelude::*, utils::HashMap};
+
+pub const FIRST_EAT: &str = "first_eat";
+pub const REACH_FIFTY: &str = "reach_fifty";
+pub const SURVIVE_FIVE_MINUTES: &str = "survive_five_minutes";
+pub const EAT_FORTY: &str = "eat_forty";
+pub const FLIP_FIFTY: &str = "flip_fifty";
+const BAR_WIDTH: f32 = 200.;
+const BAR_HEIGHT: f32 = 6.;
+const LOCKED_COLOR: Color = Color::srgb(0.3, 0.3, 0.3);
+
+/// Milestones that are unlocked once, for good, the first time a run reaches
+/// them. Each is registered with [`AppAchievementExt::add_achievement`] as a
+/// goal for some measure of a run's [`RunStats`], checked as the run goes.
+/// Each unlock is announced with a toast carrying its badge; unlocks that
+/// land together queue up behind each other like any other toast. The
+/// achievements screen from the main menu lists them all, with how close the
+/// best run so far got to the locked ones.
+pub struct AchievementPlugin;
+
+/// Something a run can reach, once its `progress` gets to `goal`.
+pub struct AchievementDef {
+    /// Unlocks are stored under it, so it mustn't change.
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// The color of its badge.
+    pub color: Color,
+    pub goal: f32,
+    pub progress: fn(&RunStats) -> f32,
+}
+
+/// Sent the first time an achievement is reached, with its id.
+#[derive(Event)]
+pub struct AchievementUnlocked(pub &'static str);
+
+/// Every registered achievement, in the order they're listed, and what's
+/// been reached of them.
+#[derive(Resource, Default)]
+pub struct Achievements {
+    registered: Vec<AchievementDef>,
+    unlocked: Vec<&'static str>,
+    /// The furthest any run got towards each achievement, by id.
+    best: HashMap<&'static str, f32>,
+}
+
+pub trait AppAchievementExt {
+    fn add_achievement(&mut self, achievement: AchievementDef) -> &mut Self;
+}
+
+#[derive(Component, Clone, Copy)]
+enum AchievementButton {
+    Back,
+}
+
+impl Plugin for AchievementPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_achievement(AchievementDef {
+            id: FIRST_EAT,
+            name: "First bite",
+            description: "eat a ball",
+            color: Color::srgb(0.8, 0.5, 0.2),
+            goal: 1.,
+            progress: |stats| stats.balls_eaten as f32,
+        })
+        .add_achievement(AchievementDef {
+            id: REACH_FIFTY,
+            name: "Half a hundred",
+            description: "reach 50",
+            color: Color::srgb(0.75, 0.75, 0.8),
+            goal: 50.,
+            progress: |stats| stats.peak_number as f32,
+        })
+        .add_achievement(AchievementDef {
+            id: SURVIVE_FIVE_MINUTES,
+            name: "Stayer",
+            description: "survive 5 minutes",
+            color: Color::srgb(1., 0.8, 0.2),
+            goal: 5. * 60.,
+            progress: |stats| stats.duration,
+        })
+        .add_achievement(AchievementDef {
+            id: EAT_FORTY,
+            name: "Big game",
+            description: "eat a 40 or bigger",
+            color: Color::srgb(0.9, 0.3, 0.3),
+            goal: 40.,
+            progress: |stats| stats.largest_ball_eaten as f32,
+        })
+        .add_achievement(AchievementDef {
+            id: FLIP_FIFTY,
+            name: "Acrobat",
+            description: "flip gravity 50 times in a run",
+            color: Color::srgb(0.4, 0.8, 1.),
+            goal: 50.,
+            progress: |stats| stats.gravity_flips as f32,
+        })
+        .add_event::<AchievementUnlocked>()
+        .add_systems(Startup, load_achievements)
+        .add_systems(
+            Update,
+            (
+                check_achievements.run_if(recorded_run),
+                announce_achievements,
+            )
+                .chain()
+                .in_set(InGameSet),
+        )
+        .add_systems(
+            OnExit(GameState::InGame),
+            record_best_progress.run_if(recorded_run.and(finished_run)),
+        )
+        .add_systems(OnEnter(GameState::Achievements), setup_achievements_screen)
+        .add_systems(
+            Update,
+            leave_achievements_screen.run_if(in_state(GameState::Achievements)),
+        );
+    }
+}
+
+impl AppAchievementExt for App {
+    fn add_achievement(&mut self, achievement: AchievementDef) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<Achievements>()
+            .registered
+            .push(achievement);
+        self
+    }
+}
+
+impl Achievements {
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.contains(&id)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&AchievementDef> {
+        self.registered
+            .iter()
+            .find(|achievement| achievement.id == id)
+    }
+
+    /// How far the best run got towards it, from 0 to 1.
+    fn best_fraction(&self, achievement: &AchievementDef) -> f32 {
+        if self.is_unlocked(achievement.id) {
+            return 1.;
+        }
+        let best = self.best.get(achievement.id).copied().unwrap_or(0.);
+        (best / achievement.goal).clamp(0., 1.)
+    }
+}
+
+fn unlocked_key(id: &str) -> String {
+    format!("achievement_{id}")
+}
+
+fn best_key(id: &str) -> String {
+    format!("achievement_{id}_best")
+}
+
+fn load_achievements(storage: Res<Storage>, mut achievements: ResMut<Achievements>) {
+    let achievements = &mut *achievements;
+    achievements.unlocked = achievements
+        .registered
+        .iter()
+        .map(|achievement| achievement.id)
+        .filter(|id| storage.load(&unlocked_key(id)).unwrap_or(false))
+        .collect();
+    achievements.best = achievements
+        .registered
+        .iter()
+        .filter_map(|achievement| {
+            storage
+                .load(&best_key(achievement.id))
+                .map(|best| (achievement.id, best))
+        })
+        .collect();
+}
+
+fn check_achievements(
+    stats: Res<RunStats>,
+    mut achievements: ResMut<Achievements>,
+    mut storage: ResMut<Storage>,
+    mut unlocked_writer: EventWriter<AchievementUnlocked>,
+) {
+    let achievements = &mut *achievements;
+
+    for achievement in &achievements.registered {
+        if (achievement.progress)(&stats) < achievement.goal
+            || achievements.unlocked.contains(&achievement.id)
+        {
+            continue;
+        }
+
+        achievements.unlocked.push(achievement.id);
+        storage.save(&unlocked_key(achievement.id), true);
+        unlocked_writer.send(AchievementUnlocked(achievement.id));
+    }
+}
+
+fn announce_achievements(
+    mut unlocked_reader: EventReader<AchievementUnlocked>,
+    achievements: Res<Achievements>,
+    mut toast_writer: EventWriter<ShowBadgeToast>,
+) {
+    for AchievementUnlocked(id) in unlocked_reader.read() {
+        let Some(achievement) = achievements.get(id) else {
+            continue;
+        };
+        toast_writer.send(ShowBadgeToast {
+            text: format!(
+                "Achievement: {} - {}",
+                achievement.name, achievement.description
+            ),
+            color: achievement.color,
+        });
+    }
+}
+
+/// Keeps how far the run got towards anything still locked, if it's further
+/// than before.
+fn record_best_progress(
+    stats: Res<RunStats>,
+    mut achievements: ResMut<Achievements>,
+    mut storage: ResMut<Storage>,
+) {
+    let achievements = &mut *achievements;
+
+    for achievement in &achievements.registered {
+        let progress = (achievement.progress)(&stats);
+        let best = achievements.best.entry(achievement.id).or_insert(0.);
+        if achievements.unlocked.contains(&achievement.id) || progress <= *best {
+            continue;
+        }
+
+        *best = progress;
+        storage.save(&best_key(achievement.id), progress);
+    }
+}
+
+fn setup_achievements_screen(mut commands: Commands, achievements: Res<Achievements>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(12.),
+                ..default()
+            },
+            StateScoped(GameState::Achievements),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new("Achievements"),
+                TextFont {
+                    font_size: 60.,
+                    ..default()
+                },
+            ));
+            builder.spawn((
+                Text::new(format!(
+                    "{} of {} unlocked",
+                    achievements.unlocked.len(),
+                    achievements.registered.len()
+                )),
+                TextFont {
+                    font_size: 20.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+            for achievement in &achievements.registered {
+                spawn_achievement_row(builder, &achievements, achievement);
+            }
+            spawn_menu_button(builder, AchievementButton::Back, "Back");
+            builder.spawn((
+                Text::default(),
+                Prompt(vec![(MenuAction::Quit, "back")]),
+                TextFont {
+                    font_size: 20.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+/// Its badge, name and description, and a bar for how close the best run got.
+fn spawn_achievement_row(
+    builder: &mut ChildBuilder,
+    achievements: &Achievements,
+    achievement: &AchievementDef,
+) {
+    let unlocked = achievements.is_unlocked(achievement.id);
+    let fraction = achievements.best_fraction(achievement);
+    let status = if unlocked {
+        "unlocked".to_string()
+    } else {
+        format!("{:.0}/{:.0}", fraction * achievement.goal, achievement.goal)
+    };
+    let color = if unlocked {
+        achievement.color
+    } else {
+        LOCKED_COLOR
+    };
+
+    builder
+        .spawn(Node {
+            width: Val::Px(520.),
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(12.),
+            ..default()
+        })
+        .with_children(|builder| {
+            builder.spawn((
+                Node {
+                    width: Val::Px(24.),
+                    height: Val::Px(24.),
+                    ..default()
+                },
+                BackgroundColor(color),
+            ));
+            builder
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    flex_grow: 1.,
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder.spawn((
+                        Text::new(format!(
+                            "{} - {}",
+                            achievement.name, achievement.description
+                        )),
+                        TextFont {
+                            font_size: 20.,
+                            ..default()
+                        },
+                    ));
+                    builder
+                        .spawn((
+                            Node {
+                                width: Val::Px(BAR_WIDTH),
+                                height: Val::Px(BAR_HEIGHT),
+                                margin: UiRect::top(Val::Px(4.)),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgba(1., 1., 1., 0.15)),
+                        ))
+                        .with_children(|builder| {
+                            builder.spawn((
+                                Node {
+                                    width: Val::Percent(fraction * 100.),
+                                    height: Val::Percent(100.),
+                                    ..default()
+                                },
+                                BackgroundColor(achievement.color),
+                            ));
+                        });
+                });
+            builder.spawn((
+                Text::new(status),
+                TextFont {
+                    font_size: 20.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+fn leave_achievements_screen(
+    button_query: Query<(&Interaction, &AchievementButton), Changed<Interaction>>,
+    menu_input: MenuInput,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    let clicked = button_query
+        .iter()
+        .any(|(interaction, AchievementButton::Back)| *interaction == Interaction::Pressed);
+
+    if menu_input.just_pressed(MenuAction::Quit) || clicked {
+        next_game_state.set(GameState::Menu);
+    }
+}
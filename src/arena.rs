@@ -0,0 +1,260 @@
+use crate::{Bound, SIZE_FACTOR, spatial::BallGrid, surface::Surface};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Balls farther than this from a spawn point don't make it any less safe.
+const SPAWN_CLEARANCE: f32 = 600.;
+
+/// Which [`ArenaLayout`] a run is played in.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+pub enum ArenaKind {
+    #[default]
+    Classic,
+    /// The classic box with a different surface on every wall.
+    Workshop,
+    /// The classic box with conveyor strips along the floor pulling towards
+    /// the side walls.
+    Drift,
+    /// The classic box with a treasure room in the top right corner, whose
+    /// door only opens for a big enough player.
+    Vault,
+}
+
+impl ArenaKind {
+    pub const ALL: [ArenaKind; 4] = [
+        ArenaKind::Classic,
+        ArenaKind::Workshop,
+        ArenaKind::Drift,
+        ArenaKind::Vault,
+    ];
+}
+
+pub struct WallSpec {
+    pub size: Vec2,
+    pub position: Vec2,
+    pub surface: Surface,
+}
+
+/// A strip that drags anything dynamic overlapping it at `velocity`,
+/// without being solid itself.
+pub struct ConveyorZone {
+    pub size: Vec2,
+    pub position: Vec2,
+    pub velocity: Vec2,
+}
+
+/// A door that stays shut until the player's number exceeds `threshold`,
+/// guarding a room stocked with `treasure` stationary balls.
+pub struct GateSpec {
+    pub size: Vec2,
+    pub position: Vec2,
+    pub threshold: i32,
+    pub room: Rect,
+    pub treasure: usize,
+}
+
+pub struct PlayerSpawn {
+    pub position: Vec2,
+    pub facing: Dir2,
+}
+
+impl PlayerSpawn {
+    /// The player's rotation when spawned here.
+    pub fn rotation(&self) -> Quat {
+        Quat::from_rotation_arc_2d(Vec2::Y, *self.facing)
+    }
+}
+
+/// The static shape of an arena: its walls and where the player may spawn.
+#[derive(Resource)]
+pub struct ArenaLayout {
+    pub walls: Vec<WallSpec>,
+    pub conveyor_zones: Vec<ConveyorZone>,
+    pub gates: Vec<GateSpec>,
+    pub player_spawns: Vec<PlayerSpawn>,
+}
+
+impl ArenaLayout {
+    pub fn new(kind: ArenaKind) -> Self {
+        match kind {
+            ArenaKind::Classic => Self::classic(),
+            ArenaKind::Workshop => Self::workshop(),
+            ArenaKind::Drift => Self::drift(),
+            ArenaKind::Vault => Self::vault(),
+        }
+    }
+
+    fn classic() -> Self {
+        Self::boxed([Surface::Standard; 4])
+    }
+
+    fn workshop() -> Self {
+        Self::boxed([
+            Surface::Bouncy,
+            Surface::Conveyor(250.),
+            Surface::Dampening,
+            Surface::Sticky,
+        ])
+    }
+
+    fn drift() -> Self {
+        let floor = Bound::LowerBound.value() + 40.;
+        let strip = |x: f32, velocity: f32| ConveyorZone {
+            size: Vec2::new(900., 60.),
+            position: Vec2::new(x, floor),
+            velocity: Vec2::new(velocity, 0.),
+        };
+
+        ArenaLayout {
+            conveyor_zones: vec![strip(-460., -200.), strip(460., 200.)],
+            ..Self::classic()
+        }
+    }
+
+    fn vault() -> Self {
+        let mut layout = Self::classic();
+        let top = Bound::UpperBound.value() - 10.;
+        let right = Bound::RightBound.value() - 10.;
+        let room = Rect::new(540., 210., right, top);
+
+        layout.walls.extend([
+            WallSpec {
+                size: Vec2::new(20., top - 190.),
+                position: Vec2::new(530., (top + 190.) / 2.),
+                surface: Surface::Standard,
+            },
+            WallSpec {
+                size: Vec2::new(270., 20.),
+                position: Vec2::new(655., 200.),
+                surface: Surface::Standard,
+            },
+        ]);
+        layout.gates.push(GateSpec {
+            size: Vec2::new(right - 790., 20.),
+            position: Vec2::new((right + 790.) / 2., 200.),
+            threshold: 40,
+            room,
+            treasure: 12,
+        });
+        layout
+            .player_spawns
+            .retain(|spawn| !room.contains(spawn.position));
+
+        layout
+    }
+
+    /// The arena bounds walled in with the given surfaces, in upper, lower,
+    /// left, right order.
+    fn boxed(surfaces: [Surface; 4]) -> Self {
+        let wall = |size: (f32, f32), position: (f32, f32), surface| WallSpec {
+            size: Vec2::new(size.0, size.1),
+            position: Vec2::new(position.0, position.1),
+            surface,
+        };
+        let spawn = |x: f32, y: f32| PlayerSpawn {
+            position: Vec2::new(x, y),
+            facing: Dir2::Y,
+        };
+
+        ArenaLayout {
+            walls: vec![
+                wall((1880., 20.), (0., Bound::UpperBound.value()), surfaces[0]),
+                wall((1880., 20.), (0., Bound::LowerBound.value()), surfaces[1]),
+                wall((20., 1020.), (Bound::LeftBound.value(), 0.), surfaces[2]),
+                wall((20., 1020.), (Bound::RightBound.value(), 0.), surfaces[3]),
+            ],
+            conveyor_zones: Vec::new(),
+            gates: Vec::new(),
+            player_spawns: vec![spawn(200., 0.), spawn(-600., -300.), spawn(600., 300.)],
+        }
+    }
+
+    /// Where runs start. Runs start before any ball is around, so the first
+    /// spawn point is as safe as any.
+    pub fn player_spawn(&self) -> &PlayerSpawn {
+        self.player_spawns
+            .first()
+            .expect("arena layouts need at least one player spawn")
+    }
+
+    /// The spawn point farthest from any ball bigger than `number`, for
+    /// putting the player back mid-run. Ties, including no big ball being
+    /// near any of them, go to the earlier spawn point.
+    pub fn safest_spawn(&self, grid: &BallGrid, number: i32) -> &PlayerSpawn {
+        let clearance = |spawn: &PlayerSpawn| {
+            grid.within(spawn.position, SPAWN_CLEARANCE)
+                .filter(|ball| ball.number > number)
+                .map(|ball| ball.position.distance(spawn.position))
+                .fold(SPAWN_CLEARANCE, f32::min)
+        };
+
+        self.player_spawns
+            .iter()
+            .rev()
+            .max_by(|a, b| clearance(a).total_cmp(&clearance(b)))
+            .expect("arena layouts need at least one player spawn")
+    }
+
+    /// Everything wrong with the layout for a player starting at
+    /// `starting_number`, for `--validate`.
+    pub fn problems(&self, starting_number: i32) -> Vec<String> {
+        let mut problems = Vec::new();
+        let arena = Bound::arena();
+        let player_size = Vec2::splat(starting_number as f32 * SIZE_FACTOR);
+        let overlaps = |a: Rect, b: Rect| !a.intersect(b).is_empty();
+
+        if self.player_spawns.is_empty() {
+            problems.push("has no player spawns".to_string());
+        }
+        for (index, spawn) in self.player_spawns.iter().enumerate() {
+            let player = Rect::from_center_size(spawn.position, player_size);
+            let at = format!("player spawn {index} at {}", spawn.position);
+            if arena.union(player) != arena {
+                problems.push(format!("{at} is outside the arena"));
+            }
+            if let Some(wall) = self
+                .walls
+                .iter()
+                .find(|wall| overlaps(player, Rect::from_center_size(wall.position, wall.size)))
+            {
+                problems.push(format!("{at} overlaps the wall at {}", wall.position));
+            }
+            for gate in &self.gates {
+                if overlaps(player, Rect::from_center_size(gate.position, gate.size)) {
+                    problems.push(format!("{at} overlaps the gate at {}", gate.position));
+                }
+                if overlaps(player, gate.room) {
+                    problems.push(format!(
+                        "{at} is shut in the room behind the gate at {}",
+                        gate.position
+                    ));
+                }
+            }
+            for (other_index, other) in self.player_spawns.iter().enumerate().skip(index + 1) {
+                if overlaps(player, Rect::from_center_size(other.position, player_size)) {
+                    problems.push(format!("{at} overlaps player spawn {other_index}"));
+                }
+            }
+        }
+
+        for gate in &self.gates {
+            let at = format!("the gate at {}", gate.position);
+            if arena.union(gate.room) != arena {
+                problems.push(format!("the room behind {at} is outside the arena"));
+            }
+            if gate.threshold < starting_number {
+                problems.push(format!("{at} is open from the start"));
+            }
+        }
+        for zone in &self.conveyor_zones {
+            if !overlaps(arena, Rect::from_center_size(zone.position, zone.size)) {
+                problems.push(format!(
+                    "the conveyor at {} is outside the arena",
+                    zone.position
+                ));
+            }
+        }
+
+        problems
+    }
+}
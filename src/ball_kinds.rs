@@ -0,0 +1,220 @@
+use crate::{Ball, InGameSet, Player};
+use avian2d::prelude::*;
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use rand::Rng;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Relative to the assets folder.
+const BALL_KINDS_PATH: &str = "balls.ron";
+
+/// Balls come in kinds defined in `assets/balls.ron`, each made of its own
+/// material: rubber balls fling the player away, iron ones stop them dead.
+/// A kind is picked by weight for every ball spawned on its own. The file is
+/// loaded behind the splash screen, and balls are plain until it has, or if
+/// it can't be, which is reported like any other missing asset.
+pub struct BallKindPlugin;
+
+#[derive(Resource, Default, Clone)]
+pub struct BallKinds(Vec<BallKind>);
+
+/// `assets/balls.ron` as loaded, copied into [`BallKinds`] once it is.
+#[derive(Asset, TypePath)]
+pub struct BallKindsFile(BallKinds);
+
+/// The file being loaded, for waiting on it.
+#[derive(Resource)]
+pub struct BallKindSource(pub Handle<BallKindsFile>);
+
+#[derive(Default)]
+struct BallKindsLoader;
+
+#[derive(Clone, Deserialize)]
+pub struct BallKind {
+    name: String,
+    weight: u32,
+    color: (f32, f32, f32),
+    restitution: f32,
+    friction: f32,
+    combine: Combine,
+    contact_damping: f32,
+}
+
+/// How a ball's coefficients mix with the player's.
+#[derive(Clone, Copy, Deserialize)]
+enum Combine {
+    Average,
+    Min,
+    Max,
+}
+
+/// Which kind a ball is, by name.
+#[derive(Component)]
+pub struct OfKind(pub String);
+
+/// Slows the player down while they touch the ball, see
+/// [`BallKind::contact_damping`].
+#[derive(Component)]
+struct ContactDamping(f32);
+
+impl Plugin for BallKindPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BallKinds>()
+            .init_asset::<BallKindsFile>()
+            .init_asset_loader::<BallKindsLoader>()
+            .add_systems(Startup, load_ball_kinds)
+            .add_systems(Update, apply_loaded_ball_kinds)
+            .add_systems(FixedUpdate, damp_player_on_contact.in_set(InGameSet));
+    }
+}
+
+impl AssetLoader for BallKindsLoader {
+    type Asset = BallKindsFile;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<BallKindsFile, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(BallKindsFile(BallKinds(ron::de::from_bytes(&bytes)?)))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+impl BallKinds {
+    /// Straight from the file, for `--validate`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| format!("couldn't read {}: {error}", path.display()))?;
+
+        ron::from_str(&contents)
+            .map(BallKinds)
+            .map_err(|error| format!("invalid: {error}"))
+    }
+
+    /// Everything wrong with the kinds, for `--validate`.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.0.iter().all(|kind| kind.weight == 0) {
+            problems.push("no kind has any weight".to_string());
+        }
+
+        for (index, kind) in self.0.iter().enumerate() {
+            let name = &kind.name;
+            if self.0[..index].iter().any(|other| other.name == *name) {
+                problems.push(format!("`{name}` is defined more than once"));
+            }
+            let (red, green, blue) = kind.color;
+            if [red, green, blue]
+                .iter()
+                .any(|channel| !(0. ..=1.).contains(channel))
+            {
+                problems.push(format!("`{name}` has color channels outside 0 to 1"));
+            }
+            if !(0. ..=1.).contains(&kind.restitution) {
+                problems.push(format!("`{name}` has restitution outside 0 to 1"));
+            }
+            if kind.friction < 0. {
+                problems.push(format!("`{name}` has negative friction"));
+            }
+            if kind.contact_damping < 0. {
+                problems.push(format!("`{name}` has negative contact damping"));
+            }
+        }
+
+        problems
+    }
+
+    pub fn get(&self, name: &str) -> Option<&BallKind> {
+        self.0.iter().find(|kind| kind.name == name)
+    }
+
+    /// `None` if no kind has any weight.
+    pub fn random(&self, rng: &mut impl Rng) -> Option<&BallKind> {
+        let total: u32 = self.0.iter().map(|kind| kind.weight).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rng.random_range(0..total);
+        self.0.iter().find(|kind| {
+            let picked = roll < kind.weight;
+            roll = roll.saturating_sub(kind.weight);
+            picked
+        })
+    }
+}
+
+impl BallKind {
+    /// Gives a freshly spawned ball this kind's material and color.
+    pub fn apply(&self, ball: &mut EntityCommands, materials: &mut Assets<ColorMaterial>) {
+        let combine = match self.combine {
+            Combine::Average => CoefficientCombine::Average,
+            Combine::Min => CoefficientCombine::Min,
+            Combine::Max => CoefficientCombine::Max,
+        };
+        let (red, green, blue) = self.color;
+
+        ball.insert((
+            Restitution::new(self.restitution).with_combine_rule(combine),
+            Friction::new(self.friction).with_combine_rule(combine),
+            MeshMaterial2d(materials.add(Color::srgb(red, green, blue))),
+            Name::new(format!("{} ball", self.name)),
+            OfKind(self.name.clone()),
+        ));
+        if self.contact_damping > 0. {
+            ball.insert(ContactDamping(self.contact_damping));
+        }
+    }
+}
+
+fn load_ball_kinds(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(BallKindSource(asset_server.load(BALL_KINDS_PATH)));
+}
+
+/// Again whenever the file changes, with hot reloading on.
+fn apply_loaded_ball_kinds(
+    mut asset_events: EventReader<AssetEvent<BallKindsFile>>,
+    files: Res<Assets<BallKindsFile>>,
+    mut ball_kinds: ResMut<BallKinds>,
+) {
+    for event in asset_events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        if let Some(BallKindsFile(kinds)) = files.get(*id) {
+            *ball_kinds = kinds.clone();
+        }
+    }
+}
+
+/// The same damping avian gives dynamic bodies, which kinematic balls can't
+/// pass on by themselves.
+fn damp_player_on_contact(
+    mut player_query: Query<(&CollidingEntities, &mut LinearVelocity), With<Player>>,
+    ball_query: Query<&ContactDamping, With<Ball>>,
+    time: Res<Time>,
+) {
+    for (hits, mut velocity) in player_query.iter_mut() {
+        let damping: f32 = hits
+            .iter()
+            .filter_map(|hit| ball_query.get(*hit).ok())
+            .map(|ContactDamping(damping)| damping)
+            .sum();
+        if damping > 0. {
+            velocity.0 *= 1. / (1. + time.delta_secs() * damping);
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::{
+    BallEaten, FatalHit, GameRng, GameState, RunState, config::GameConfig, errors::GameError,
+    toast::ShowToast,
+};
+use bevy::{
+    diagnostic::SystemInfo,
+    prelude::*,
+    render::view::screenshot::{Screenshot, ScreenshotCaptured},
+    utils::SystemTime,
+};
+use std::{collections::VecDeque, io::Cursor, ops::Deref, path::PathBuf, process::Command};
+
+const REPORT_KEY: KeyCode = KeyCode::F8;
+/// How far back the event log in a report goes, in seconds.
+const EVENT_LOG_WINDOW: f32 = 60.;
+
+/// F8 freezes the game and saves a bug report: a zip of a screenshot, what
+/// happened over the last minute, the seed and what the game is running on,
+/// in a `reports` folder next to the photos. If the config has a
+/// `bug_report_hook`, it's run with the zip's path, e.g. to upload it. The
+/// game carries on once the report is saved. Web builds have nowhere to save
+/// reports, so there the key does nothing.
+pub struct BugReportPlugin;
+
+/// Timestamped, in seconds since the game started.
+#[derive(Resource, Default)]
+struct EventLog(VecDeque<(f32, String)>);
+
+#[derive(Resource, Default)]
+struct BugReport {
+    capturing: bool,
+    /// Whether the game was paused already, so it stays paused after.
+    was_paused: bool,
+}
+
+impl Plugin for BugReportPlugin {
+    fn build(&self, app: &mut App) {
+        if cfg!(target_arch = "wasm32") {
+            return;
+        }
+
+        app.init_resource::<EventLog>()
+            .init_resource::<BugReport>()
+            .init_resource::<SystemInfo>()
+            .add_systems(Update, (log_events, start_bug_report).chain());
+    }
+}
+
+impl EventLog {
+    fn push(&mut self, now: f32, line: String) {
+        self.0.push_back((now, line));
+        while self
+            .0
+            .front()
+            .is_some_and(|(at, _)| now - at > EVENT_LOG_WINDOW)
+        {
+            self.0.pop_front();
+        }
+    }
+
+    fn contents(&self) -> String {
+        self.0
+            .iter()
+            .map(|(at, line)| format!("[{at:>9.2}s] {line}\n"))
+            .collect()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn report_path(file_name: String) -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("shape-eater")
+        .join("reports")
+        .join(file_name)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn report_path(file_name: String) -> PathBuf {
+    PathBuf::from(file_name)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn log_events(
+    mut event_log: ResMut<EventLog>,
+    mut game_state_reader: EventReader<StateTransitionEvent<GameState>>,
+    mut run_state_reader: EventReader<StateTransitionEvent<RunState>>,
+    mut ball_eaten_reader: EventReader<BallEaten>,
+    mut fatal_hit_reader: EventReader<FatalHit>,
+    mut error_reader: EventReader<GameError>,
+    mut toast_reader: EventReader<ShowToast>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_secs();
+
+    for transition in game_state_reader.read() {
+        event_log.push(now, format!("game state: {:?}", transition.entered));
+    }
+    for transition in run_state_reader.read() {
+        event_log.push(now, format!("run state: {:?}", transition.entered));
+    }
+    for BallEaten {
+        number, position, ..
+    } in ball_eaten_reader.read()
+    {
+        event_log.push(now, format!("ate a {number} at {position}"));
+    }
+    for FatalHit { ball } in fatal_hit_reader.read() {
+        event_log.push(now, format!("fatal hit from {ball:?}"));
+    }
+    for error in error_reader.read() {
+        event_log.push(now, format!("error: {error}"));
+    }
+    for ShowToast(text) in toast_reader.read() {
+        event_log.push(now, format!("toast: {text}"));
+    }
+}
+
+fn start_bug_report(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut bug_report: ResMut<BugReport>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    if !keys.just_pressed(REPORT_KEY) || bug_report.capturing {
+        return;
+    }
+
+    *bug_report = BugReport {
+        capturing: true,
+        was_paused: virtual_time.is_paused(),
+    };
+    virtual_time.pause();
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_bug_report);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn save_bug_report(
+    trigger: Trigger<ScreenshotCaptured>,
+    mut bug_report: ResMut<BugReport>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    event_log: Res<EventLog>,
+    game_rng: Res<GameRng>,
+    game_state: Res<State<GameState>>,
+    system_info: Res<SystemInfo>,
+    config: Res<GameConfig>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    bug_report.capturing = false;
+    if !bug_report.was_paused {
+        virtual_time.unpause();
+    }
+
+    let mut screenshot = Vec::new();
+    match trigger.event().deref().clone().try_into_dynamic() {
+        Ok(image) => {
+            if let Err(error) = image
+                .to_rgb8()
+                .write_to(&mut Cursor::new(&mut screenshot), image::ImageFormat::Png)
+            {
+                warn!("failed to encode the bug report screenshot: {error}");
+            }
+        }
+        Err(error) => warn!("failed to read the bug report screenshot: {error}"),
+    }
+
+    let summary = format!(
+        "shape-eater {}\nseed: {}\nstate: {:?}\nos: {}\nkernel: {}\ncpu: {} ({} cores)\nmemory: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        game_rng.seed,
+        game_state.get(),
+        system_info.os,
+        system_info.kernel,
+        system_info.cpu,
+        system_info.core_count,
+        system_info.memory,
+    );
+
+    let saved_at = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let path = report_path(format!("report-{}-{saved_at}.zip", game_rng.seed));
+    let archive = zip(&[
+        ("report.txt", summary.into_bytes()),
+        ("events.txt", event_log.contents().into_bytes()),
+        ("screenshot.png", screenshot),
+    ]);
+    let saved = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&path, archive));
+    if let Err(error) = saved {
+        warn!("failed to save the bug report: {error}");
+        toast_writer.send(ShowToast("Couldn't save the bug report".to_string()));
+        return;
+    }
+    toast_writer.send(ShowToast(format!("Saved {}", path.display())));
+
+    let Some(hook) = &config.bug_report_hook else {
+        return;
+    };
+    if let Err(error) = Command::new(hook).arg(&path).spawn() {
+        warn!("failed to run the bug report hook {hook}: {error}");
+    }
+}
+
+/// A zip archive of `files`, stored without compression.
+fn zip(files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    /// 1 January 1980, the earliest date a zip can hold, in DOS format.
+    const DOS_DATE: u16 = 0x21;
+
+    let mut archive = Vec::new();
+    let mut directory = Vec::new();
+
+    for (name, contents) in files {
+        let offset = archive.len() as u32;
+        let crc = crc32fast::hash(contents);
+        let size = contents.len() as u32;
+        // Version needed, flags, method, time, date, crc, sizes, name length.
+        let mut header = Vec::new();
+        header.extend(20u16.to_le_bytes());
+        header.extend(0u16.to_le_bytes());
+        header.extend(0u16.to_le_bytes());
+        header.extend(0u16.to_le_bytes());
+        header.extend(DOS_DATE.to_le_bytes());
+        header.extend(crc.to_le_bytes());
+        header.extend(size.to_le_bytes());
+        header.extend(size.to_le_bytes());
+        header.extend((name.len() as u16).to_le_bytes());
+
+        archive.extend(0x04034b50u32.to_le_bytes());
+        archive.extend(&header);
+        archive.extend(0u16.to_le_bytes());
+        archive.extend(name.as_bytes());
+        archive.extend(contents);
+
+        directory.extend(0x02014b50u32.to_le_bytes());
+        directory.extend(20u16.to_le_bytes());
+        directory.extend(&header);
+        // Extra, comment, disk, internal and external attributes.
+        directory.extend([0; 12]);
+        directory.extend(offset.to_le_bytes());
+        directory.extend(name.as_bytes());
+    }
+
+    let directory_offset = archive.len() as u32;
+    let entries = files.len() as u16;
+    archive.extend(&directory);
+    archive.extend(0x06054b50u32.to_le_bytes());
+    archive.extend([0; 4]);
+    archive.extend(entries.to_le_bytes());
+    archive.extend(entries.to_le_bytes());
+    archive.extend((directory.len() as u32).to_le_bytes());
+    archive.extend(directory_offset.to_le_bytes());
+    archive.extend(0u16.to_le_bytes());
+    archive
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn zip_reads_back() {
+        let files = [
+            ("report.txt", b"seed: 42\n".to_vec()),
+            ("events.txt", Vec::new()),
+            ("screenshot.png", (0..=255).collect()),
+        ];
+        let mut archive = ::zip::ZipArchive::new(Cursor::new(zip(&files))).unwrap();
+
+        assert_eq!(archive.len(), files.len());
+        for (index, (name, contents)) in files.iter().enumerate() {
+            let mut file = archive.by_index(index).unwrap();
+            assert_eq!(file.name(), *name);
+            let mut read = Vec::new();
+            file.read_to_end(&mut read).unwrap();
+            assert_eq!(read, *contents);
+        }
+    }
+}
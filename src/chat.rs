@@ -0,0 +1,343 @@
+use crate::{
+    Bound, GOLDEN_GLOW_COLOR, GameState, InGameEntity, InGameSet, Numbered, Player, SWARM_NUMBERS,
+    config::GameConfig,
+    gravity::{GravityDirection, TriggerGravityEvent},
+    random_point_on_bound,
+    safe_area::KeepInSafeArea,
+    spawn_numbered_ball,
+    stats::RunModifier,
+    timers::{AppGameTimersExt, GameTimers},
+    toast::ShowToast,
+};
+use bevy::{prelude::*, utils::HashMap};
+use rand::Rng;
+use std::sync::{
+    Mutex,
+    mpsc::{self, Receiver},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+const TWITCH_IRC_ADDRESS: &str = "irc.chat.twitch.tv:6667";
+#[cfg(not(target_arch = "wasm32"))]
+const RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(10);
+/// How long each vote stays open. Only one event can be triggered per
+/// vote, which keeps a busy chat from flooding the run.
+const VOTE_DURATION: f32 = 20.;
+const VOTE_TIMER: &str = "chat_vote";
+/// Fewer votes than this for the winner and nothing happens.
+const MIN_VOTES: usize = 3;
+const BALL_RAIN_COUNT: usize = 20;
+const GOLDEN_BALL_SPEED: f32 = 150.;
+
+/// With `chat_channel` set in the config, viewers in that Twitch channel's
+/// chat can vote for events during a run by typing `!rain`, `!zerog` or
+/// `!golden`. Votes are tallied over a window, one per viewer, and the event
+/// with the most goes off when it closes. Chat is read anonymously over IRC
+/// on a thread of its own, so nothing needs a login. Not available in web
+/// builds.
+pub struct ChatPlugin;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum ChatEvent {
+    BallRain,
+    ZeroGravity,
+    GoldenBall,
+}
+
+/// A vote read from chat on the connection's thread.
+struct ChatVote {
+    viewer: String,
+    event: ChatEvent,
+}
+
+#[derive(Resource)]
+struct ChatConnection(Mutex<Receiver<ChatVote>>);
+
+/// Each viewer's latest vote in the one that's open.
+#[derive(Resource, Default)]
+struct ChatVotes {
+    votes: HashMap<String, ChatEvent>,
+}
+
+#[derive(Event)]
+struct ChatEventWon(ChatEvent);
+
+#[derive(Component)]
+struct VoteTally;
+
+impl Plugin for ChatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ChatEventWon>()
+            .init_resource::<ChatVotes>()
+            .add_game_timer(
+                VOTE_TIMER,
+                Timer::from_seconds(VOTE_DURATION, TimerMode::Repeating),
+            )
+            .add_systems(Startup, connect_to_chat)
+            .add_systems(
+                OnEnter(GameState::InGame),
+                open_voting.run_if(resource_exists::<ChatConnection>),
+            )
+            // Game timers tick on the fixed step.
+            .add_systems(
+                FixedUpdate,
+                (tally_votes, run_chat_events)
+                    .chain()
+                    .in_set(InGameSet)
+                    .run_if(resource_exists::<ChatConnection>),
+            )
+            .add_systems(
+                Update,
+                update_vote_tally
+                    .in_set(InGameSet)
+                    .run_if(resource_exists::<ChatConnection>),
+            );
+    }
+}
+
+impl ChatEvent {
+    const ALL: [ChatEvent; 3] = [
+        ChatEvent::BallRain,
+        ChatEvent::ZeroGravity,
+        ChatEvent::GoldenBall,
+    ];
+
+    fn command(self) -> &'static str {
+        match self {
+            ChatEvent::BallRain => "!rain",
+            ChatEvent::ZeroGravity => "!zerog",
+            ChatEvent::GoldenBall => "!golden",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ChatEvent::BallRain => "ball rain",
+            ChatEvent::ZeroGravity => "zero-g",
+            ChatEvent::GoldenBall => "golden ball",
+        }
+    }
+}
+
+impl ChatVotes {
+    fn count(&self, event: ChatEvent) -> usize {
+        self.votes.values().filter(|vote| **vote == event).count()
+    }
+}
+
+/// Reads a vote out of a chat message, which comes in like
+/// `:viewer!viewer@viewer.tmi.twitch.tv PRIVMSG #channel :!rain`.
+fn parse_vote(line: &str) -> Option<ChatVote> {
+    let (source, command) = line.strip_prefix(':')?.split_once(' ')?;
+    let (_, message) = command.strip_prefix("PRIVMSG ")?.split_once(" :")?;
+    let viewer = source.split('!').next()?;
+    let first_word = message.split_whitespace().next()?;
+
+    let event = ChatEvent::ALL
+        .into_iter()
+        .find(|event| event.command().eq_ignore_ascii_case(first_word))?;
+    Some(ChatVote {
+        viewer: viewer.to_string(),
+        event,
+    })
+}
+
+fn connect_to_chat(mut commands: Commands, config: Res<GameConfig>) {
+    let Some(channel) = config.chat_channel.clone() else {
+        return;
+    };
+
+    let (vote_sender, vote_receiver) = mpsc::channel();
+    #[cfg(not(target_arch = "wasm32"))]
+    std::thread::spawn(move || read_chat(&channel, &vote_sender));
+    #[cfg(target_arch = "wasm32")]
+    {
+        drop(vote_sender);
+        warn!("can't read #{channel}'s chat, chat integration isn't available in web builds");
+    }
+    commands.insert_resource(ChatConnection(Mutex::new(vote_receiver)));
+}
+
+/// Keeps reconnecting whenever the connection drops, until the game closes.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_chat(channel: &str, vote_sender: &mpsc::Sender<ChatVote>) {
+    loop {
+        match read_chat_until_dropped(channel, vote_sender) {
+            Ok(()) => return,
+            Err(error) => warn!("lost the connection to #{channel}'s chat: {error}"),
+        }
+        std::thread::sleep(RECONNECT_DELAY);
+    }
+}
+
+/// Only returns `Ok` once nothing is listening for votes any more.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_chat_until_dropped(
+    channel: &str,
+    vote_sender: &mpsc::Sender<ChatVote>,
+) -> std::io::Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+
+    let stream = std::net::TcpStream::connect(TWITCH_IRC_ADDRESS)?;
+    let mut writer = stream.try_clone()?;
+    // Twitch lets `justinfan` nicknames read chat without logging in.
+    write!(
+        writer,
+        "NICK justinfan{}\r\nJOIN #{}\r\n",
+        rand::rng().random_range(10_000..100_000),
+        channel.to_lowercase(),
+    )?;
+    info!("reading #{channel}'s chat");
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if let Some(server) = line.strip_prefix("PING ") {
+            write!(writer, "PONG {server}\r\n")?;
+        } else if parse_vote(&line).is_some_and(|vote| vote_sender.send(vote).is_err()) {
+            return Ok(());
+        }
+    }
+    Err(std::io::ErrorKind::UnexpectedEof.into())
+}
+
+/// Votes sent in between runs don't count towards the first one.
+fn open_voting(
+    mut commands: Commands,
+    connection: Res<ChatConnection>,
+    mut chat_votes: ResMut<ChatVotes>,
+) {
+    if let Ok(vote_receiver) = connection.0.lock() {
+        vote_receiver.try_iter().for_each(drop);
+    }
+    chat_votes.votes.clear();
+
+    commands.spawn((
+        VoteTally,
+        InGameEntity,
+        Text::default(),
+        TextFont {
+            font_size: 20.,
+            ..default()
+        },
+        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        KeepInSafeArea,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(60.),
+            width: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+    ));
+}
+
+fn tally_votes(
+    connection: Res<ChatConnection>,
+    mut chat_votes: ResMut<ChatVotes>,
+    game_timers: Res<GameTimers>,
+    mut won_writer: EventWriter<ChatEventWon>,
+) {
+    if let Ok(vote_receiver) = connection.0.lock() {
+        for ChatVote { viewer, event } in vote_receiver.try_iter() {
+            chat_votes.votes.insert(viewer, event);
+        }
+    }
+
+    if !game_timers.just_finished(VOTE_TIMER) {
+        return;
+    }
+    let winner = ChatEvent::ALL
+        .into_iter()
+        .map(|event| (event, chat_votes.count(event)))
+        .max_by_key(|(_, count)| *count);
+    chat_votes.votes.clear();
+
+    if let Some((event, count)) = winner.filter(|(_, count)| *count >= MIN_VOTES) {
+        info!("chat voted for {} with {count} votes", event.name());
+        won_writer.send(ChatEventWon(event));
+    }
+}
+
+/// Chat events don't draw from the run's seeded rng, so they don't change
+/// what the seed spawns.
+#[allow(clippy::too_many_arguments)]
+fn run_chat_events(
+    mut commands: Commands,
+    mut won_reader: EventReader<ChatEventWon>,
+    player_query: Query<(&Numbered, &Transform), With<Player>>,
+    direction: Res<GravityDirection>,
+    config: Res<GameConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut gravity_event_writer: EventWriter<TriggerGravityEvent>,
+    mut modifier_writer: EventWriter<RunModifier>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    let rng = &mut rand::rng();
+    for ChatEventWon(event) in won_reader.read() {
+        match event {
+            // Falls in from the side gravity pulls away from.
+            ChatEvent::BallRain => {
+                let bound = if direction.0.y < 0. {
+                    Bound::UpperBound
+                } else {
+                    Bound::LowerBound
+                };
+                for _ in 0..BALL_RAIN_COUNT {
+                    spawn_numbered_ball(
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        rng.random_range(SWARM_NUMBERS),
+                        random_point_on_bound(bound, rng),
+                        *direction.0 * config.ball_speed * rng.random_range(0.8..1.6),
+                    );
+                }
+            }
+            ChatEvent::ZeroGravity => {
+                gravity_event_writer.send(TriggerGravityEvent { strength: 0. });
+            }
+            // Just small enough to eat, heading for the player.
+            ChatEvent::GoldenBall => {
+                let Ok((Numbered(player_number), player_transform)) = player_query.get_single()
+                else {
+                    continue;
+                };
+                let start = random_point_on_bound(Bound::random(rng), rng);
+                let heading =
+                    (player_transform.translation.truncate() - start).normalize_or(Vec2::NEG_Y);
+                let ball = spawn_numbered_ball(
+                    &mut commands,
+                    &mut meshes,
+                    &mut materials,
+                    (player_number - 1).max(1),
+                    start,
+                    heading * GOLDEN_BALL_SPEED,
+                );
+                commands
+                    .entity(ball)
+                    .insert(MeshMaterial2d(materials.add(GOLDEN_GLOW_COLOR)));
+            }
+        }
+        modifier_writer.send(RunModifier(format!("chat: {}", event.name())));
+        toast_writer.send(ShowToast(format!("Chat voted for {}!", event.name())));
+    }
+}
+
+/// Like `!rain 4 · !zerog 1 · !golden 0 - 12s`.
+fn update_vote_tally(
+    chat_votes: Res<ChatVotes>,
+    game_timers: Res<GameTimers>,
+    mut tally_query: Query<&mut Text, With<VoteTally>>,
+) {
+    let counts: Vec<String> = ChatEvent::ALL
+        .into_iter()
+        .map(|event| format!("{} {}", event.command(), chat_votes.count(event)))
+        .collect();
+    let remaining = game_timers.remaining_secs(VOTE_TIMER).ceil();
+
+    for mut text in tally_query.iter_mut() {
+        text.0 = format!("{} - {remaining}s", counts.join(" · "));
+    }
+}
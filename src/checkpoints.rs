@@ -0,0 +1,95 @@
+use crate::{
+    GameState, InGameEntity, InGameSet, Numbered, Player, config::GameConfig,
+    safe_area::KeepInSafeArea,
+};
+use bevy::prelude::*;
+
+/// With [`GameConfig::checkpoints`] set, reaching each of those numbers
+/// raises a floor for the rest of the run: a hit from a bigger ball drops the
+/// player back to the floor instead of ending the run, unless they're already
+/// down at it.
+pub struct CheckpointPlugin;
+
+/// The highest checkpoint reached this run, if any.
+#[derive(Resource, Default)]
+pub struct CheckpointFloor(pub Option<i32>);
+
+#[derive(Component)]
+struct CheckpointIndicator;
+
+impl Plugin for CheckpointPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CheckpointFloor>()
+            .add_systems(OnEnter(GameState::InGame), start_checkpoints)
+            .add_systems(
+                Update,
+                (raise_checkpoint_floor, update_checkpoint_indicator)
+                    .chain()
+                    .in_set(InGameSet),
+            );
+    }
+}
+
+fn start_checkpoints(
+    mut commands: Commands,
+    mut floor: ResMut<CheckpointFloor>,
+    config: Res<GameConfig>,
+) {
+    floor.0 = None;
+    if config.checkpoints.is_empty() {
+        return;
+    }
+
+    commands.spawn((
+        CheckpointIndicator,
+        InGameEntity,
+        Text::default(),
+        TextFont {
+            font_size: 20.,
+            ..default()
+        },
+        KeepInSafeArea,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(90.),
+            left: Val::Px(20.),
+            ..default()
+        },
+    ));
+}
+
+fn raise_checkpoint_floor(
+    player_query: Query<&Numbered, (With<Player>, Changed<Numbered>)>,
+    mut floor: ResMut<CheckpointFloor>,
+    config: Res<GameConfig>,
+) {
+    let Ok(Numbered(player_number)) = player_query.get_single() else {
+        return;
+    };
+
+    let reached = config
+        .checkpoints
+        .iter()
+        .copied()
+        .filter(|checkpoint| player_number >= checkpoint)
+        .max();
+    if reached > floor.0 {
+        floor.0 = reached;
+    }
+}
+
+fn update_checkpoint_indicator(
+    floor: Res<CheckpointFloor>,
+    mut indicator_query: Query<&mut Text, With<CheckpointIndicator>>,
+) {
+    if !floor.is_changed() {
+        return;
+    }
+
+    for mut text in indicator_query.iter_mut() {
+        text.0 = match floor.0 {
+            Some(floor) => format!("floor {floor}"),
+            None => "no floor yet".to_string(),
+        };
+    }
+}
@@ -0,0 +1,75 @@
+use crate::profiles;
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: shape-eater [--seed <u64>] [--config <file.ron>] [--profile <name>] [--mode <name>] [--windowed] [--validate] [--scenario <file.ron>]";
+
+/// Options given on the command line, which take precedence over anything
+/// picked in game so scripted runs can start straight into a configured game.
+#[derive(Default)]
+pub struct LaunchOptions {
+    pub seed: Option<u64>,
+    pub config: Option<PathBuf>,
+    /// See [`profiles::is_valid_name`].
+    pub profile: Option<String>,
+    /// The name of a game mode to play in, checked once they're all
+    /// registered, see [`crate::modes`].
+    pub mode: Option<String>,
+    pub windowed: bool,
+    /// Check the game's content and the config instead of playing, see
+    /// [`crate::validate`].
+    pub validate: bool,
+    /// A scripted run to play without a window, see [`crate::scenario`].
+    pub scenario: Option<PathBuf>,
+}
+
+impl LaunchOptions {
+    /// Parses the process arguments, exiting with the usage text on bad input.
+    pub fn from_env() -> Self {
+        match Self::parse(std::env::args().skip(1)) {
+            Ok(options) => options,
+            Err(error) => {
+                eprintln!("{error}\n{USAGE}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = LaunchOptions::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let seed = args.next().ok_or("--seed needs a value")?;
+                    options.seed =
+                        Some(seed.parse().map_err(|_| format!("invalid seed `{seed}`"))?);
+                }
+                "--config" => {
+                    options.config = Some(args.next().ok_or("--config needs a path")?.into());
+                }
+                "--profile" => {
+                    let profile = args.next().ok_or("--profile needs a name")?;
+                    if !profiles::is_valid_name(&profile) {
+                        return Err(format!("invalid profile name `{profile}`"));
+                    }
+                    options.profile = Some(profile);
+                }
+                "--mode" => {
+                    options.mode = Some(args.next().ok_or("--mode needs a name")?);
+                }
+                "--windowed" => options.windowed = true,
+                "--validate" => options.validate = true,
+                "--scenario" => {
+                    options.scenario = Some(args.next().ok_or("--scenario needs a path")?.into());
+                }
+                "--help" | "-h" => {
+                    println!("{USAGE}");
+                    std::process::exit(0);
+                }
+                _ => return Err(format!("unknown argument `{arg}`")),
+            }
+        }
+
+        Ok(options)
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{
+    STARTING_NUMBER, arena::ArenaKind, new_game_plus::NewGamePlusProfile,
+    number_words::NumberLanguage, transitions::TransitionKind,
+};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Gameplay tuning values, overridable with a RON file passed via `--config`.
+/// Any field left out of the file keeps its default.
+#[derive(Resource, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub starting_number: i32,
+    pub ball_spawn_interval: f32,
+    pub ball_speed: f32,
+    /// Chance that a spawn is a swarm of tiny balls instead of a single ball.
+    pub swarm_chance: f64,
+    /// The biggest the player can grow. Balls eaten past it still score.
+    pub max_number: Option<i32>,
+    /// Reaching this number wins the run.
+    pub victory_number: Option<i32>,
+    /// Shedding down to this number loses the run.
+    pub lose_number: Option<i32>,
+    /// Numbers that, once reached, become a floor a hit knocks the player
+    /// back to instead of ending the run, e.g. `[25, 50, 100]`.
+    pub checkpoints: Vec<i32>,
+    /// How many balls bigger than the player can be alive before the
+    /// overwhelm siren goes off.
+    pub siren_threshold: usize,
+    /// Only balls this close to the player count towards `siren_threshold`.
+    pub siren_radius: Option<f32>,
+    pub siren_sound: bool,
+    pub reduced_motion: bool,
+    /// Ball labels smaller than this on screen are hidden.
+    pub label_min_font_size: f32,
+    /// Ball labels further than this from the player are hidden.
+    pub label_max_distance: Option<f32>,
+    /// Spells out numbers below `number_words_below` in this language, e.g.
+    /// `number_words: Some(French)`.
+    pub number_words: Option<NumberLanguage>,
+    pub number_words_below: i32,
+    /// Ticks toward the nearest ball bigger than the player, pitched by how
+    /// much bigger it is.
+    pub threat_sonar: bool,
+    /// Gamepad rumble, and device vibration in web builds.
+    pub haptics: bool,
+    /// Applies movement keys the frame they're pressed instead of the next,
+    /// and turns off pipelined rendering, for players who feel the lag.
+    pub low_latency_input: bool,
+    /// A millisecond timer from the first input to the victory number, with
+    /// splits along the way.
+    pub speedrun_timer: bool,
+    /// Balls spawn spinning, and the spin curves their paths.
+    pub ball_spin: bool,
+    /// A fading trail behind every ball, green while it can be eaten and red
+    /// while it's bigger than the player.
+    pub ball_trails: bool,
+    /// Fades the run timer and other HUD stats back while nothing bigger is
+    /// near the player.
+    pub adaptive_hud: bool,
+    /// A Twitch channel whose chat can vote for events during runs, e.g.
+    /// `chat_channel: Some("shape_eater")`.
+    pub chat_channel: Option<String>,
+    /// A program run with the path of every bug report saved with F8, e.g.
+    /// `bug_report_hook: Some("./upload-report.sh")`.
+    pub bug_report_hook: Option<String>,
+    /// The sound bank the game's sounds come from, relative to the assets
+    /// folder. See [`crate::sound_bank`].
+    pub sound_bank: String,
+    /// Splits runs into waves this many seconds long, with a shop between
+    /// them selling help for coins dropped by eaten balls. See
+    /// [`crate::shop`].
+    pub shop_wave_length: Option<f32>,
+    /// Lays an egg on a random wall this often, in seconds, which hatches
+    /// into a ball unless the player breaks it first. See [`crate::eggs`].
+    pub egg_interval: Option<f32>,
+    pub arena: ArenaKind,
+    pub new_game_plus: NewGamePlusProfile,
+    /// How the screen changes between the menu, a run and the death screen.
+    pub transition: TransitionKind,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            starting_number: STARTING_NUMBER,
+            ball_spawn_interval: 0.5,
+            ball_speed: 100.,
+            swarm_chance: 0.03,
+            max_number: Some(500),
+            victory_number: Some(500),
+            lose_number: None,
+            checkpoints: Vec::new(),
+            siren_threshold: 8,
+            siren_radius: None,
+            siren_sound: true,
+            reduced_motion: false,
+            label_min_font_size: 6.,
+            label_max_distance: None,
+            number_words: None,
+            number_words_below: 20,
+            threat_sonar: false,
+            haptics: true,
+            low_latency_input: false,
+            speedrun_timer: false,
+            ball_spin: false,
+            ball_trails: false,
+            adaptive_hud: true,
+            chat_channel: None,
+            bug_report_hook: None,
+            sound_bank: "sounds/default.sounds.ron".to_string(),
+            shop_wave_length: None,
+            egg_interval: None,
+            arena: ArenaKind::Classic,
+            new_game_plus: NewGamePlusProfile::default(),
+            transition: TransitionKind::Fade,
+        }
+    }
+}
+
+impl GameConfig {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| format!("couldn't read {}: {error}", path.display()))?;
+
+        ron::from_str(&contents)
+            .map_err(|error| format!("invalid config {}: {error}", path.display()))
+    }
+
+    /// Everything that parses but can't work, for `--validate`.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let starting_number = self.starting_number;
+
+        if starting_number < 1 {
+            problems.push("starting_number has to be at least 1".to_string());
+        }
+        if self.ball_spawn_interval <= 0. {
+            problems.push("ball_spawn_interval has to be above 0".to_string());
+        }
+        if self
+            .shop_wave_length
+            .is_some_and(|wave_length| wave_length <= 0.)
+        {
+            problems.push("shop_wave_length has to be above 0".to_string());
+        }
+        if self.egg_interval.is_some_and(|interval| interval <= 0.) {
+            problems.push("egg_interval has to be above 0".to_string());
+        }
+        if self.siren_radius.is_some_and(|radius| radius <= 0.) {
+            problems.push("siren_radius has to be above 0".to_string());
+        }
+        if !(0. ..=1.).contains(&self.swarm_chance) {
+            problems.push("swarm_chance has to be between 0 and 1".to_string());
+        }
+        if self
+            .max_number
+            .is_some_and(|max_number| max_number < starting_number)
+        {
+            problems.push("max_number is below starting_number".to_string());
+        }
+        if let Some(victory_number) = self.victory_number {
+            if victory_number <= starting_number {
+                problems.push("victory_number is won from the start".to_string());
+            }
+            if self
+                .max_number
+                .is_some_and(|max_number| victory_number > max_number)
+            {
+                problems
+                    .push("victory_number is above max_number, so can't be reached".to_string());
+            }
+        }
+        if self
+            .lose_number
+            .is_some_and(|lose_number| lose_number >= starting_number)
+        {
+            problems.push("lose_number is lost from the start".to_string());
+        }
+        if !self.checkpoints.is_sorted_by(|a, b| a < b) {
+            problems.push("checkpoints have to go up".to_string());
+        }
+        if self
+            .checkpoints
+            .first()
+            .is_some_and(|checkpoint| *checkpoint <= starting_number)
+        {
+            problems.push("the first checkpoint is reached from the start".to_string());
+        }
+
+        problems
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    GameState, RunState,
+    menu::spawn_menu_button,
+    pause::PauseReason,
+    prompts::{MenuAction, MenuInput, Prompt},
+};
+use bevy::{input::InputSystem, prelude::*, ui::FocusPolicy, window::WindowCloseRequested};
+
+/// Closing the window, or pressing Ctrl+Q, in the middle of a run pauses it
+/// and asks before quitting, so a run isn't thrown away by a stray click.
+/// Anywhere else the game quits straight away.
+pub struct ConfirmQuitPlugin;
+
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ConfirmQuitState {
+    #[default]
+    Closed,
+    Open,
+}
+
+#[derive(Component, Clone, Copy)]
+enum ConfirmQuitButton {
+    Quit,
+    Cancel,
+}
+
+impl Plugin for ConfirmQuitPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<ConfirmQuitState>()
+            .enable_state_scoped_entities::<ConfirmQuitState>()
+            .add_systems(OnEnter(ConfirmQuitState::Open), setup_confirm_quit_dialog)
+            // Ahead of everything else in the frame, so Ctrl+Q doesn't also count
+            // as a plain Q press.
+            .add_systems(
+                PreUpdate,
+                request_quit.after(InputSystem).run_if(confirm_quit_closed),
+            )
+            .add_systems(
+                Update,
+                choose_confirm_quit_option.run_if(in_state(ConfirmQuitState::Open)),
+            );
+    }
+}
+
+/// Run condition for screens the dialog can open over, so they don't also
+/// react to its input.
+pub fn confirm_quit_closed(confirm_quit_state: Res<State<ConfirmQuitState>>) -> bool {
+    *confirm_quit_state.get() == ConfirmQuitState::Closed
+}
+
+/// Swallows the Q of Ctrl+Q.
+fn take_quit_key(keys: &mut ButtonInput<KeyCode>) -> bool {
+    keys.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]) && keys.clear_just_pressed(KeyCode::KeyQ)
+}
+
+fn request_quit(
+    mut close_requests: EventReader<WindowCloseRequested>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    game_state: Res<State<GameState>>,
+    mut pause_reason: ResMut<PauseReason>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+    mut next_confirm_quit_state: ResMut<NextState<ConfirmQuitState>>,
+    mut exit_writer: EventWriter<AppExit>,
+) {
+    let close_requested = close_requests.read().count() > 0;
+    if !take_quit_key(&mut keys) && !close_requested {
+        return;
+    }
+
+    if *game_state.get() == GameState::InGame {
+        *pause_reason = PauseReason::Requested;
+        next_run_state.set(RunState::Paused);
+        next_confirm_quit_state.set(ConfirmQuitState::Open);
+    } else {
+        exit_writer.send(AppExit::Success);
+    }
+}
+
+fn choose_confirm_quit_option(
+    button_query: Query<(&Interaction, &ConfirmQuitButton), Changed<Interaction>>,
+    menu_input: MenuInput,
+    mut next_confirm_quit_state: ResMut<NextState<ConfirmQuitState>>,
+    mut exit_writer: EventWriter<AppExit>,
+) {
+    let clicked = button_query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| *button);
+    let chosen = if menu_input.just_pressed(MenuAction::QuitRun) {
+        Some(ConfirmQuitButton::Quit)
+    } else if menu_input.just_pressed(MenuAction::Quit) {
+        Some(ConfirmQuitButton::Cancel)
+    } else {
+        clicked
+    };
+
+    match chosen {
+        Some(ConfirmQuitButton::Quit) => {
+            exit_writer.send(AppExit::Success);
+        }
+        Some(ConfirmQuitButton::Cancel) => next_confirm_quit_state.set(ConfirmQuitState::Closed),
+        None => {}
+    }
+}
+
+fn setup_confirm_quit_dialog(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(20.),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0., 0., 0., 0.8)),
+            FocusPolicy::Block,
+            GlobalZIndex(10),
+            StateScoped(ConfirmQuitState::Open),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new("Quit the game?"),
+                TextFont {
+                    font_size: 60.,
+                    ..default()
+                },
+            ));
+            builder.spawn((
+                Text::new("this run will be lost"),
+                TextFont {
+                    font_size: 24.,
+                    ..default()
+                },
+            ));
+            spawn_menu_button(builder, ConfirmQuitButton::Quit, "Quit");
+            spawn_menu_button(builder, ConfirmQuitButton::Cancel, "Cancel");
+            builder.spawn((
+                Text::default(),
+                Prompt(vec![
+                    (MenuAction::QuitRun, "quit"),
+                    (MenuAction::Quit, "cancel"),
+                ]),
+                TextFont {
+                    font_size: 20.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
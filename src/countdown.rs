@@ -0,0 +1,67 @@
+use crate::RunState;
+use bevy::prelude::*;
+
+const COUNTDOWN_SECONDS: f32 = 3.;
+
+/// Every run opens with a 3-2-1 countdown. The player can already move into
+/// position, but nothing spawns, no gameplay timers tick and nothing can be
+/// eaten or hit until it's over.
+pub struct CountdownPlugin;
+
+#[derive(Component)]
+struct CountdownText;
+
+#[derive(Resource, Default)]
+struct CountdownElapsed(f32);
+
+impl Plugin for CountdownPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CountdownElapsed>()
+            .add_systems(OnEnter(RunState::Countdown), start_countdown)
+            .add_systems(Update, count_down.run_if(in_state(RunState::Countdown)));
+    }
+}
+
+fn start_countdown(mut commands: Commands, mut elapsed: ResMut<CountdownElapsed>) {
+    elapsed.0 = 0.;
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            StateScoped(RunState::Countdown),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                CountdownText,
+                Text::new(format!("{COUNTDOWN_SECONDS}")),
+                TextFont {
+                    font_size: 160.,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn count_down(
+    mut elapsed: ResMut<CountdownElapsed>,
+    mut text_query: Query<&mut Text, With<CountdownText>>,
+    time: Res<Time>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+) {
+    elapsed.0 += time.delta_secs();
+    if elapsed.0 >= COUNTDOWN_SECONDS {
+        next_run_state.set(RunState::Playing);
+        return;
+    }
+
+    let remaining = (COUNTDOWN_SECONDS - elapsed.0).ceil();
+    for mut text in text_query.iter_mut() {
+        text.0 = format!("{remaining}");
+    }
+}
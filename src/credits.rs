@@ -0,0 +1,132 @@
+use crate::{
+    GameState,
+    menu::spawn_menu_button,
+    prompts::{MenuAction, MenuInput, Prompt},
+};
+use bevy::prelude::*;
+
+/// Lines starting with `#` are headings.
+const CREDITS: &str = include_str!("../assets/credits.txt");
+/// In px/s.
+const SCROLL_SPEED: f32 = 40.;
+const VIEW_HEIGHT: f32 = 500.;
+
+/// Who and what the game was made with, scrolling up the screen on a loop,
+/// reached from the main menu.
+pub struct CreditsPlugin;
+
+/// The column of credits, moved up through its clipped view.
+#[derive(Component)]
+struct CreditsScroll {
+    offset: f32,
+}
+
+#[derive(Component)]
+struct CreditsBackButton;
+
+impl Plugin for CreditsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Credits), setup_credits_screen)
+            .add_systems(
+                Update,
+                (scroll_credits, leave_credits).run_if(in_state(GameState::Credits)),
+            );
+    }
+}
+
+fn setup_credits_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(20.),
+                ..default()
+            },
+            StateScoped(GameState::Credits),
+        ))
+        .with_children(|builder| {
+            builder
+                .spawn(Node {
+                    width: Val::Percent(100.),
+                    height: Val::Px(VIEW_HEIGHT),
+                    justify_content: JustifyContent::Center,
+                    overflow: Overflow::clip(),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder
+                        .spawn((
+                            CreditsScroll {
+                                offset: VIEW_HEIGHT,
+                            },
+                            Node {
+                                position_type: PositionType::Absolute,
+                                top: Val::Px(VIEW_HEIGHT),
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                row_gap: Val::Px(10.),
+                                ..default()
+                            },
+                        ))
+                        .with_children(|builder| {
+                            for line in CREDITS.lines() {
+                                let (text, font_size) = match line.strip_prefix('#') {
+                                    Some(heading) => (heading.trim(), 44.),
+                                    None => (line.trim(), 28.),
+                                };
+                                builder.spawn((
+                                    Text::new(text),
+                                    TextFont {
+                                        font_size,
+                                        ..default()
+                                    },
+                                ));
+                            }
+                        });
+                });
+            spawn_menu_button(builder, CreditsBackButton, "Back");
+            builder.spawn((
+                Text::default(),
+                Prompt(vec![(MenuAction::Quit, "back")]),
+                TextFont {
+                    font_size: 20.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+/// Once the last line has gone off the top, the credits start again from the
+/// bottom.
+fn scroll_credits(
+    mut scroll_query: Query<(&mut CreditsScroll, &mut Node, &ComputedNode)>,
+    time: Res<Time>,
+) {
+    for (mut scroll, mut node, computed) in scroll_query.iter_mut() {
+        scroll.offset -= SCROLL_SPEED * time.delta_secs();
+        let height = computed.size().y * computed.inverse_scale_factor();
+        if scroll.offset < -height {
+            scroll.offset = VIEW_HEIGHT;
+        }
+        node.top = Val::Px(scroll.offset);
+    }
+}
+
+fn leave_credits(
+    button_query: Query<&Interaction, (Changed<Interaction>, With<CreditsBackButton>)>,
+    menu_input: MenuInput,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if menu_input.just_pressed(MenuAction::Quit)
+        || button_query
+            .iter()
+            .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        next_game_state.set(GameState::Menu);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{
+    GameState, PLAYER_COLOR, RunState,
+    settings::{CursorMode, Settings},
+};
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+    window::PrimaryWindow,
+    winit::cursor::{CursorIcon, CustomCursor},
+};
+
+/// In px, square.
+const CURSOR_SIZE: u32 = 16;
+const CURSOR_BORDER: u32 = 2;
+
+/// Menus get a cursor in the player's colour, and the cursor is hidden while
+/// a run is being played, coming back whenever it's paused or over. Both can
+/// be turned off in the settings.
+pub struct CursorPlugin;
+
+#[derive(Resource)]
+struct ThemedCursor(Handle<Image>);
+
+impl Plugin for CursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, create_themed_cursor).add_systems(
+            Update,
+            update_cursor.run_if(
+                resource_changed::<Settings>
+                    .or(state_changed::<GameState>)
+                    .or(state_changed::<RunState>),
+            ),
+        );
+    }
+}
+
+/// A little copy of the player, with the point in the middle.
+fn create_themed_cursor(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let fill = PLAYER_COLOR.to_srgba().to_u8_array();
+    let border = Color::WHITE.to_srgba().to_u8_array();
+    let data = (0..CURSOR_SIZE * CURSOR_SIZE)
+        .flat_map(|index| {
+            let (x, y) = (index % CURSOR_SIZE, index / CURSOR_SIZE);
+            let edge = CURSOR_SIZE - CURSOR_BORDER;
+            if x < CURSOR_BORDER || y < CURSOR_BORDER || x >= edge || y >= edge {
+                border
+            } else {
+                fill
+            }
+        })
+        .collect();
+
+    let image = Image::new(
+        Extent3d {
+            width: CURSOR_SIZE,
+            height: CURSOR_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    commands.insert_resource(ThemedCursor(images.add(image)));
+}
+
+fn update_cursor(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    run_state: Option<Res<State<RunState>>>,
+    themed_cursor: Res<ThemedCursor>,
+    mut window_query: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+) {
+    let playing = run_state.is_some_and(|run_state| {
+        matches!(run_state.get(), RunState::Playing | RunState::Countdown)
+    });
+    let hidden = playing && settings.cursor != CursorMode::AlwaysShown;
+    let icon = match settings.cursor {
+        CursorMode::Themed => CursorIcon::Custom(CustomCursor::Image {
+            handle: themed_cursor.0.clone(),
+            hotspot: ((CURSOR_SIZE / 2) as u16, (CURSOR_SIZE / 2) as u16),
+        }),
+        CursorMode::System | CursorMode::AlwaysShown => CursorIcon::default(),
+    };
+
+    for (window_entity, mut window) in window_query.iter_mut() {
+        window.cursor_options.visible = !hidden;
+        commands.entity(window_entity).insert(icon.clone());
+    }
+}
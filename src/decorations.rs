@@ -0,0 +1,149 @@
+use crate::{Bound, GameState, InGameEntity, InGameSet, config::GameConfig, layers::RenderLayer};
+use bevy::prelude::*;
+
+/// There's no music track to follow yet, so the beat runs at a steady tempo.
+const TEMPO_BPM: f32 = 120.;
+const GRID_SPACING: f32 = 100.;
+const GRID_LINE_WIDTH: f32 = 2.;
+const RING_RADII: [f32; 3] = [150., 300., 450.];
+const RING_WIDTH: f32 = 4.;
+/// How much bigger a ring gets on the beat.
+const RING_PULSE_SCALE: f32 = 0.06;
+/// Each ring pulses this many beats after the one inside it, so the pulse
+/// ripples outwards.
+const RING_RIPPLE_DELAY: f32 = 0.15;
+
+/// Faint grid lines and rings behind the arena that pulse on the beat of a
+/// [`BeatClock`]. With reduced motion on they stay put and only the grid
+/// brightens a little on each beat.
+pub struct ArenaDecorationPlugin;
+
+/// Counts beats through a run, for anything that wants to move in time with
+/// the music.
+#[derive(Resource)]
+pub struct BeatClock {
+    pub bpm: f32,
+    /// In beats since the run started.
+    elapsed: f32,
+}
+
+#[derive(Resource)]
+struct DecorationMaterials {
+    grid: Handle<ColorMaterial>,
+    ring: Handle<ColorMaterial>,
+}
+
+#[derive(Component)]
+struct DecorationRing {
+    /// In beats.
+    delay: f32,
+}
+
+impl Plugin for ArenaDecorationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BeatClock {
+            bpm: TEMPO_BPM,
+            elapsed: 0.,
+        })
+        .add_systems(OnEnter(GameState::InGame), spawn_decorations)
+        .add_systems(
+            Update,
+            (tick_beat_clock, pulse_decorations)
+                .chain()
+                .in_set(InGameSet),
+        );
+    }
+}
+
+impl BeatClock {
+    /// 1 right on a beat, fading to 0 by the next one. `delay` is in beats.
+    pub fn pulse(&self, delay: f32) -> f32 {
+        let since_beat = (self.elapsed - delay).rem_euclid(1.);
+        (1. - since_beat).powi(3)
+    }
+}
+
+fn spawn_decorations(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut beat_clock: ResMut<BeatClock>,
+) {
+    beat_clock.elapsed = 0.;
+
+    let grid_material = materials.add(Color::srgba(1., 1., 1., 0.03));
+    let ring_material = materials.add(Color::srgba(1., 1., 1., 0.05));
+    let arena = Bound::arena();
+
+    let vertical_line = meshes.add(Rectangle::new(GRID_LINE_WIDTH, arena.height()));
+    let mut x = (arena.min.x / GRID_SPACING).ceil() * GRID_SPACING;
+    while x <= arena.max.x {
+        commands.spawn((
+            InGameEntity,
+            Mesh2d(vertical_line.clone()),
+            MeshMaterial2d(grid_material.clone()),
+            Transform::from_translation(RenderLayer::Decorations.at(Vec2::new(x, 0.))),
+        ));
+        x += GRID_SPACING;
+    }
+
+    let horizontal_line = meshes.add(Rectangle::new(arena.width(), GRID_LINE_WIDTH));
+    let mut y = (arena.min.y / GRID_SPACING).ceil() * GRID_SPACING;
+    while y <= arena.max.y {
+        commands.spawn((
+            InGameEntity,
+            Mesh2d(horizontal_line.clone()),
+            MeshMaterial2d(grid_material.clone()),
+            Transform::from_translation(RenderLayer::Decorations.at(Vec2::new(0., y))),
+        ));
+        y += GRID_SPACING;
+    }
+
+    for (index, radius) in RING_RADII.into_iter().enumerate() {
+        commands.spawn((
+            DecorationRing {
+                delay: index as f32 * RING_RIPPLE_DELAY,
+            },
+            InGameEntity,
+            Mesh2d(meshes.add(Annulus::new(
+                radius - RING_WIDTH / 2.,
+                radius + RING_WIDTH / 2.,
+            ))),
+            MeshMaterial2d(ring_material.clone()),
+            Transform::from_translation(RenderLayer::Decorations.at(Vec2::ZERO)),
+        ));
+    }
+
+    commands.insert_resource(DecorationMaterials {
+        grid: grid_material,
+        ring: ring_material,
+    });
+}
+
+fn tick_beat_clock(mut beat_clock: ResMut<BeatClock>, time: Res<Time>) {
+    beat_clock.elapsed += time.delta_secs() * beat_clock.bpm / 60.;
+}
+
+fn pulse_decorations(
+    beat_clock: Res<BeatClock>,
+    config: Res<GameConfig>,
+    decoration_materials: Res<DecorationMaterials>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut ring_query: Query<(&DecorationRing, &mut Transform)>,
+) {
+    let pulse = beat_clock.pulse(0.);
+    if let Some(grid) = materials.get_mut(&decoration_materials.grid) {
+        grid.color.set_alpha(0.03 + 0.03 * pulse);
+    }
+    if config.reduced_motion {
+        return;
+    }
+
+    if let Some(ring) = materials.get_mut(&decoration_materials.ring) {
+        ring.color.set_alpha(0.05 + 0.1 * pulse);
+    }
+    for (ring, mut transform) in ring_query.iter_mut() {
+        let scale = 1. + RING_PULSE_SCALE * beat_clock.pulse(ring.delay);
+        transform.scale = Vec3::new(scale, scale, 1.);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    Ball, GameState, InGameEntity, MovementAction, Numbered, Player, PlayerControlSet,
+    prompts::AnyInput, safe_area::KeepInSafeArea, settings::settings_closed,
+    text_entry::no_text_entry,
+};
+use bevy::prelude::*;
+
+/// How long the main menu sits untouched before the demo starts.
+const MENU_IDLE_TIMEOUT: f32 = 15.;
+/// Bigger balls closer than this, sideways, are run away from.
+const DANGER_DISTANCE: f32 = 200.;
+/// Close enough to a ball's column to stop and wait for it.
+const CHASE_TOLERANCE: f32 = 10.;
+
+/// Leaving the main menu alone for a while starts a demo run that plays
+/// itself: a simple bot moves the player towards smaller balls and away from
+/// bigger ones. Any input goes back to the menu. Demo runs don't count for
+/// anything, so they don't touch scores, hints or unlocks.
+pub struct DemoPlugin;
+
+#[derive(Resource, Default)]
+pub struct Demo {
+    pub running: bool,
+    menu_idle_time: f32,
+}
+
+impl Plugin for DemoPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Demo>()
+            .add_systems(OnEnter(GameState::Menu), end_demo)
+            .add_systems(OnEnter(GameState::InGame), spawn_demo_banner)
+            .add_systems(
+                Update,
+                start_demo_when_idle.run_if(
+                    in_state(GameState::Menu)
+                        .and(settings_closed)
+                        .and(no_text_entry),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    drive_demo_player.in_set(PlayerControlSet),
+                    leave_demo_on_input,
+                )
+                    .run_if(in_state(GameState::InGame).and(in_demo)),
+            );
+    }
+}
+
+/// Run condition for a demo run being played.
+pub fn in_demo(demo: Res<Demo>) -> bool {
+    demo.running
+}
+
+/// Run condition for the player being in control, for everything a demo run
+/// shouldn't do or record.
+pub fn not_in_demo(demo: Res<Demo>) -> bool {
+    !demo.running
+}
+
+fn end_demo(mut demo: ResMut<Demo>) {
+    demo.running = false;
+    demo.menu_idle_time = 0.;
+}
+
+fn start_demo_when_idle(
+    mut any_input: AnyInput,
+    time: Res<Time>,
+    mut demo: ResMut<Demo>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if any_input.just_pressed() || any_input.mouse_moved() {
+        demo.menu_idle_time = 0.;
+        return;
+    }
+
+    demo.menu_idle_time += time.delta_secs();
+    if demo.menu_idle_time >= MENU_IDLE_TIMEOUT {
+        demo.running = true;
+        next_game_state.set(GameState::InGame);
+    }
+}
+
+fn spawn_demo_banner(mut commands: Commands, demo: Res<Demo>) {
+    if !demo.running {
+        return;
+    }
+
+    commands.spawn((
+        InGameEntity,
+        Text::new("demo - press any key"),
+        TextFont {
+            font_size: 30.,
+            ..default()
+        },
+        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        KeepInSafeArea,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(20.),
+            width: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+    ));
+}
+
+/// Runs from the nearest bigger ball if one is close, otherwise heads for the
+/// nearest smaller one.
+#[allow(clippy::type_complexity)]
+fn drive_demo_player(
+    player_query: Query<(&Numbered, &Transform), With<Player>>,
+    ball_query: Query<(&Numbered, &Transform), (With<Ball>, Without<Player>)>,
+    mut movement_event_writer: EventWriter<MovementAction>,
+) {
+    let Ok((Numbered(player_number), player_transform)) = player_query.get_single() else {
+        return;
+    };
+    let player_x = player_transform.translation.x;
+
+    let nearest = |bigger: bool| {
+        ball_query
+            .iter()
+            .filter(|(Numbered(number), _)| (number > player_number) == bigger)
+            .map(|(_, transform)| transform.translation.x - player_x)
+            .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+    };
+
+    let direction = match (nearest(true), nearest(false)) {
+        (Some(threat), _) if threat.abs() < DANGER_DISTANCE => -threat.signum(),
+        (_, Some(food)) if food.abs() > CHASE_TOLERANCE => food.signum(),
+        _ => return,
+    };
+    movement_event_writer.send(MovementAction(direction as i32));
+}
+
+fn leave_demo_on_input(mut any_input: AnyInput, mut next_game_state: ResMut<NextState<GameState>>) {
+    if any_input.just_pressed() || any_input.mouse_moved() {
+        next_game_state.set(GameState::Menu);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{
+    GameState,
+    menu::spawn_menu_button,
+    prompts::{MenuAction, MenuInput, Prompt},
+    storage::Storage,
+};
+use bevy::prelude::*;
+use rand::Rng;
+use std::{fmt, str::FromStr};
+
+const DIFFICULTY_KEY: &str = "difficulty";
+/// How much of the player an Easy hit takes off.
+const EASY_HIT_SHRINK: f32 = 0.25;
+
+/// Easy, Normal or Hard, picked on its own screen from the main menu and kept
+/// between sessions. It scales how often balls spawn and how fast they go,
+/// skews their numbers, and changes what a hit from a bigger ball costs.
+pub struct DifficultyPlugin;
+
+#[derive(Resource, Clone, Copy, Default, PartialEq)]
+pub enum Difficulty {
+    /// Hits shrink the player instead of ending the run.
+    Easy,
+    #[default]
+    Normal,
+    /// Checkpoint floors don't catch hits.
+    Hard,
+}
+
+#[derive(Component, Clone, Copy)]
+enum DifficultyButton {
+    Choose(Difficulty),
+    Back,
+}
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Difficulty>()
+            .add_systems(Startup, load_difficulty)
+            .add_systems(OnEnter(GameState::Difficulty), setup_difficulty_screen)
+            .add_systems(
+                Update,
+                (choose_difficulty, show_difficulty)
+                    .chain()
+                    .run_if(in_state(GameState::Difficulty)),
+            );
+    }
+}
+
+impl Difficulty {
+    const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Easy,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    pub fn spawn_interval_multiplier(self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.4,
+            Difficulty::Normal => 1.,
+            Difficulty::Hard => 0.7,
+        }
+    }
+
+    pub fn ball_speed_multiplier(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.8,
+            Difficulty::Normal => 1.,
+            Difficulty::Hard => 1.25,
+        }
+    }
+
+    /// From 1 to 99, skewed towards small numbers on Easy and big ones on
+    /// Hard. Normal draws them the same as before difficulties existed, so
+    /// old seeds still play the same.
+    pub fn ball_number(self, rng: &mut impl Rng) -> i32 {
+        let skew = match self {
+            Difficulty::Easy => 1.5,
+            Difficulty::Normal => return rng.random_range(1..100),
+            Difficulty::Hard => 0.7,
+        };
+        1 + (rng.random::<f32>().powf(skew) * 99.) as i32
+    }
+
+    /// What the player is down to after a hit that would otherwise end the
+    /// run, if it doesn't.
+    pub fn shrink_on_hit(self, player_number: i32) -> Option<i32> {
+        match self {
+            Difficulty::Easy => {
+                Some(((player_number as f32 * (1. - EASY_HIT_SHRINK)).round() as i32).max(1))
+            }
+            Difficulty::Normal | Difficulty::Hard => None,
+        }
+    }
+
+    pub fn checkpoints_catch_hits(self) -> bool {
+        self != Difficulty::Hard
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name() == s)
+            .ok_or(())
+    }
+}
+
+impl DifficultyButton {
+    fn label(self, current: Difficulty) -> String {
+        match self {
+            DifficultyButton::Choose(difficulty) if difficulty == current => {
+                format!("> {} <", difficulty.name())
+            }
+            DifficultyButton::Choose(difficulty) => difficulty.name().to_string(),
+            DifficultyButton::Back => "Back".to_string(),
+        }
+    }
+}
+
+fn load_difficulty(storage: Res<Storage>, mut difficulty: ResMut<Difficulty>) {
+    *difficulty = storage.load(DIFFICULTY_KEY).unwrap_or_default();
+}
+
+fn setup_difficulty_screen(mut commands: Commands, difficulty: Res<Difficulty>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(20.),
+                ..default()
+            },
+            StateScoped(GameState::Difficulty),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new("Difficulty"),
+                TextFont {
+                    font_size: 60.,
+                    ..default()
+                },
+            ));
+            for button in Difficulty::ALL
+                .map(DifficultyButton::Choose)
+                .into_iter()
+                .chain([DifficultyButton::Back])
+            {
+                spawn_menu_button(builder, button, &button.label(*difficulty));
+            }
+            builder.spawn((
+                Text::default(),
+                Prompt(vec![
+                    (MenuAction::Difficulty, "change"),
+                    (MenuAction::Quit, "back"),
+                ]),
+                TextFont {
+                    font_size: 20.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+/// Clicking a difficulty picks it and goes back to the menu.
+fn choose_difficulty(
+    button_query: Query<(&Interaction, &DifficultyButton), Changed<Interaction>>,
+    menu_input: MenuInput,
+    mut difficulty: ResMut<Difficulty>,
+    mut storage: ResMut<Storage>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    let clicked = button_query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| *button);
+
+    if menu_input.just_pressed(MenuAction::Difficulty) {
+        *difficulty = difficulty.next();
+    }
+    if let Some(DifficultyButton::Choose(chosen)) = clicked {
+        *difficulty = chosen;
+    }
+    if difficulty.is_changed() {
+        storage.save(DIFFICULTY_KEY, *difficulty);
+    }
+
+    if menu_input.just_pressed(MenuAction::Quit) || clicked.is_some() {
+        next_game_state.set(GameState::Menu);
+    }
+}
+
+fn show_difficulty(
+    difficulty: Res<Difficulty>,
+    button_query: Query<(&DifficultyButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !difficulty.is_changed() {
+        return;
+    }
+
+    for (button, children) in button_query.iter() {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.0 = button.label(*difficulty);
+            }
+        }
+    }
+}
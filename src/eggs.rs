@@ -0,0 +1,236 @@
+use crate::{
+    Ball, Bound, GameRng, GameState, InGameEntity, InGameSet, Numbered, Player, SIZE_FACTOR,
+    config::GameConfig,
+    difficulty::Difficulty,
+    governor::PerformanceGovernor,
+    layers::RenderLayer,
+    new_game_plus::NewGamePlus,
+    number_words::format_number,
+    random_point_on_bound, spawn_numbered_ball,
+    timers::{AppGameTimersExt, GameTimers},
+};
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// In seconds.
+const INCUBATION: f32 = 3.;
+const EGG_LAYING_TIMER: &str = "egg_laying";
+const EGG_SIZE: Vec2 = Vec2::new(36., 48.);
+/// Pale while freshly laid, the color of a ball by the time it hatches.
+const FRESH_EGG_COLOR: Color = Color::srgb(1., 0.9, 0.8);
+const HATCHING_EGG_COLOR: Color = Color::srgb(1., 0., 0.);
+/// How far, in radians, an egg about to hatch rocks either way.
+const MAX_WOBBLE: f32 = 0.3;
+const WOBBLE_SPEED: f32 = 20.;
+
+/// With [`GameConfig::egg_interval`] set, eggs appear along the arena's
+/// walls, each showing the number of the ball inside it. Eggs sit still and
+/// aren't solid, and after a few seconds of rocking harder and harder they
+/// hatch into a ball heading away from the wall. A player bigger than an egg's
+/// number can touch it to break it before it hatches. Eggs ready to hatch
+/// wait while the performance governor has no room for another ball.
+pub struct EggPlugin;
+
+#[derive(Component)]
+pub struct Egg {
+    pub number: i32,
+    incubation: Timer,
+    /// Which way the ball heads when it hatches.
+    pub facing: Vec2,
+    material: Handle<ColorMaterial>,
+}
+
+impl Plugin for EggPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_game_timer(
+            EGG_LAYING_TIMER,
+            Timer::from_seconds(1., TimerMode::Repeating),
+        )
+        .add_systems(OnEnter(GameState::InGame), start_egg_timer)
+        // Game timers tick on the fixed step.
+        .add_systems(FixedUpdate, lay_eggs.in_set(InGameSet).run_if(lays_eggs))
+        .add_systems(
+            Update,
+            (break_eggs, incubate_eggs)
+                .chain()
+                .in_set(InGameSet)
+                .run_if(lays_eggs),
+        );
+    }
+}
+
+fn lays_eggs(config: Res<GameConfig>) -> bool {
+    config.egg_interval.is_some()
+}
+
+fn start_egg_timer(mut game_timers: ResMut<GameTimers>, config: Res<GameConfig>) {
+    if let Some(interval) = config.egg_interval {
+        game_timers.restart(EGG_LAYING_TIMER, interval);
+    }
+}
+
+impl Egg {
+    /// How long it's been incubating, in seconds.
+    pub fn incubated(&self) -> f32 {
+        self.incubation.elapsed_secs()
+    }
+
+    pub fn set_incubated(&mut self, secs: f32) {
+        self.incubation.set_elapsed(Duration::from_secs_f32(secs));
+    }
+}
+
+/// The way into the arena from a bound.
+fn inward(bound: Bound) -> Vec2 {
+    match bound {
+        Bound::UpperBound => Vec2::NEG_Y,
+        Bound::LowerBound => Vec2::Y,
+        Bound::LeftBound => Vec2::X,
+        Bound::RightBound => Vec2::NEG_X,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn lay_eggs(
+    mut commands: Commands,
+    game_timers: Res<GameTimers>,
+    mut game_rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    new_game_plus: Res<NewGamePlus>,
+    difficulty: Res<Difficulty>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if !game_timers.just_finished(EGG_LAYING_TIMER) {
+        return;
+    }
+    let rng = &mut game_rng.rng;
+
+    let bound = Bound::random(rng);
+    let facing = inward(bound);
+    let arena = Bound::arena().inflate(-EGG_SIZE.max_element());
+    let position =
+        (random_point_on_bound(bound, rng) + facing * EGG_SIZE.y / 2.).clamp(arena.min, arena.max);
+    let number = new_game_plus.ball_number(difficulty.ball_number(rng), &config);
+
+    spawn_egg(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        &config,
+        number,
+        position,
+        facing,
+        0.,
+    );
+}
+
+/// An egg that's been incubating for `incubated` seconds.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_egg(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    config: &GameConfig,
+    number: i32,
+    position: Vec2,
+    facing: Vec2,
+    incubated: f32,
+) {
+    let material = materials.add(FRESH_EGG_COLOR);
+    let mut incubation = Timer::from_seconds(INCUBATION, TimerMode::Once);
+    incubation.set_elapsed(Duration::from_secs_f32(incubated));
+
+    commands
+        .spawn((
+            Egg {
+                number,
+                incubation,
+                facing,
+                material: material.clone(),
+            },
+            InGameEntity,
+            Mesh2d(meshes.add(Ellipse::new(EGG_SIZE.x / 2., EGG_SIZE.y / 2.))),
+            MeshMaterial2d(material),
+            Transform::from_translation(RenderLayer::Balls.at(position)),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text2d::new(format_number(number, config)),
+                TextFont {
+                    font_size: 18.,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+                Transform::from_xyz(0., 0., 1.),
+            ));
+        });
+}
+
+/// Eggs aren't solid, so a touch is the player's square overlapping them.
+#[allow(clippy::type_complexity)]
+fn break_eggs(
+    mut commands: Commands,
+    player_query: Query<(&Numbered, &Transform), With<Player>>,
+    egg_query: Query<(Entity, &Egg, &Transform), Without<Player>>,
+) {
+    let Ok((Numbered(player_number), player_transform)) = player_query.get_single() else {
+        return;
+    };
+    let player_position = player_transform.translation.truncate();
+    let reach = *player_number as f32 * SIZE_FACTOR / 2. + EGG_SIZE.x / 2.;
+
+    for (entity, egg, transform) in egg_query.iter() {
+        let offset = (transform.translation.truncate() - player_position).abs();
+        if egg.number < *player_number && offset.max_element() <= reach {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn incubate_eggs(
+    mut commands: Commands,
+    mut egg_query: Query<(Entity, &mut Egg, &mut Transform)>,
+    ball_query: Query<(), With<Ball>>,
+    governor: Res<PerformanceGovernor>,
+    config: Res<GameConfig>,
+    new_game_plus: Res<NewGamePlus>,
+    difficulty: Res<Difficulty>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
+) {
+    let ball_speed = new_game_plus.ball_speed(&config) * difficulty.ball_speed_multiplier();
+    let mut room = governor
+        .ball_cap()
+        .map(|cap| cap.saturating_sub(ball_query.iter().count()));
+
+    for (entity, mut egg, mut transform) in egg_query.iter_mut() {
+        egg.incubation.tick(time.delta());
+        if egg.incubation.finished() && room != Some(0) {
+            room = room.map(|room| room - 1);
+            commands.entity(entity).despawn_recursive();
+            spawn_numbered_ball(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                egg.number,
+                transform.translation.truncate(),
+                egg.facing * ball_speed,
+            );
+            continue;
+        }
+
+        let progress = egg.incubation.fraction();
+        if let Some(material) = materials.get_mut(&egg.material) {
+            material.color = FRESH_EGG_COLOR.mix(&HATCHING_EGG_COLOR, progress);
+        }
+        let wobble = if config.reduced_motion {
+            0.
+        } else {
+            (egg.incubation.elapsed_secs() * WOBBLE_SPEED).sin() * MAX_WOBBLE * progress
+        };
+        transform.rotation = Quat::from_rotation_z(wobble);
+    }
+}
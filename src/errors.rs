@@ -0,0 +1,135 @@
+use crate::{
+    GameState, ball_kinds::BallKindsFile, safe_area::KeepInSafeArea, sound_bank::SoundBank,
+};
+use bevy::{asset::AssetLoadFailedEvent, prelude::*};
+use std::fmt;
+
+const VISIBLE_ERRORS: usize = 5;
+
+/// Systems that can fail return `Result<(), GameError>` and are piped into
+/// [`report_errors`], instead of panicking. Every error is logged, and the
+/// latest few are listed in the corner of the screen (with how often each
+/// repeated) until the next run starts. Assets that fail to load are reported
+/// the same way.
+pub struct ErrorReportingPlugin;
+
+#[derive(Event, Debug)]
+pub enum GameError {
+    MissingEntity(&'static str),
+    MissingAsset(String),
+}
+
+#[derive(Resource, Default)]
+struct ErrorLog(Vec<(String, u32)>);
+
+#[derive(Component)]
+struct ErrorLogText;
+
+impl Plugin for ErrorReportingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GameError>()
+            .init_resource::<ErrorLog>()
+            .add_systems(Startup, spawn_error_log)
+            .add_systems(OnEnter(GameState::InGame), clear_error_log)
+            .add_systems(
+                Update,
+                (
+                    (
+                        report_failed_loads::<AudioSource>,
+                        report_failed_loads::<SoundBank>,
+                        report_failed_loads::<BallKindsFile>,
+                    ),
+                    log_errors,
+                    show_error_log,
+                )
+                    .chain(),
+            );
+    }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::MissingEntity(what) => write!(f, "{what} is missing"),
+            GameError::MissingAsset(path) => write!(f, "couldn't load {path}"),
+        }
+    }
+}
+
+pub fn report_errors(
+    In(result): In<Result<(), GameError>>,
+    mut error_writer: EventWriter<GameError>,
+) {
+    if let Err(error) = result {
+        error_writer.send(error);
+    }
+}
+
+fn spawn_error_log(mut commands: Commands) {
+    commands.spawn((
+        ErrorLogText,
+        Text::default(),
+        TextFont {
+            font_size: 16.,
+            ..default()
+        },
+        TextColor(Color::srgb(1., 0.4, 0.4)),
+        KeepInSafeArea,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.),
+            left: Val::Px(10.),
+            ..default()
+        },
+        GlobalZIndex(10),
+    ));
+}
+
+fn clear_error_log(mut error_log: ResMut<ErrorLog>) {
+    error_log.0.clear();
+}
+
+fn report_failed_loads<A: Asset>(
+    mut failed_reader: EventReader<AssetLoadFailedEvent<A>>,
+    mut error_writer: EventWriter<GameError>,
+) {
+    for failed in failed_reader.read() {
+        error_writer.send(GameError::MissingAsset(failed.path.to_string()));
+    }
+}
+
+fn log_errors(mut error_reader: EventReader<GameError>, mut error_log: ResMut<ErrorLog>) {
+    for error in error_reader.read() {
+        let message = error.to_string();
+
+        if let Some((_, count)) = error_log
+            .0
+            .iter_mut()
+            .find(|(logged, _)| *logged == message)
+        {
+            *count += 1;
+            continue;
+        }
+        warn!("{message}");
+        error_log.0.push((message, 1));
+    }
+}
+
+fn show_error_log(error_log: Res<ErrorLog>, mut text_query: Query<&mut Text, With<ErrorLogText>>) {
+    if !error_log.is_changed() {
+        return;
+    }
+
+    let hidden = error_log.0.len().saturating_sub(VISIBLE_ERRORS);
+    let lines: Vec<String> = error_log.0[hidden..]
+        .iter()
+        .map(|(message, count)| match count {
+            1 => message.clone(),
+            _ => format!("{message} (x{count})"),
+        })
+        .collect();
+
+    for mut text in text_query.iter_mut() {
+        text.0 = lines.join("\n");
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{BACKGROUND_COLOR, Bound, GameState, InGameEntity, layers::RenderLayer};
+use bevy::{
+    prelude::*,
+    render::{mesh::Indices, render_asset::RenderAssetUsages, render_resource::PrimitiveTopology},
+    window::{PrimaryWindow, WindowResized},
+};
+
+/// How far past the bounds the frame starts, so balls sitting on a bound are
+/// still hidden.
+const FRAME_INSET: f32 = 10.;
+/// Extra room past the window edges, for the screen tilt.
+const WINDOW_MARGIN: f32 = 200.;
+
+/// Balls spawn outside the arena and travel in; a background-colored frame
+/// around the arena hides them until they cross a bound. The frame reaches
+/// the window edges and is rebuilt whenever the window is resized.
+pub struct ArenaFramePlugin;
+
+#[derive(Component)]
+struct ArenaFrame;
+
+impl Plugin for ArenaFramePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::InGame), spawn_arena_frame)
+            .add_systems(Update, fit_arena_frame.run_if(on_event::<WindowResized>));
+    }
+}
+
+/// A rectangle with a rectangular hole, as four quads around `inner`.
+fn frame_mesh(inner: Rect, outer: Rect) -> Mesh {
+    let corners = |rect: Rect| {
+        [
+            [rect.min.x, rect.min.y, 0.],
+            [rect.max.x, rect.min.y, 0.],
+            [rect.max.x, rect.max.y, 0.],
+            [rect.min.x, rect.max.y, 0.],
+        ]
+    };
+    let positions: Vec<[f32; 3]> = corners(outer).into_iter().chain(corners(inner)).collect();
+
+    let indices = (0..4)
+        .flat_map(|side| {
+            let next = (side + 1) % 4;
+            [side, next, next + 4, side, next + 4, side + 4]
+        })
+        .collect();
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+fn window_frame_mesh(window: &Window) -> Mesh {
+    let inner = Bound::arena().inflate(FRAME_INSET);
+    let outer = Rect::from_center_half_size(Vec2::ZERO, window.size() / 2. + WINDOW_MARGIN)
+        .union(inner.inflate(WINDOW_MARGIN));
+
+    frame_mesh(inner, outer)
+}
+
+fn spawn_arena_frame(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    commands.spawn((
+        ArenaFrame,
+        InGameEntity,
+        Mesh2d(meshes.add(window_frame_mesh(window))),
+        MeshMaterial2d(materials.add(BACKGROUND_COLOR)),
+        Transform::from_xyz(0., 0., RenderLayer::Covers.z()),
+    ));
+}
+
+fn fit_arena_frame(
+    mut meshes: ResMut<Assets<Mesh>>,
+    frame_query: Query<&Mesh2d, With<ArenaFrame>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    for Mesh2d(mesh) in frame_query.iter() {
+        if let Some(mesh) = meshes.get_mut(mesh) {
+            *mesh = window_frame_mesh(window);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{
+    GameRng, GameState, InGameEntity, InGameSet, Numbered, Player, Wall, arena::ArenaLayout,
+    layers::RenderLayer, seed_run, spawn_numbered_ball, stats::RunModifier,
+};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+
+const TREASURE_NUMBERS: std::ops::RangeInclusive<i32> = 20..=40;
+
+/// Spawns the layout's gates and their treasure, and opens each gate once
+/// the player's number goes past its threshold.
+pub struct GatePlugin;
+
+#[derive(Component)]
+struct Gate {
+    threshold: i32,
+}
+
+impl Plugin for GatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::InGame), spawn_gates.after(seed_run))
+            .add_systems(Update, open_gates.in_set(InGameSet));
+    }
+}
+
+fn spawn_gates(
+    mut commands: Commands,
+    layout: Res<ArenaLayout>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    for gate in &layout.gates {
+        commands
+            .spawn((
+                Gate {
+                    threshold: gate.threshold,
+                },
+                Wall,
+                InGameEntity,
+                Sprite {
+                    color: Color::srgb(0.8, 0.7, 0.2),
+                    custom_size: Some(gate.size),
+                    ..default()
+                },
+                Transform::from_translation(RenderLayer::Walls.at(gate.position)),
+                RigidBody::Static,
+                Collider::rectangle(gate.size.x, gate.size.y),
+                Restitution::PERFECTLY_ELASTIC,
+            ))
+            .with_children(|builder| {
+                builder.spawn((
+                    Text2d::new(format!("> {}", gate.threshold)),
+                    TextFont {
+                        font_size: 16.,
+                        ..default()
+                    },
+                    TextColor(Color::BLACK),
+                    Transform::from_xyz(0., 0., 1.),
+                ));
+            });
+
+        for _ in 0..gate.treasure {
+            let rng = &mut game_rng.rng;
+            let position = Vec2::new(
+                rng.random_range(gate.room.min.x + 40.0..gate.room.max.x - 40.),
+                rng.random_range(gate.room.min.y + 40.0..gate.room.max.y - 40.),
+            );
+            let number = rng.random_range(TREASURE_NUMBERS);
+
+            spawn_numbered_ball(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                number,
+                position,
+                Vec2::ZERO,
+            );
+        }
+    }
+}
+
+fn open_gates(
+    mut commands: Commands,
+    player_query: Query<&Numbered, (With<Player>, Changed<Numbered>)>,
+    gate_query: Query<(Entity, &Gate)>,
+    mut modifier_writer: EventWriter<RunModifier>,
+) {
+    let Ok(Numbered(player_number)) = player_query.get_single() else {
+        return;
+    };
+
+    for (entity, gate) in gate_query.iter() {
+        if *player_number > gate.threshold {
+            commands.entity(entity).despawn_recursive();
+            modifier_writer.send(RunModifier(format!("opened the {} gate", gate.threshold)));
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{Ball, InGameSet};
+use bevy::prelude::*;
+
+/// Frames are over budget below about 48 fps and have headroom again above
+/// about 57, so a vsynced 60 fps still counts as headroom.
+const FRAME_BUDGET: f32 = 1. / 60.;
+/// How quickly the smoothed frame time follows the real one.
+const SMOOTHING_RATE: f32 = 4.;
+const STEP_DOWN_AFTER: f32 = 2.;
+const STEP_UP_AFTER: f32 = 5.;
+/// The ball cap at each step, from full quality down.
+const BALL_CAPS: [Option<usize>; 4] = [None, Some(300), Some(200), Some(120)];
+/// How much of their full length trails keep at each step, from full quality
+/// down; none at all at the last.
+const TRAIL_QUALITY: [f32; 4] = [1., 0.75, 0.5, 0.];
+
+/// Watches frame times during a run and, while they stay over budget, steps
+/// down through [`BALL_CAPS`] so fewer balls are alive at once, and through
+/// [`TRAIL_QUALITY`] so trails leave fewer dots; once there's been headroom
+/// for a while it steps back up.
+pub struct PerformanceGovernorPlugin;
+
+#[derive(Resource, Default)]
+pub struct PerformanceGovernor {
+    step: usize,
+    smoothed_frame_time: f32,
+    over_budget_for: f32,
+    under_budget_for: f32,
+}
+
+impl Plugin for PerformanceGovernorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PerformanceGovernor>()
+            .add_systems(Update, govern_performance.in_set(InGameSet));
+    }
+}
+
+impl PerformanceGovernor {
+    /// How many balls may be alive at once at the current step, if it's
+    /// capped at all.
+    pub fn ball_cap(&self) -> Option<usize> {
+        BALL_CAPS[self.step]
+    }
+
+    /// From 1 for full trails to 0 for none at the current step.
+    pub fn trail_quality(&self) -> f32 {
+        TRAIL_QUALITY[self.step]
+    }
+}
+
+/// Run condition for spawning more balls: whether the current step's cap
+/// leaves room for another.
+pub fn has_ball_headroom(
+    governor: Res<PerformanceGovernor>,
+    ball_query: Query<(), With<Ball>>,
+) -> bool {
+    governor
+        .ball_cap()
+        .is_none_or(|cap| ball_query.iter().count() < cap)
+}
+
+fn govern_performance(mut governor: ResMut<PerformanceGovernor>, time: Res<Time<Real>>) {
+    let delta = time.delta_secs();
+    let blend = 1. - (-SMOOTHING_RATE * delta).exp();
+    governor.smoothed_frame_time += (delta - governor.smoothed_frame_time) * blend;
+
+    if governor.smoothed_frame_time > FRAME_BUDGET * 1.25 {
+        governor.over_budget_for += delta;
+        governor.under_budget_for = 0.;
+    } else if governor.smoothed_frame_time < FRAME_BUDGET * 1.05 {
+        governor.under_budget_for += delta;
+        governor.over_budget_for = 0.;
+    } else {
+        governor.over_budget_for = 0.;
+        governor.under_budget_for = 0.;
+    }
+
+    let step = governor.step;
+    if governor.over_budget_for >= STEP_DOWN_AFTER && step + 1 < BALL_CAPS.len() {
+        governor.step += 1;
+    } else if governor.under_budget_for >= STEP_UP_AFTER && step > 0 {
+        governor.step -= 1;
+    } else {
+        return;
+    }
+
+    governor.over_budget_for = 0.;
+    governor.under_budget_for = 0.;
+    info!(
+        "performance step {}: ball cap {:?}, trail quality {}",
+        governor.step, BALL_CAPS[governor.step], TRAIL_QUALITY[governor.step]
+    );
+}
@@ -0,0 +1,254 @@
+use crate::{
+    GameRng, GameState, InGameEntity, InGameSet,
+    config::GameConfig,
+    haptics::HapticPulse,
+    safe_area::KeepInSafeArea,
+    sound_bank::{GameSounds, PlaySound, SoundBank, SoundEvent},
+    stats::RunModifier,
+    timers::{AppGameTimersExt, GameTimers},
+};
+use avian2d::prelude::*;
+use bevy::{audio::Volume, prelude::*};
+use rand::Rng;
+use std::time::Duration;
+
+const BASE_GRAVITY: f32 = 1000.;
+const GRAVITY_EVENT_TIMER: &str = "gravity_event";
+const EVENT_DURATION: f32 = 8.;
+const CALM_DURATION: std::ops::Range<f32> = 15.0..30.0;
+const STRENGTH_LERP_RATE: f32 = 3.;
+const TILT_ANGLE: f32 = 0.03;
+const TILT_DURATION: f32 = 0.3;
+
+/// Gravity's magnitude is perturbed by random events that temporarily double
+/// or halve it, independently of its direction, which the player flips.
+/// This owns avian's [`Gravity`] and rebuilds it from the two every frame,
+/// and gives every flip a sound, a haptic pulse and a brief tilt of the
+/// screen.
+pub struct GravityPlugin;
+
+#[derive(Resource)]
+pub struct GravityDirection(pub Dir2);
+
+/// The player tried to flip gravity. `blocked` flips were refused because the
+/// last one was too recent, and leave `direction` as it was.
+#[derive(Event)]
+pub struct GravityFlip {
+    pub direction: Dir2,
+    pub blocked: bool,
+}
+
+/// Starts a gravity event straight away instead of waiting for the next
+/// random one, e.g. `strength: 0.` for zero-g.
+#[derive(Event)]
+pub struct TriggerGravityEvent {
+    pub strength: f32,
+}
+
+#[derive(Resource)]
+struct FlipTones {
+    flip: Handle<Pitch>,
+    blocked: Handle<Pitch>,
+}
+
+#[derive(Resource, Default)]
+struct ScreenTilt {
+    angle: f32,
+    remaining: f32,
+}
+
+#[derive(Resource)]
+struct GravityStrength {
+    current: f32,
+    target: f32,
+}
+
+#[derive(Component)]
+struct GravityIndicator;
+
+impl FromWorld for FlipTones {
+    fn from_world(world: &mut World) -> Self {
+        let mut pitches = world.resource_mut::<Assets<Pitch>>();
+        FlipTones {
+            flip: pitches.add(Pitch::new(520., Duration::from_millis(120))),
+            blocked: pitches.add(Pitch::new(90., Duration::from_millis(250))),
+        }
+    }
+}
+
+impl Plugin for GravityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GravityFlip>()
+            .add_event::<TriggerGravityEvent>()
+            .init_resource::<FlipTones>()
+            .init_resource::<ScreenTilt>()
+            .insert_resource(GravityDirection(Dir2::NEG_Y))
+            .insert_resource(Gravity(Vec2::NEG_Y * BASE_GRAVITY))
+            .insert_resource(GravityStrength {
+                current: 1.,
+                target: 1.,
+            })
+            .add_game_timer(
+                GRAVITY_EVENT_TIMER,
+                Timer::from_seconds(CALM_DURATION.start, TimerMode::Once),
+            )
+            .add_systems(OnEnter(GameState::InGame), start_gravity_events)
+            // Game timers tick on the fixed step.
+            .add_systems(FixedUpdate, run_gravity_events.in_set(InGameSet))
+            .add_systems(
+                Update,
+                (apply_gravity, update_gravity_indicator)
+                    .chain()
+                    .in_set(InGameSet),
+            )
+            .add_systems(Update, (play_flip_feedback, tilt_screen).chain());
+    }
+}
+
+fn start_gravity_events(
+    mut commands: Commands,
+    mut direction: ResMut<GravityDirection>,
+    mut strength: ResMut<GravityStrength>,
+    mut game_timers: ResMut<GameTimers>,
+) {
+    direction.0 = Dir2::NEG_Y;
+    *strength = GravityStrength {
+        current: 1.,
+        target: 1.,
+    };
+    game_timers.restart(GRAVITY_EVENT_TIMER, CALM_DURATION.start);
+
+    commands.spawn((
+        GravityIndicator,
+        InGameEntity,
+        Text::default(),
+        TextFont {
+            font_size: 30.,
+            ..default()
+        },
+        KeepInSafeArea,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.),
+            left: Val::Px(20.),
+            ..default()
+        },
+    ));
+}
+
+fn run_gravity_events(
+    mut strength: ResMut<GravityStrength>,
+    mut game_timers: ResMut<GameTimers>,
+    mut game_rng: ResMut<GameRng>,
+    mut trigger_reader: EventReader<TriggerGravityEvent>,
+    mut modifier_writer: EventWriter<RunModifier>,
+) {
+    if let Some(trigger) = trigger_reader.read().last() {
+        strength.target = trigger.strength;
+        game_timers.restart(GRAVITY_EVENT_TIMER, EVENT_DURATION);
+        modifier_writer.send(RunModifier(format!("gravity x{}", strength.target)));
+        return;
+    }
+    if !game_timers.just_finished(GRAVITY_EVENT_TIMER) {
+        return;
+    }
+
+    let rng = &mut game_rng.rng;
+    if strength.target == 1. {
+        strength.target = if rng.random_bool(0.5) { 2. } else { 0.5 };
+        game_timers.restart(GRAVITY_EVENT_TIMER, EVENT_DURATION);
+        modifier_writer.send(RunModifier(format!("gravity x{}", strength.target)));
+    } else {
+        strength.target = 1.;
+        let calm = rng.random_range(CALM_DURATION);
+        game_timers.restart(GRAVITY_EVENT_TIMER, calm);
+    }
+}
+
+fn apply_gravity(
+    mut gravity: ResMut<Gravity>,
+    mut strength: ResMut<GravityStrength>,
+    direction: Res<GravityDirection>,
+    time: Res<Time>,
+) {
+    let blend = 1. - (-STRENGTH_LERP_RATE * time.delta_secs()).exp();
+    strength.current += (strength.target - strength.current) * blend;
+
+    gravity.0 = *direction.0 * BASE_GRAVITY * strength.current;
+}
+
+fn update_gravity_indicator(
+    strength: Res<GravityStrength>,
+    mut indicator_query: Query<&mut Text, With<GravityIndicator>>,
+) {
+    for mut text in indicator_query.iter_mut() {
+        text.0 = match strength.target {
+            2. => "gravity x2".to_string(),
+            0.5 => "gravity x0.5".to_string(),
+            0. => "zero-g".to_string(),
+            _ => String::new(),
+        };
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn play_flip_feedback(
+    mut commands: Commands,
+    mut flip_reader: EventReader<GravityFlip>,
+    mut pulse_writer: EventWriter<HapticPulse>,
+    tones: Res<FlipTones>,
+    sounds: Res<GameSounds>,
+    banks: Res<Assets<SoundBank>>,
+    mut play_writer: EventWriter<PlaySound>,
+    config: Res<GameConfig>,
+    mut tilt: ResMut<ScreenTilt>,
+) {
+    let flip_cue = banks
+        .get(&sounds.0)
+        .is_some_and(|bank| bank.has(SoundEvent::Flip));
+
+    for flip in flip_reader.read() {
+        let (tone, volume, strength, rumble_millis) = if flip.blocked {
+            (&tones.blocked, 0.6, 1., 250)
+        } else {
+            (&tones.flip, 0.3, 0.4, 100)
+        };
+
+        if flip_cue && !flip.blocked {
+            play_writer.send(PlaySound(SoundEvent::Flip));
+        } else {
+            commands.spawn((
+                AudioPlayer(tone.clone()),
+                PlaybackSettings::DESPAWN.with_volume(Volume::new(volume)),
+                InGameEntity,
+            ));
+        }
+        pulse_writer.send(HapticPulse {
+            strength,
+            duration: Duration::from_millis(rumble_millis),
+        });
+
+        if !flip.blocked && !config.reduced_motion {
+            *tilt = ScreenTilt {
+                angle: TILT_ANGLE * flip.direction.y.signum(),
+                remaining: TILT_DURATION,
+            };
+        }
+    }
+}
+
+fn tilt_screen(
+    mut tilt: ResMut<ScreenTilt>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+    time: Res<Time<Real>>,
+) {
+    if tilt.remaining <= 0. {
+        return;
+    }
+    tilt.remaining = (tilt.remaining - time.delta_secs()).max(0.);
+
+    let angle = tilt.angle * tilt.remaining / TILT_DURATION;
+    for mut transform in camera_query.iter_mut() {
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{BallEaten, FatalHit, config::GameConfig, settings::Settings};
+use bevy::{
+    input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest},
+    prelude::*,
+};
+use std::time::Duration;
+
+/// Short vibrations for gameplay moments. Anything can play one by sending
+/// [`HapticPulse`]; it's played on every connected gamepad and, in web builds,
+/// on the device itself, unless vibration is turned off in the settings or
+/// the `haptics` config is off.
+pub struct HapticsPlugin;
+
+#[derive(Event)]
+pub struct HapticPulse {
+    /// From 0 to 1. The web vibration API can't vary strength, so it only
+    /// reaches gamepads.
+    pub strength: f32,
+    pub duration: Duration,
+}
+
+impl Plugin for HapticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HapticPulse>().add_systems(
+            Update,
+            (pulse_on_hits, play_haptic_pulses)
+                .chain()
+                .run_if(haptics_enabled),
+        );
+    }
+}
+
+fn haptics_enabled(config: Res<GameConfig>, settings: Res<Settings>) -> bool {
+    config.haptics && settings.haptics
+}
+
+fn pulse_on_hits(
+    mut ball_eaten_reader: EventReader<BallEaten>,
+    mut fatal_hit_reader: EventReader<FatalHit>,
+    mut pulse_writer: EventWriter<HapticPulse>,
+) {
+    for _ in ball_eaten_reader.read() {
+        pulse_writer.send(HapticPulse {
+            strength: 0.2,
+            duration: Duration::from_millis(40),
+        });
+    }
+    if fatal_hit_reader.read().next().is_some() {
+        pulse_writer.send(HapticPulse {
+            strength: 1.,
+            duration: Duration::from_millis(400),
+        });
+    }
+}
+
+fn play_haptic_pulses(
+    mut pulse_reader: EventReader<HapticPulse>,
+    mut rumble_writer: EventWriter<GamepadRumbleRequest>,
+    gamepad_query: Query<Entity, With<Gamepad>>,
+) {
+    for pulse in pulse_reader.read() {
+        for gamepad in gamepad_query.iter() {
+            rumble_writer.send(GamepadRumbleRequest::Add {
+                duration: pulse.duration,
+                intensity: GamepadRumbleIntensity {
+                    strong_motor: pulse.strength,
+                    weak_motor: pulse.strength,
+                },
+                gamepad,
+            });
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if let Some(window) = web_sys::window() {
+            window
+                .navigator()
+                .vibrate_with_duration(pulse.duration.as_millis() as u32);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{Bound, GameState, InGameSet, Player};
+use bevy::prelude::*;
+
+const COLUMNS: usize = 24;
+const ROWS: usize = 14;
+/// In px, on the screens the heatmap is shown on.
+const CELL_SIZE: f32 = 8.;
+
+/// Keeps track of where in the arena the player spends a run, on a coarse
+/// grid, drawn on the death screen and the pause menu so players can see
+/// where they camp and where they die.
+pub struct HeatmapPlugin;
+
+#[derive(Resource)]
+pub struct PositionHeatmap {
+    /// Seconds spent in each cell, row by row from the bottom of the arena.
+    seconds: Vec<f32>,
+    /// Where the player was last seen, which is where they died once the run
+    /// is over.
+    last_cell: Option<usize>,
+}
+
+impl Default for PositionHeatmap {
+    fn default() -> Self {
+        PositionHeatmap {
+            seconds: vec![0.; COLUMNS * ROWS],
+            last_cell: None,
+        }
+    }
+}
+
+impl Plugin for HeatmapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PositionHeatmap>()
+            .add_systems(OnEnter(GameState::InGame), reset_heatmap)
+            .add_systems(FixedUpdate, track_player_position.in_set(InGameSet));
+    }
+}
+
+impl PositionHeatmap {
+    fn cell_at(position: Vec2) -> usize {
+        let arena = Bound::arena();
+        let fraction = ((position - arena.min) / arena.size()).clamp(Vec2::ZERO, Vec2::ONE);
+        let column = ((fraction.x * COLUMNS as f32) as usize).min(COLUMNS - 1);
+        let row = ((fraction.y * ROWS as f32) as usize).min(ROWS - 1);
+        row * COLUMNS + column
+    }
+}
+
+/// Draws the heatmap as a grid of cells, brighter the longer the player spent
+/// in them, with the last cell they were in picked out in red.
+pub fn spawn_heatmap(builder: &mut ChildBuilder, heatmap: &PositionHeatmap) {
+    let most_seconds = heatmap.seconds.iter().copied().fold(0., f32::max);
+
+    builder
+        .spawn((
+            Node {
+                display: Display::Grid,
+                grid_template_columns: RepeatedGridTrack::px(COLUMNS as u16, CELL_SIZE),
+                grid_template_rows: RepeatedGridTrack::px(ROWS as u16, CELL_SIZE),
+                margin: UiRect::vertical(Val::Px(10.)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(1., 1., 1., 0.05)),
+        ))
+        .with_children(|builder| {
+            // The arena's y goes up but the grid's rows go down.
+            for row in (0..ROWS).rev() {
+                for column in 0..COLUMNS {
+                    let cell = row * COLUMNS + column;
+                    let color = if heatmap.last_cell == Some(cell) {
+                        Color::srgb(1., 0.2, 0.2)
+                    } else if most_seconds > 0. {
+                        let heat = heatmap.seconds[cell] / most_seconds;
+                        Color::srgba(1., 0.7, 0.2, heat.sqrt())
+                    } else {
+                        Color::NONE
+                    };
+                    builder.spawn((Node::default(), BackgroundColor(color)));
+                }
+            }
+        });
+}
+
+fn reset_heatmap(mut heatmap: ResMut<PositionHeatmap>) {
+    *heatmap = PositionHeatmap::default();
+}
+
+fn track_player_position(
+    mut heatmap: ResMut<PositionHeatmap>,
+    player_query: Query<&Transform, With<Player>>,
+    time: Res<Time>,
+) {
+    for transform in player_query.iter() {
+        let cell = PositionHeatmap::cell_at(transform.translation.truncate());
+        heatmap.seconds[cell] += time.delta_secs();
+        heatmap.last_cell = Some(cell);
+    }
+}
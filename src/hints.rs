@@ -0,0 +1,171 @@
+use crate::{
+    GameState, InGameSet,
+    gravity::GravityFlip,
+    prompts::{MenuAction, MenuInput},
+    recorded_run,
+    shedding::MassShed,
+    stats::RunStats,
+    storage::Storage,
+    toast::ShowToast,
+};
+use bevy::prelude::*;
+
+const SHOWN_HINTS_KEY: &str = "hints_shown";
+const FLIP_HINT_AFTER: f32 = 30.;
+const SHED_HINT_AFTER: f32 = 60.;
+/// Runs shorter than this count as dying quickly.
+const QUICK_DEATH: f32 = 20.;
+const QUICK_DEATHS_FOR_HINT: u32 = 3;
+
+/// Watches how the player plays for signs they've missed a mechanic (never
+/// flipping gravity, never shedding, dying to bigger balls straight away run
+/// after run) and shows a tip about it as a toast. Each tip is only ever
+/// shown once; the ones already shown are saved to storage.
+pub struct HintPlugin;
+
+#[derive(Clone, Copy)]
+enum Hint {
+    FlipGravity,
+    Shed,
+    AvoidBiggerBalls,
+}
+
+#[derive(Resource, Default)]
+struct ShownHints(Vec<String>);
+
+#[derive(Resource, Default)]
+struct HintWatch {
+    flipped_gravity: bool,
+    shed: bool,
+    quick_deaths: u32,
+}
+
+impl Plugin for HintPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShownHints>()
+            .init_resource::<HintWatch>()
+            .add_systems(Startup, load_shown_hints)
+            .add_systems(OnEnter(GameState::InGame), start_watching_run)
+            .add_systems(OnEnter(GameState::DeathScreen), count_quick_deaths)
+            .add_systems(
+                Update,
+                watch_for_hints.in_set(InGameSet).run_if(recorded_run),
+            );
+    }
+}
+
+impl Hint {
+    fn key(self) -> &'static str {
+        match self {
+            Hint::FlipGravity => "flip_gravity",
+            Hint::Shed => "shed",
+            Hint::AvoidBiggerBalls => "avoid_bigger_balls",
+        }
+    }
+
+    /// Names the keys or buttons of the device used last.
+    fn text(self, menu_input: &MenuInput) -> String {
+        match self {
+            Hint::FlipGravity => format!(
+                "Tip: press {} to flip gravity",
+                menu_input.glyph(MenuAction::FlipGravity)
+            ),
+            Hint::Shed => format!(
+                "Tip: press {} to shed mass, then eat it back",
+                menu_input.glyph(MenuAction::Shed)
+            ),
+            Hint::AvoidBiggerBalls => {
+                "Tip: only balls smaller than you are safe to eat".to_string()
+            }
+        }
+    }
+}
+
+fn load_shown_hints(storage: Res<Storage>, mut shown_hints: ResMut<ShownHints>) {
+    if let Some(shown) = storage.load::<String>(SHOWN_HINTS_KEY) {
+        shown_hints.0 = shown.split(',').map(str::to_string).collect();
+    }
+}
+
+fn show_hint(
+    hint: Hint,
+    menu_input: &MenuInput,
+    shown_hints: &mut ShownHints,
+    storage: &mut Storage,
+    toast_writer: &mut EventWriter<ShowToast>,
+) {
+    if shown_hints.0.iter().any(|shown| shown == hint.key()) {
+        return;
+    }
+
+    shown_hints.0.push(hint.key().to_string());
+    storage.save(SHOWN_HINTS_KEY, shown_hints.0.join(","));
+    toast_writer.send(ShowToast(hint.text(menu_input)));
+}
+
+fn start_watching_run(
+    mut watch: ResMut<HintWatch>,
+    menu_input: MenuInput,
+    mut shown_hints: ResMut<ShownHints>,
+    mut storage: ResMut<Storage>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    watch.flipped_gravity = false;
+    watch.shed = false;
+
+    if watch.quick_deaths >= QUICK_DEATHS_FOR_HINT {
+        show_hint(
+            Hint::AvoidBiggerBalls,
+            &menu_input,
+            &mut shown_hints,
+            &mut storage,
+            &mut toast_writer,
+        );
+    }
+}
+
+fn count_quick_deaths(mut watch: ResMut<HintWatch>, stats: Res<RunStats>) {
+    if stats.duration < QUICK_DEATH {
+        watch.quick_deaths += 1;
+    } else {
+        watch.quick_deaths = 0;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn watch_for_hints(
+    mut watch: ResMut<HintWatch>,
+    mut flip_reader: EventReader<GravityFlip>,
+    mut shed_reader: EventReader<MassShed>,
+    stats: Res<RunStats>,
+    menu_input: MenuInput,
+    mut shown_hints: ResMut<ShownHints>,
+    mut storage: ResMut<Storage>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    if flip_reader.read().next().is_some() {
+        watch.flipped_gravity = true;
+    }
+    if shed_reader.read().next().is_some() {
+        watch.shed = true;
+    }
+
+    if !watch.flipped_gravity && stats.duration > FLIP_HINT_AFTER {
+        show_hint(
+            Hint::FlipGravity,
+            &menu_input,
+            &mut shown_hints,
+            &mut storage,
+            &mut toast_writer,
+        );
+    }
+    if !watch.shed && stats.duration > SHED_HINT_AFTER {
+        show_hint(
+            Hint::Shed,
+            &menu_input,
+            &mut shown_hints,
+            &mut storage,
+            &mut toast_writer,
+        );
+    }
+}
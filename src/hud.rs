@@ -0,0 +1,172 @@
+use crate::{
+    GameState, InGameEntity, InGameSet, Numbered, Player,
+    config::GameConfig,
+    safe_area::KeepInSafeArea,
+    score::Score,
+    spatial::{BallGrid, BallGridSet},
+    stats::RunStats,
+};
+use bevy::prelude::*;
+
+/// Bigger balls closer than this keep the whole HUD up.
+const THREAT_RADIUS: f32 = 300.;
+const CALM_OPACITY: f32 = 0.2;
+/// In seconds, after something on a widget changes.
+const SHOW_AFTER_CHANGE: f32 = 2.;
+/// In opacity per second.
+const FADE_SPEED: f32 = 3.;
+
+/// The player's number, the score, the run timer and how many balls have
+/// been eaten, kept in the bottom corners of the screen through a run, since
+/// the number on the player gets hard to read while it's small. With
+/// `adaptive_hud` on, everything but the number fades back while no bigger
+/// ball is near the player, and comes back when one is or when something it
+/// shows changes.
+pub struct HudPlugin;
+
+/// Marks HUD text that can fade back in calm moments.
+#[derive(Component)]
+pub struct HudFade;
+
+#[derive(Resource)]
+struct HudOpacity {
+    opacity: f32,
+    showing_for: f32,
+    balls_eaten: u32,
+}
+
+#[derive(Component)]
+enum HudText {
+    Number,
+    Score,
+    Timer,
+    BallsEaten,
+}
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HudOpacity {
+            opacity: 1.,
+            showing_for: 0.,
+            balls_eaten: 0,
+        })
+        .add_systems(OnEnter(GameState::InGame), spawn_hud)
+        .add_systems(Update, update_hud.run_if(in_state(GameState::InGame)))
+        .add_systems(
+            Update,
+            fade_calm_hud
+                .after(BallGridSet)
+                .in_set(InGameSet)
+                .run_if(|config: Res<GameConfig>| config.adaptive_hud),
+        );
+    }
+}
+
+fn spawn_hud(mut commands: Commands, mut hud_opacity: ResMut<HudOpacity>) {
+    *hud_opacity = HudOpacity {
+        opacity: 1.,
+        showing_for: SHOW_AFTER_CHANGE,
+        balls_eaten: 0,
+    };
+
+    commands.spawn((
+        HudText::Number,
+        InGameEntity,
+        Text::default(),
+        TextFont {
+            font_size: 40.,
+            ..default()
+        },
+        KeepInSafeArea,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(20.),
+            left: Val::Px(20.),
+            ..default()
+        },
+    ));
+
+    commands
+        .spawn((
+            InGameEntity,
+            KeepInSafeArea,
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(20.),
+                right: Val::Px(20.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::End,
+                ..default()
+            },
+        ))
+        .with_children(|builder| {
+            for text in [HudText::Score, HudText::Timer, HudText::BallsEaten] {
+                builder.spawn((
+                    text,
+                    HudFade,
+                    Text::default(),
+                    TextFont {
+                        font_size: 30.,
+                        ..default()
+                    },
+                ));
+            }
+        });
+}
+
+fn update_hud(
+    player_query: Query<&Numbered, With<Player>>,
+    score: Res<Score>,
+    stats: Res<RunStats>,
+    mut hud_query: Query<(&HudText, &mut Text)>,
+) {
+    let player_number = player_query.get_single().ok();
+
+    for (hud_text, mut text) in hud_query.iter_mut() {
+        text.0 = match hud_text {
+            HudText::Number => match player_number {
+                Some(Numbered(number)) => number.to_string(),
+                None => continue,
+            },
+            HudText::Score => format!("{} points", score.0),
+            HudText::Timer => {
+                let minutes = (stats.duration / 60.) as u32;
+                format!("{minutes}:{:04.1}", stats.duration % 60.)
+            }
+            HudText::BallsEaten => format!("{} eaten", stats.balls_eaten),
+        };
+    }
+}
+
+fn fade_calm_hud(
+    mut hud_opacity: ResMut<HudOpacity>,
+    grid: Res<BallGrid>,
+    player_query: Query<(&Transform, &Numbered), With<Player>>,
+    stats: Res<RunStats>,
+    mut fade_query: Query<&mut TextColor, With<HudFade>>,
+    time: Res<Time>,
+) {
+    let threatened = player_query
+        .get_single()
+        .is_ok_and(|(transform, Numbered(player_number))| {
+            grid.within(transform.translation.truncate(), THREAT_RADIUS)
+                .any(|ball| ball.number > *player_number)
+        });
+    if stats.balls_eaten != hud_opacity.balls_eaten {
+        hud_opacity.balls_eaten = stats.balls_eaten;
+        hud_opacity.showing_for = SHOW_AFTER_CHANGE;
+    }
+    hud_opacity.showing_for -= time.delta_secs();
+
+    let target = if threatened || hud_opacity.showing_for > 0. {
+        1.
+    } else {
+        CALM_OPACITY
+    };
+    let step = FADE_SPEED * time.delta_secs();
+    hud_opacity.opacity += (target - hud_opacity.opacity).clamp(-step, step);
+
+    for mut color in fade_query.iter_mut() {
+        color.0.set_alpha(hud_opacity.opacity);
+    }
+}
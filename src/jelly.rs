@@ -0,0 +1,181 @@
+use crate::{InGameSet, Numbered, Player, SIZE_FACTOR, config::GameConfig, skins::SkinShape};
+use avian2d::prelude::*;
+use bevy::{
+    prelude::*,
+    render::{mesh::Indices, render_asset::RenderAssetUsages, render_resource::PrimitiveTopology},
+};
+
+const POINTS_PER_SIDE: usize = 6;
+const POINT_COUNT: usize = POINTS_PER_SIDE * 4;
+const STIFFNESS: f32 = 120.;
+const DAMPING: f32 = 8.;
+/// How strongly each point is pulled toward its neighbours, so a dent spreads
+/// along the edge instead of staying a spike.
+const COUPLING: f32 = 60.;
+/// How hard a new contact knocks the edge inward, in player sizes per second.
+const IMPACT_KICK: f32 = 1.5;
+const IMPACT_SPREAD: f32 = 0.25;
+const MAX_DENT: f32 = 0.2;
+
+/// Draws the player as a wobbling square, or whatever shape their skin has:
+/// its outline is a ring of points on springs that get knocked inward
+/// wherever something new touches it. Purely cosmetic, the collider stays a
+/// plain rectangle. Reduced motion keeps the outline at rest.
+pub struct JellyPlugin;
+
+#[derive(Component)]
+struct Jelly {
+    mesh: Handle<Mesh>,
+    shape: SkinShape,
+    offsets: [Vec2; POINT_COUNT],
+    velocities: [Vec2; POINT_COUNT],
+}
+
+impl Plugin for JellyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, add_jelly)
+            .add_systems(
+                Update,
+                (dent_on_contact, simulate_jelly)
+                    .chain()
+                    .after(add_jelly)
+                    .in_set(InGameSet),
+            )
+            .add_systems(PostUpdate, shape_jelly_mesh);
+    }
+}
+
+/// Where each outline point sits at rest on a unit square, going around it,
+/// or on the circle or diamond that fits in it.
+fn rest_point(shape: SkinShape, index: usize) -> Vec2 {
+    let side = index / POINTS_PER_SIDE;
+    let along = (index % POINTS_PER_SIDE) as f32 / POINTS_PER_SIDE as f32 - 0.5;
+    let on_square = match side {
+        0 => Vec2::new(along, -0.5),
+        1 => Vec2::new(0.5, along),
+        2 => Vec2::new(-along, 0.5),
+        _ => Vec2::new(-0.5, -along),
+    };
+    match shape {
+        SkinShape::Square => on_square,
+        SkinShape::Round => on_square.normalize() * 0.5,
+        SkinShape::Diamond => Vec2::from_angle(std::f32::consts::FRAC_PI_4)
+            .rotate(on_square * std::f32::consts::FRAC_1_SQRT_2),
+    }
+}
+
+fn jelly_mesh(size: f32, shape: SkinShape, offsets: &[Vec2; POINT_COUNT]) -> Mesh {
+    let positions: Vec<[f32; 3]> = std::iter::once([0., 0., 0.])
+        .chain(offsets.iter().enumerate().map(|(index, offset)| {
+            ((rest_point(shape, index) + *offset) * size)
+                .extend(0.)
+                .into()
+        }))
+        .collect();
+    let indices = (0..POINT_COUNT as u32)
+        .flat_map(|index| [0, index + 1, (index + 1) % POINT_COUNT as u32 + 1])
+        .collect();
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+fn add_jelly(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    player_query: Query<(Entity, &Numbered, Option<&SkinShape>), Added<Player>>,
+) {
+    for (player, Numbered(number), shape) in player_query.iter() {
+        let shape = shape.copied().unwrap_or_default();
+        let offsets = [Vec2::ZERO; POINT_COUNT];
+        let mesh = meshes.add(jelly_mesh(*number as f32 * SIZE_FACTOR, shape, &offsets));
+
+        commands.entity(player).insert((
+            Mesh2d(mesh.clone()),
+            Jelly {
+                mesh,
+                shape,
+                offsets,
+                velocities: [Vec2::ZERO; POINT_COUNT],
+            },
+        ));
+    }
+}
+
+fn dent_on_contact(
+    collisions: Res<Collisions>,
+    mut jelly_query: Query<(Entity, &Numbered, &mut Jelly)>,
+    config: Res<GameConfig>,
+) {
+    if config.reduced_motion {
+        return;
+    }
+
+    for (player, Numbered(number), mut jelly) in jelly_query.iter_mut() {
+        let size = *number as f32 * SIZE_FACTOR;
+
+        for contacts in collisions.collisions_with_entity(player) {
+            if contacts.is_sensor
+                || !contacts.during_current_frame
+                || contacts.during_previous_frame
+            {
+                continue;
+            }
+
+            for manifold in &contacts.manifolds {
+                for contact in &manifold.contacts {
+                    let point = if contacts.entity1 == player {
+                        contact.point1
+                    } else {
+                        contact.point2
+                    } / size;
+
+                    for index in 0..POINT_COUNT {
+                        let rest = rest_point(jelly.shape, index);
+                        let distance = rest.distance(point);
+                        let weight =
+                            (-(distance * distance) / (IMPACT_SPREAD * IMPACT_SPREAD)).exp();
+                        jelly.velocities[index] -= rest.normalize() * IMPACT_KICK * weight;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn simulate_jelly(mut jelly_query: Query<&mut Jelly>, config: Res<GameConfig>, time: Res<Time>) {
+    let delta = time.delta_secs();
+
+    for mut jelly in jelly_query.iter_mut() {
+        if config.reduced_motion {
+            jelly.offsets = [Vec2::ZERO; POINT_COUNT];
+            jelly.velocities = [Vec2::ZERO; POINT_COUNT];
+            continue;
+        }
+
+        let offsets = jelly.offsets;
+        for index in 0..POINT_COUNT {
+            let previous = offsets[(index + POINT_COUNT - 1) % POINT_COUNT];
+            let next = offsets[(index + 1) % POINT_COUNT];
+            let offset = offsets[index];
+
+            let acceleration = -STIFFNESS * offset - DAMPING * jelly.velocities[index]
+                + COUPLING * ((previous + next) / 2. - offset);
+            jelly.velocities[index] += acceleration * delta;
+            jelly.offsets[index] =
+                (offset + jelly.velocities[index] * delta).clamp_length_max(MAX_DENT);
+        }
+    }
+}
+
+fn shape_jelly_mesh(mut meshes: ResMut<Assets<Mesh>>, jelly_query: Query<(&Numbered, &Jelly)>) {
+    for (Numbered(number), jelly) in jelly_query.iter() {
+        if let Some(mesh) = meshes.get_mut(&jelly.mesh) {
+            *mesh = jelly_mesh(*number as f32 * SIZE_FACTOR, jelly.shape, &jelly.offsets);
+        }
+    }
+}
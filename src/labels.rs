@@ -0,0 +1,129 @@
+use crate::{
+    Ball, FONT_SIZE_FACTOR, Numbered, Player,
+    config::GameConfig,
+    layers::RenderLayer,
+    number_words::{fit_to_ball, format_number},
+};
+use bevy::prelude::*;
+
+/// Font sizes are rounded to multiples of this and the label is scaled to
+/// make up the difference, so the glyph atlas only ever sees a few sizes.
+const FONT_SIZE_STEP: f32 = 4.;
+
+/// Ball number labels are kept in a pool and reused, instead of every ball
+/// spawning (and despawning) its own `Text2d` child. Labels too small to read
+/// or too far from the player are hidden, per the config.
+pub struct BallLabelPlugin;
+
+#[derive(Component)]
+struct BallLabel {
+    ball: Option<Entity>,
+    font_size: f32,
+}
+
+#[derive(Resource, Default)]
+struct LabelPool(Vec<Entity>);
+
+impl Plugin for BallLabelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LabelPool>()
+            .add_systems(Update, attach_ball_labels)
+            .add_systems(
+                PostUpdate,
+                follow_balls.before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+fn bucketed_font_size(font_size: f32) -> f32 {
+    (font_size / FONT_SIZE_STEP).round().max(1.) * FONT_SIZE_STEP
+}
+
+fn attach_ball_labels(
+    mut commands: Commands,
+    mut pool: ResMut<LabelPool>,
+    new_ball_query: Query<(Entity, &Numbered), Added<Ball>>,
+    mut label_query: Query<(
+        &mut BallLabel,
+        &mut Text2d,
+        &mut TextFont,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    config: Res<GameConfig>,
+) {
+    for (ball, Numbered(number)) in new_ball_query.iter() {
+        let label_text = format_number(*number, &config);
+        let font_size = *number as f32 * FONT_SIZE_FACTOR / 2. * fit_to_ball(&label_text);
+        let bucketed_size = bucketed_font_size(font_size);
+        let scale = Vec3::splat(font_size / bucketed_size);
+
+        let pooled_label = pool
+            .0
+            .pop()
+            .and_then(|label| label_query.get_mut(label).ok());
+
+        if let Some((mut label, mut text, mut text_font, mut transform, mut visibility)) =
+            pooled_label
+        {
+            label.ball = Some(ball);
+            label.font_size = font_size;
+            text.0 = label_text;
+            text_font.font_size = bucketed_size;
+            transform.scale = scale;
+            *visibility = Visibility::Inherited;
+        } else {
+            commands.spawn((
+                BallLabel {
+                    ball: Some(ball),
+                    font_size,
+                },
+                Text2d::new(label_text),
+                TextFont {
+                    font_size: bucketed_size,
+                    ..default()
+                },
+                Transform::from_scale(scale),
+            ));
+        }
+    }
+}
+
+fn follow_balls(
+    mut pool: ResMut<LabelPool>,
+    mut label_query: Query<(Entity, &mut BallLabel, &mut Transform, &mut Visibility)>,
+    ball_query: Query<&Transform, (With<Ball>, Without<BallLabel>)>,
+    player_query: Query<&Transform, (With<Player>, Without<BallLabel>)>,
+    config: Res<GameConfig>,
+) {
+    let player_position = player_query
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation.truncate());
+
+    for (label_entity, mut label, mut transform, mut visibility) in label_query.iter_mut() {
+        let Some(ball) = label.ball else {
+            continue;
+        };
+
+        if let Ok(ball_transform) = ball_query.get(ball) {
+            let position = ball_transform.translation.truncate();
+            transform.translation = RenderLayer::BallLabels.at(position);
+
+            let too_far = config
+                .label_max_distance
+                .zip(player_position)
+                .is_some_and(|(max_distance, player)| position.distance(player) > max_distance);
+            let readable = label.font_size >= config.label_min_font_size && !too_far;
+            visibility.set_if_neq(if readable {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            });
+        } else {
+            label.ball = None;
+            *visibility = Visibility::Hidden;
+            pool.0.push(label_entity);
+        }
+    }
+}
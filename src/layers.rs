@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+
+/// Depth bands for everything drawn in the world, back to front. Children
+/// offset their z a little within their parent's band; UI always draws on top
+/// and is ordered with `GlobalZIndex` instead.
+#[derive(Clone, Copy)]
+pub enum RenderLayer {
+    /// Behind everything, see [`crate::parallax`].
+    Backdrop,
+    Decorations,
+    Zones,
+    /// Left behind moving things, see [`crate::trails::Trail`].
+    Trails,
+    Balls,
+    Player,
+    BallLabels,
+    Popups,
+    /// Hides balls that are still outside the arena.
+    Covers,
+    Walls,
+}
+
+impl RenderLayer {
+    pub fn z(self) -> f32 {
+        match self {
+            RenderLayer::Backdrop => -30.,
+            RenderLayer::Decorations => -20.,
+            RenderLayer::Zones => -10.,
+            RenderLayer::Trails => -5.,
+            RenderLayer::Balls => 0.,
+            RenderLayer::Player => 10.,
+            RenderLayer::BallLabels => 20.,
+            RenderLayer::Popups => 30.,
+            RenderLayer::Covers => 90.,
+            RenderLayer::Walls => 100.,
+        }
+    }
+
+    pub fn at(self, position: Vec2) -> Vec3 {
+        position.extend(self.z())
+    }
+}
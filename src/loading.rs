@@ -0,0 +1,111 @@
+use crate::{
+    GameState,
+    ball_kinds::BallKindSource,
+    config::GameConfig,
+    news,
+    sound_bank::{GameSounds, SoundBank},
+    storage::Storage,
+};
+use bevy::{asset::UntypedAssetId, prelude::*};
+
+/// Loads the sound bank and every sound in it, and the ball kinds, up front
+/// behind a splash screen, so nothing is loaded mid-run and the first eat
+/// doesn't hitch. Assets that fail to load don't hold the game up; they're
+/// reported like any other missing asset.
+pub struct LoadingPlugin;
+
+#[derive(Component)]
+struct LoadingProgressText;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Loading),
+            (load_game_sounds, setup_loading_screen),
+        )
+        .add_systems(Update, finish_loading.run_if(in_state(GameState::Loading)));
+    }
+}
+
+fn load_game_sounds(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<GameConfig>,
+) {
+    commands.insert_resource(GameSounds(asset_server.load(&config.sound_bank)));
+}
+
+fn is_settled(asset_server: &AssetServer, id: impl Into<UntypedAssetId>) -> bool {
+    asset_server
+        .get_load_state(id)
+        .is_some_and(|state| state.is_loaded() || state.is_failed())
+}
+
+fn setup_loading_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(20.),
+                ..default()
+            },
+            StateScoped(GameState::Loading),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new("shape-eater"),
+                TextFont {
+                    font_size: 100.,
+                    ..default()
+                },
+            ));
+            builder.spawn((
+                LoadingProgressText,
+                Text::new("loading..."),
+                TextFont {
+                    font_size: 24.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn finish_loading(
+    sounds: Res<GameSounds>,
+    ball_kinds: Res<BallKindSource>,
+    banks: Res<Assets<SoundBank>>,
+    asset_server: Res<AssetServer>,
+    storage: Res<Storage>,
+    mut progress_query: Query<&mut Text, With<LoadingProgressText>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if !is_settled(&asset_server, &sounds.0) || !is_settled(&asset_server, &ball_kinds.0) {
+        return;
+    }
+    // A bank that failed to load has no sounds to wait for.
+    let handles: Vec<_> = banks
+        .get(&sounds.0)
+        .map_or(Vec::new(), |bank| bank.sounds().collect());
+    let settled = handles
+        .iter()
+        .filter(|handle| is_settled(&asset_server, **handle))
+        .count();
+
+    for mut text in progress_query.iter_mut() {
+        text.0 = format!("loading... {settled}/{}", handles.len());
+    }
+
+    if settled == handles.len() {
+        next_game_state.set(if news::has_unseen_news(&storage) {
+            GameState::News
+        } else {
+            GameState::Menu
+        });
+    }
+}
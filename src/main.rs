@@ -1,7 +1,163 @@
+use achievements::AchievementPlugin;
+use arena::ArenaLayout;
 use avian2d::prelude::*;
-use bevy::{prelude::*, time::Stopwatch, window::WindowMode};
-use rand::prelude::*;
-use std::mem::discriminant;
+use ball_kinds::{BallKindPlugin, BallKinds};
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::render::pipelined_rendering::PipelinedRenderingPlugin;
+use bevy::{
+    app::ScheduleRunnerPlugin,
+    ecs::schedule::ScheduleLabel,
+    input::InputSystem,
+    prelude::*,
+    render::{RenderPlugin, settings::WgpuSettings},
+    window::{ExitCondition, WindowMode},
+    winit::WinitPlugin,
+};
+use bug_report::BugReportPlugin;
+use chat::ChatPlugin;
+use checkpoints::{CheckpointFloor, CheckpointPlugin};
+use cli::LaunchOptions;
+use config::GameConfig;
+use confirm_quit::ConfirmQuitPlugin;
+use countdown::CountdownPlugin;
+use credits::CreditsPlugin;
+use cursor::CursorPlugin;
+use decorations::ArenaDecorationPlugin;
+use demo::{Demo, DemoPlugin, not_in_demo};
+use difficulty::{Difficulty, DifficultyPlugin};
+use eggs::EggPlugin;
+use errors::{ErrorReportingPlugin, GameError, report_errors};
+use frame::ArenaFramePlugin;
+use gates::GatePlugin;
+use governor::PerformanceGovernorPlugin;
+use gravity::{GravityDirection, GravityFlip, GravityPlugin};
+use haptics::HapticsPlugin;
+use heatmap::{HeatmapPlugin, PositionHeatmap, spawn_heatmap};
+use hints::HintPlugin;
+use hud::HudPlugin;
+use jelly::JellyPlugin;
+use labels::BallLabelPlugin;
+use layers::RenderLayer;
+use loading::LoadingPlugin;
+use menu::MainMenuPlugin;
+use mirror::MirrorPlugin;
+use modes::{GameModePlugin, GameModes};
+use new_game_plus::{NewGamePlus, NewGamePlusPlugin};
+use news::NewsPlugin;
+use number_words::{fit_to_ball, format_number};
+use parallax::ParallaxPlugin;
+use pause::PausePlugin;
+use photo::PhotoModePlugin;
+use popups::ScorePopupPlugin;
+use profiles::Profiles;
+use prompts::{MenuAction, MenuInput, Prompt, PromptPlugin};
+use rand::{prelude::*, rngs::StdRng};
+use rewind::RewindPlugin;
+use safe_area::SafeAreaPlugin;
+use saved_run::SavedRunPlugin;
+use scenario::{Scenario, ScenarioPlugin};
+use score::{Score, ScorePlugin, points};
+use score_table::{ScoreTable, ScoreTablePlugin, spawn_top_runs};
+use settings::{Settings, SettingsPlugin};
+use share_card::ShareCardPlugin;
+use shedding::{Fragment, SheddingPlugin};
+use shop::{ShopEffects, ShopPlugin};
+use siren::SirenPlugin;
+use skins::{Skin, SkinPlugin};
+use sonar::ThreatSonarPlugin;
+use sound_bank::{PlaySound, SoundBankPlugin, SoundEvent};
+use spatial::{BallGrid, SpatialGridPlugin};
+use speedrun::SpeedrunPlugin;
+use spin::BallSpinPlugin;
+use stats::{RunLog, RunModifier, RunStats, RunStatsPlugin};
+use std::{mem::discriminant, ops::RangeInclusive, time::Duration};
+use storage::Storage;
+use stress::StressTestPlugin;
+use surface::SurfacePlugin;
+use text_entry::{TextEntered, TextEntryPlugin, TextEntrySet, no_text_entry, spawn_text_entry};
+use timers::{AppGameTimersExt, GameTimers, GameTimersPlugin};
+use toast::{ShowToast, ToastPlugin};
+use trails::TrailPlugin;
+use transitions::{TransitionPlugin, not_covering};
+use tutorial::{Tutorial, TutorialPlugin, in_tutorial};
+use victory::VictoryPlugin;
+use weekly::{WeeklyPlaylist, WeeklyPlaylistPlugin};
+use wells::GravityWellPlugin;
+use xp::{Experience, XpPlugin, spawn_xp_summary};
+
+mod achievements;
+mod arena;
+mod ball_kinds;
+mod bug_report;
+mod chat;
+mod checkpoints;
+mod cli;
+mod config;
+mod confirm_quit;
+mod countdown;
+mod credits;
+mod cursor;
+mod decorations;
+mod demo;
+mod difficulty;
+mod eggs;
+mod errors;
+mod frame;
+mod gates;
+mod governor;
+mod gravity;
+mod haptics;
+mod heatmap;
+mod hints;
+mod hud;
+mod jelly;
+mod labels;
+mod layers;
+mod loading;
+mod menu;
+mod mirror;
+mod modes;
+mod new_game_plus;
+mod news;
+mod number_words;
+mod parallax;
+mod pause;
+mod photo;
+mod popups;
+mod profiles;
+mod prompts;
+mod rewind;
+mod safe_area;
+mod saved_run;
+mod scenario;
+mod score;
+mod score_table;
+mod settings;
+mod share_card;
+mod shedding;
+mod shop;
+mod siren;
+mod skins;
+mod sonar;
+mod sound_bank;
+mod spatial;
+mod speedrun;
+mod spin;
+mod stats;
+mod storage;
+mod stress;
+mod surface;
+mod text_entry;
+mod timers;
+mod toast;
+mod trails;
+mod transitions;
+mod tutorial;
+mod validate;
+mod victory;
+mod weekly;
+mod wells;
+mod xp;
 
 #[derive(Component)]
 struct Player;
@@ -12,51 +168,156 @@ struct Ball;
 #[derive(Component)]
 struct Wall;
 
+/// Part of a run, despawned by [`despawn_in_game_entities`] once the run is
+/// over. Screens are `StateScoped` instead, but a run outlives
+/// `GameState::InGame`: the victory screen shows over the arena.
 #[derive(Component)]
 struct InGameEntity;
 
-#[derive(Component)]
-struct DeathScreenEntity;
-
 #[derive(Component)]
 struct Numbered(i32);
 
 #[derive(Event)]
 struct MovementAction(i32);
 
-#[derive(Resource)]
-struct BallSpawnTimer(Timer);
+#[derive(Event)]
+struct BallEaten {
+    ball: Entity,
+    number: i32,
+    /// Where the player touched the ball, on its edge.
+    position: Vec2,
+    /// What it's worth, at the speed the player was going as they touched it,
+    /// with a premium for any growth it would have given past the size cap.
+    points: i32,
+}
+
+#[derive(Event)]
+struct WallHit {
+    speed: f32,
+}
 
+/// A hit that ends the run, unless a checkpoint catches it. `ball` is what
+/// hit the player, if anything did.
+#[derive(Event)]
+struct FatalHit {
+    ball: Option<Entity>,
+}
+
+/// Every gameplay random roll goes through this, so a run can be replayed by
+/// launching with the same `--seed`. Each run starts the rng over from its
+/// own seed, drawing a new one once the last has been played.
 #[derive(Resource)]
-struct WallBounceStopwatch(Stopwatch);
+struct GameRng {
+    seed: u64,
+    rng: StdRng,
+    /// Whether `seed` has yet to start a run.
+    fresh: bool,
+}
 
 #[derive(Component)]
 struct PlayerText;
 
+/// The best score for the kind of run being played, and where it's saved.
 #[derive(Resource)]
-struct HighScore(i32);
+struct HighScore {
+    score: i32,
+    key: String,
+    /// Who set it, if they typed their name in.
+    name: Option<String>,
+    /// Set by the run that just beat it, until its name is typed in.
+    awaiting_name: bool,
+}
+
+/// Where a new high score's name is typed in.
+#[derive(Component)]
+struct HighScoreNameEntry;
 
 #[derive(Resource)]
 struct CurrentScore(i32);
 
+#[derive(Component)]
+struct GoldenGlow {
+    remaining: f32,
+}
+
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 struct InGameSet;
 
+/// Moving the player, which unlike the rest of [`InGameSet`] also works
+/// during the countdown.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct PlayerControlSet;
+
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 struct DeathScreenSet;
 
-#[derive(States, Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(States, Debug, Clone, Default, PartialEq, Eq, Hash)]
 enum GameState {
+    #[default]
+    Loading,
+    News,
+    Menu,
     DeathScreen,
     InGame,
+    Victory,
+    HighScores,
+    Credits,
+    Difficulty,
+    ModeSelect,
+    Cosmetics,
+    Achievements,
+    /// Passed through on the way back into a fresh run, since setting
+    /// `InGame` again from inside it wouldn't rerun its `OnExit`/`OnEnter`.
+    Restarting,
+}
+
+/// Whether a run in progress is under way or held up. Only exists
+/// while in [`GameState::InGame`].
+#[derive(SubStates, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[source(GameState = GameState::InGame)]
+enum RunState {
+    /// Counting down before the run gets going, see [`countdown`].
+    #[default]
+    Countdown,
+    Playing,
+    Paused,
+    /// Playing the last few seconds back in reverse, see [`rewind`].
+    Rewinding,
+    /// Frozen with a free camera, see [`photo`].
+    Photo,
+    /// Between waves, see [`shop`].
+    Shop,
 }
 
+const BALL_SPAWN_TIMER: &str = "ball_spawn";
+const WALL_BOUNCE_STOPWATCH: &str = "wall_bounce";
+const GRAVITY_FLIP_STOPWATCH: &str = "gravity_flip";
+/// Points for every bit of growth past the size cap, on top of the ball's.
+const OVERFLOW_SCORE_RATE: i32 = 3;
+
 const STARTING_NUMBER: i32 = 15;
+const FIXED_TIMESTEP_HZ: f64 = 64.;
 const SIZE_FACTOR: f32 = 1.5;
 const FONT_SIZE_FACTOR: f32 = SIZE_FACTOR * 0.8;
 
+const SWARM_SIZE: RangeInclusive<usize> = 8..=15;
+const SWARM_NUMBERS: RangeInclusive<i32> = 1..=5;
+/// How far apart the balls of a swarm start.
+const SWARM_SPREAD: f32 = 40.;
+/// The most a swarm ball's heading strays from the swarm's, in radians.
+const SWARM_DIVERGENCE: f32 = 0.08;
+
+const GOLDEN_GLOW_DURATION: f32 = 0.6;
+const GRAVITY_FLIP_COOLDOWN: f32 = 0.5;
+/// Eating a ball at least this fraction of the player's number is a big eat,
+/// with a sound of its own.
+const BIG_EAT_FRACTION: f32 = 0.75;
+
 const BACKGROUND_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const PLAYER_COLOR: Color = Color::srgb(0., 0., 1.);
+const GOLDEN_GLOW_COLOR: Color = Color::srgb(1., 0.8, 0.2);
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Copy, Clone)]
 enum Bound {
     UpperBound,
@@ -82,195 +343,504 @@ impl Bound {
         }
     }
 
-    fn other_random(&self) -> Self {
-        let mut rng = rand::rng();
+    /// The area inside all four bounds.
+    fn arena() -> Rect {
+        Rect::new(
+            Bound::LeftBound.value(),
+            Bound::LowerBound.value(),
+            Bound::RightBound.value(),
+            Bound::UpperBound.value(),
+        )
+    }
+
+    fn other_random(&self, rng: &mut impl Rng) -> Self {
         let other_variants: Vec<Bound> = Self::VARIANTS
             .into_iter()
             .filter(|v| discriminant(v) != discriminant(self))
             .collect();
 
-        *other_variants.choose(&mut rng).unwrap()
+        *other_variants.choose(rng).unwrap()
     }
 
-    fn random() -> Self {
-        let mut rng = rand::rng();
-
-        *Self::VARIANTS.choose(&mut rng).unwrap()
+    fn random(rng: &mut impl Rng) -> Self {
+        *Self::VARIANTS.choose(rng).unwrap()
     }
 }
 
-fn main() {
-    App::new()
-        .add_plugins((
-            DefaultPlugins.set(WindowPlugin {
-                primary_window: Some(Window {
-                    resizable: false,
-                    mode: WindowMode::BorderlessFullscreen(MonitorSelection::Primary),
+fn main() -> AppExit {
+    let options = LaunchOptions::from_env();
+    if options.validate {
+        std::process::exit(validate::run(options.config.as_deref()));
+    }
+    let scenario = options.scenario.as_deref().map(|path| {
+        Scenario::load(path).unwrap_or_else(|error| {
+            eprintln!("{error}");
+            std::process::exit(2);
+        })
+    });
+    let config = match (&scenario, &options.config) {
+        (Some(scenario), _) => scenario.config.clone(),
+        (None, Some(path)) => GameConfig::load(path).unwrap_or_else(|error| {
+            eprintln!("{error}");
+            std::process::exit(2);
+        }),
+        (None, None) => GameConfig::default(),
+    };
+    let stress_balls = scenario
+        .as_ref()
+        .map_or(0, |scenario| scenario.stress_balls);
+    let seed = match &scenario {
+        Some(scenario) => scenario.seed,
+        None => options.seed.unwrap_or_else(rand::random),
+    };
+    // Scenarios mustn't depend on, or leave anything in, the player's data,
+    // not even which profile was played last.
+    let mut shared_storage = if scenario.is_some() {
+        Storage::in_memory()
+    } else {
+        Storage::for_platform(None)
+    };
+    let profiles = Profiles::select(options.profile.clone(), &mut shared_storage);
+    let storage = if scenario.is_some() {
+        Storage::in_memory()
+    } else {
+        profiles.storage()
+    };
+    let settings = Settings::load(&storage);
+    let window_mode = if options.windowed || !settings.fullscreen {
+        WindowMode::Windowed
+    } else {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Primary)
+    };
+
+    let mut default_plugins = DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            resizable: false,
+            mode: window_mode,
+            ..default()
+        }),
+        // Closing mid-run asks first, see `confirm_quit`.
+        close_when_requested: false,
+        ..default()
+    });
+    // Rendering a frame while the next one is simulated adds a frame of lag.
+    #[cfg(not(target_arch = "wasm32"))]
+    if config.low_latency_input {
+        default_plugins = default_plugins.disable::<PipelinedRenderingPlugin>();
+    }
+    // Scenarios run without a window or a GPU, as fast as they can.
+    if scenario.is_some() {
+        default_plugins = default_plugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .set(RenderPlugin {
+                render_creation: WgpuSettings {
+                    backends: None,
                     ..default()
-                }),
+                }
+                .into(),
                 ..default()
-            }),
-            PhysicsPlugins::default(),
+            })
+            .disable::<WinitPlugin>()
+            .add(ScheduleRunnerPlugin::run_loop(Duration::ZERO));
+    }
+    // Reading the movement keys before the fixed step runs, rather than after
+    // it like the rest of `Update`, lets them move the player the same frame.
+    let input_schedule = if config.low_latency_input {
+        PreUpdate.intern()
+    } else {
+        Update.intern()
+    };
+
+    App::new()
+        .add_plugins((
+            default_plugins,
+            PhysicsPlugins::default().set(PhysicsInterpolationPlugin::interpolate_all()),
+            BallLabelPlugin,
+            GatePlugin,
+            GravityPlugin,
+            SheddingPlugin,
+            SirenPlugin,
+            SpatialGridPlugin,
+            StressTestPlugin(stress_balls),
+            SurfacePlugin,
+            GameTimersPlugin,
+            ToastPlugin,
+        ))
+        .add_plugins((
+            ArenaFramePlugin,
+            ErrorReportingPlugin,
+            HapticsPlugin,
+            JellyPlugin,
+            MainMenuPlugin,
+            NewGamePlusPlugin,
+            NewsPlugin,
+            PerformanceGovernorPlugin,
+            PausePlugin,
+            PromptPlugin,
+            RunStatsPlugin,
+            ScoreTablePlugin,
+            ThreatSonarPlugin,
+            VictoryPlugin,
         ))
-        .insert_state(GameState::InGame)
-        .configure_sets(Update, InGameSet.run_if(in_state(GameState::InGame)))
+        .add_plugins((
+            ArenaDecorationPlugin,
+            CheckpointPlugin,
+            ConfirmQuitPlugin,
+            CountdownPlugin,
+            CreditsPlugin,
+            DemoPlugin,
+            GravityWellPlugin,
+            HintPlugin,
+            LoadingPlugin,
+            RewindPlugin,
+            ScorePopupPlugin,
+            SettingsPlugin,
+            TextEntryPlugin,
+            TutorialPlugin,
+            WeeklyPlaylistPlugin,
+        ))
+        .add_plugins((
+            AchievementPlugin,
+            BallKindPlugin,
+            BallSpinPlugin,
+            ChatPlugin,
+            CursorPlugin,
+            DifficultyPlugin,
+            GameModePlugin(options.mode),
+            HeatmapPlugin,
+            HudPlugin,
+            MirrorPlugin,
+            SavedRunPlugin,
+            ShareCardPlugin,
+            SpeedrunPlugin,
+            TrailPlugin,
+            TransitionPlugin,
+        ))
+        .add_plugins((
+            BugReportPlugin,
+            EggPlugin,
+            ParallaxPlugin,
+            PhotoModePlugin,
+            SafeAreaPlugin,
+            ScenarioPlugin(scenario),
+            ScorePlugin,
+            ShopPlugin,
+            SkinPlugin,
+            SoundBankPlugin,
+            XpPlugin,
+        ))
+        .init_state::<GameState>()
+        .add_sub_state::<RunState>()
+        .enable_state_scoped_entities::<GameState>()
+        .enable_state_scoped_entities::<RunState>()
+        .configure_sets(Update, InGameSet.run_if(in_state(RunState::Playing)))
+        .configure_sets(
+            FixedUpdate,
+            InGameSet.run_if(in_state(RunState::Playing).and(not_covering)),
+        )
+        .configure_sets(
+            Update,
+            PlayerControlSet.run_if(in_state(RunState::Playing).or(in_state(RunState::Countdown))),
+        )
+        .configure_sets(
+            PreUpdate,
+            PlayerControlSet.run_if(in_state(RunState::Playing).or(in_state(RunState::Countdown))),
+        )
+        .configure_sets(
+            FixedUpdate,
+            PlayerControlSet.run_if(in_state(RunState::Playing).or(in_state(RunState::Countdown))),
+        )
         .configure_sets(
             Update,
             DeathScreenSet.run_if(in_state(GameState::DeathScreen)),
         )
-        .insert_resource(Gravity(Vec2::NEG_Y * 1000.))
-        .insert_resource(HighScore(0))
+        .insert_resource(storage)
+        .insert_resource(profiles)
+        .insert_resource(settings)
+        .insert_resource(HighScore {
+            score: 0,
+            key: "high_score".to_string(),
+            name: None,
+            awaiting_name: false,
+        })
         .insert_resource(CurrentScore(0))
-        .insert_resource(Gravity(Vec2::NEG_Y * 1000.))
-        .insert_resource(BallSpawnTimer(Timer::from_seconds(
-            0.5,
-            TimerMode::Repeating,
-        )))
+        .add_game_timer(
+            BALL_SPAWN_TIMER,
+            Timer::from_seconds(config.ball_spawn_interval, TimerMode::Repeating),
+        )
+        .add_game_stopwatch(WALL_BOUNCE_STOPWATCH)
+        .add_game_stopwatch(GRAVITY_FLIP_STOPWATCH)
+        .insert_resource(GameRng::new(seed))
+        .insert_resource(ArenaLayout::new(config.arena))
+        .insert_resource(config)
+        .insert_resource(Time::<Fixed>::from_hz(FIXED_TIMESTEP_HZ))
         .insert_resource(ClearColor(BACKGROUND_COLOR))
-        .insert_resource(WallBounceStopwatch(Stopwatch::new()))
         .add_event::<MovementAction>()
+        .add_event::<BallEaten>()
+        .add_event::<WallHit>()
+        .add_event::<FatalHit>()
         .add_systems(Startup, set_camera)
-        .add_systems(OnEnter(GameState::InGame), setup_game)
-        .add_systems(OnExit(GameState::InGame), handle_game_over)
-        .add_systems(OnEnter(GameState::DeathScreen), setup_death_screen)
-        .add_systems(OnExit(GameState::DeathScreen), death_screen_exit)
-        .add_systems(Update, restart_game.in_set(DeathScreenSet))
+        .add_systems(
+            OnEnter(GameState::InGame),
+            (seed_run, (setup_game, load_high_score)).chain(),
+        )
+        .add_systems(
+            OnExit(GameState::InGame),
+            record_final_score.pipe(report_errors),
+        )
+        .add_systems(
+            OnEnter(GameState::DeathScreen),
+            (
+                despawn_in_game_entities,
+                handle_game_over,
+                setup_death_screen,
+            ),
+        )
+        .add_systems(
+            OnEnter(GameState::Restarting),
+            (despawn_in_game_entities, restart_run),
+        )
+        .add_systems(OnEnter(GameState::Menu), despawn_in_game_entities)
+        .add_systems(OnExit(GameState::Victory), despawn_in_game_entities)
+        .add_systems(
+            Update,
+            restart_game.in_set(DeathScreenSet).run_if(no_text_entry),
+        )
+        .add_systems(Update, save_high_score_name.after(TextEntrySet))
+        .add_systems(
+            input_schedule,
+            movement_input
+                .after(InputSystem)
+                .run_if(not_in_demo)
+                .in_set(PlayerControlSet),
+        )
         .add_systems(
             Update,
             (
-                tick_stopwatch,
-                keyboard_input,
                 change_gravity,
-                movement,
-                spawn_ball,
+                end_run_on_shrinking,
+                announce_beaten_high_score.run_if(recorded_run),
+                fade_golden_glow,
+            )
+                .in_set(InGameSet),
+        )
+        .add_systems(
+            FixedUpdate,
+            movement.pipe(report_errors).in_set(PlayerControlSet),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                spawn_ball.run_if(governor::has_ball_headroom.and(not(in_tutorial))),
                 despawn_out_of_bounds_balls,
-                handle_hits,
+                detect_hits,
+                (eat_balls, (resize_player, update_player_text))
+                    .chain()
+                    .after(detect_hits),
+                play_hit_sounds.after(detect_hits).before(eat_balls),
+                end_run_on_fatal_hit
+                    .after(detect_hits)
+                    .run_if(not(in_tutorial)),
             )
                 .in_set(InGameSet),
         )
-        .run();
+        .run()
+}
+
+/// Run condition for runs that count towards scores, hints and unlocks, which
+/// demo and tutorial runs don't.
+fn recorded_run(demo: Res<Demo>, tutorial: Res<Tutorial>) -> bool {
+    !demo.running && !tutorial.running
+}
+
+/// Run condition for leaving [`GameState::InGame`] because the run ended, on
+/// the death or victory screen, rather than being quit, restarted or saved
+/// for later. The state has already moved on by the time `OnExit` runs.
+fn finished_run(game_state: Res<State<GameState>>) -> bool {
+    matches!(
+        game_state.get(),
+        GameState::DeathScreen | GameState::Victory
+    )
 }
 
 fn set_camera(mut commands: Commands) {
     commands.spawn(Camera2d);
 }
 
+impl GameRng {
+    fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            fresh: true,
+        }
+    }
+}
+
+/// Runs that don't count, demos and the tutorial, leave a fresh seed for the
+/// next one.
+fn seed_run(mut game_rng: ResMut<GameRng>, demo: Res<Demo>, tutorial: Res<Tutorial>) {
+    let seed = if game_rng.fresh {
+        game_rng.seed
+    } else {
+        game_rng.rng.random()
+    };
+    *game_rng = GameRng {
+        fresh: demo.running || tutorial.running,
+        ..GameRng::new(seed)
+    };
+    info!("seed: {seed}");
+}
+
+/// Modes and difficulties other than Classic and Normal keep high scores of
+/// their own, under the key for the run's kind with theirs added on.
+fn load_high_score(
+    storage: Res<Storage>,
+    new_game_plus: Res<NewGamePlus>,
+    weekly_playlist: Res<WeeklyPlaylist>,
+    modes: Res<GameModes>,
+    difficulty: Res<Difficulty>,
+    mut high_score: ResMut<HighScore>,
+) {
+    let mut key = weekly_playlist
+        .high_score_key()
+        .unwrap_or_else(|| new_game_plus.high_score_key().to_string());
+    let mode = modes.selected().name();
+    if mode != "Classic" {
+        key = format!("{key}_{}", mode.to_lowercase());
+    }
+    if *difficulty != Difficulty::Normal {
+        key = format!("{key}_{}", difficulty.to_string().to_lowercase());
+    }
+    *high_score = HighScore {
+        score: storage.load(&key).unwrap_or(0),
+        name: storage
+            .load::<String>(&HighScore::name_key(&key))
+            .filter(|name| !name.is_empty()),
+        key,
+        awaiting_name: false,
+    };
+}
+
 fn setup_game(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    config: Res<GameConfig>,
+    layout: Res<ArenaLayout>,
+    skin: Res<Skin>,
 ) {
-    let walls = [
-        ((1880., 20.), (0., Bound::UpperBound.value())),
-        ((1880., 20.), (0., Bound::LowerBound.value())),
-        ((20., 1020.), (Bound::LeftBound.value(), 0.)),
-        ((20., 1020.), (Bound::RightBound.value(), 0.)),
-    ];
-
-    for (size, transform) in walls {
-        commands.spawn((
+    for wall in &layout.walls {
+        let mut wall_entity = commands.spawn((
             Wall,
             InGameEntity,
             Sprite {
-                color: Color::srgb(0.0, 0.4, 0.7),
-                custom_size: Some(Vec2::new(size.0, size.1)),
+                color: wall.surface.color(),
+                custom_size: Some(wall.size),
                 ..default()
             },
-            Transform::from_xyz(transform.0, transform.1, 100.),
+            Transform::from_translation(RenderLayer::Walls.at(wall.position)),
             RigidBody::Static,
-            Collider::rectangle(size.0, size.1),
-            Restitution::PERFECTLY_ELASTIC,
+            Collider::rectangle(wall.size.x, wall.size.y),
+            wall.surface.restitution(),
+            wall.surface.friction(),
         ));
-    }
-
-    let covers = [
-        ((10_000., 200.), (0., Bound::UpperBound.value() + 110.)),
-        ((10_000., 200.), (0., Bound::LowerBound.value() - 110.)),
-        ((200., 10_000.), (Bound::LeftBound.value() - 110., 0.)),
-        ((200., 10_000.), (Bound::RightBound.value() + 110., 0.)),
-    ];
 
-    for (size, transform) in covers {
-        commands.spawn((
-            Sprite {
-                color: BACKGROUND_COLOR,
-                custom_size: Some(Vec2::new(size.0, size.1)),
-                ..default()
-            },
-            Transform::from_xyz(transform.0, transform.1, 99.),
-            InGameEntity,
-        ));
+        if let Some(conveyor) = wall.surface.conveyor(wall.size) {
+            wall_entity.insert((conveyor, CollidingEntities::default()));
+        }
     }
 
+    let spawn = layout.player_spawn();
+
     commands
         .spawn((
             Player,
             InGameEntity,
             CollidingEntities::default(),
-            Numbered(STARTING_NUMBER),
+            Numbered(config.starting_number),
             Mesh2d(meshes.add(Rectangle::new(
-                STARTING_NUMBER as f32 * SIZE_FACTOR,
-                STARTING_NUMBER as f32 * SIZE_FACTOR,
+                config.starting_number as f32 * SIZE_FACTOR,
+                config.starting_number as f32 * SIZE_FACTOR,
             ))),
-            MeshMaterial2d(materials.add(Color::srgb(0., 0., 1.))),
-            Transform::from_xyz(200., 0., 0.),
+            MeshMaterial2d(materials.add(skin.color())),
+            skin.shape(),
+            Transform::from_translation(RenderLayer::Player.at(spawn.position))
+                .with_rotation(spawn.rotation()),
             RigidBody::Dynamic,
             Restitution::new(0.9),
             Collider::rectangle(
-                STARTING_NUMBER as f32 * SIZE_FACTOR,
-                STARTING_NUMBER as f32 * SIZE_FACTOR,
+                config.starting_number as f32 * SIZE_FACTOR,
+                config.starting_number as f32 * SIZE_FACTOR,
             ),
         ))
         .with_children(|builder| {
+            let text = format_number(config.starting_number, &config);
             builder.spawn((
                 PlayerText,
-                Text2d::new(STARTING_NUMBER.to_string()),
                 TextFont {
-                    font_size: STARTING_NUMBER as f32 * FONT_SIZE_FACTOR,
+                    font_size: config.starting_number as f32
+                        * FONT_SIZE_FACTOR
+                        * fit_to_ball(&text),
                     ..default()
                 },
+                Text2d::new(text),
             ));
         });
 }
 
-fn handle_game_over(
+fn record_final_score(
+    player_query: Query<(), With<Player>>,
+    mut current_score: ResMut<CurrentScore>,
+    score: Res<Score>,
+) -> Result<(), GameError> {
+    player_query
+        .get_single()
+        .map_err(|_| GameError::MissingEntity("player"))?;
+    current_score.0 = score.0;
+    Ok(())
+}
+
+/// Runs end on the death screen, on leaving the victory screen, on a restart,
+/// or on quitting to the menu from the pause menu.
+fn despawn_in_game_entities(
     mut commands: Commands,
     in_game_entities: Query<Entity, With<InGameEntity>>,
-    asset_server: Res<AssetServer>,
-    player_score_query: Query<&Numbered, With<Player>>,
-    mut current_score: ResMut<CurrentScore>,
 ) {
-    let Numbered(player_score) = player_score_query.single();
-    current_score.0 = *player_score;
-
-    commands.spawn((
-        AudioPlayer::new(asset_server.load("sounds/game_over.ogg")),
-        InGameEntity,
-    ));
     for entity in in_game_entities.iter() {
         commands.entity(entity).despawn_recursive();
     }
 }
 
-fn death_screen_exit(
-    mut commands: Commands,
-    death_screen_entities: Query<Entity, With<DeathScreenEntity>>,
-) {
-    for entity in death_screen_entities.iter() {
-        commands.entity(entity).despawn_recursive();
-    }
+fn handle_game_over(mut play_writer: EventWriter<PlaySound>) {
+    play_writer.send(PlaySound(SoundEvent::GameOver));
 }
 
+fn restart_run(mut next_game_state: ResMut<NextState<GameState>>) {
+    next_game_state.set(GameState::InGame);
+}
+
+#[allow(clippy::too_many_arguments)]
 fn setup_death_screen(
     mut commands: Commands,
     current_score: ResMut<CurrentScore>,
     mut high_score: ResMut<HighScore>,
+    mut storage: ResMut<Storage>,
+    run_log: Res<RunLog>,
+    stats: Res<RunStats>,
+    heatmap: Res<PositionHeatmap>,
+    score_table: Res<ScoreTable>,
+    experience: Res<Experience>,
 ) {
-    let high_score_text = if current_score.0 > high_score.0 {
-        high_score.0 = current_score.0;
-        "new high score!".to_string()
+    let new_high_score = record_high_score(current_score.0, &mut high_score, &mut storage);
+    let high_score_text = if new_high_score {
+        "new high score! enter your name".to_string()
     } else {
-        format!("high score - {}", high_score.0)
+        high_score.describe()
     };
 
     commands
@@ -280,20 +850,18 @@ fn setup_death_screen(
                 height: Val::Percent(100.0),
                 align_items: AlignItems::Center,
                 justify_content: JustifyContent::Center,
+                column_gap: Val::Px(60.),
                 ..default()
             },
-            DeathScreenEntity,
+            StateScoped(GameState::DeathScreen),
         ))
         .with_children(|builder| {
             builder
-                .spawn((
-                    Node {
-                        flex_direction: FlexDirection::Column,
-                        align_items: AlignItems::Center,
-                        ..default()
-                    },
-                    DeathScreenEntity,
-                ))
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
                 .with_children(|builder| {
                     builder.spawn((
                         Text::new("Game over"),
@@ -301,7 +869,6 @@ fn setup_death_screen(
                             font_size: 100.,
                             ..default()
                         },
-                        DeathScreenEntity,
                     ));
                     builder.spawn((
                         Text::new(format!("score - {}", current_score.0)),
@@ -309,41 +876,130 @@ fn setup_death_screen(
                             font_size: 30.,
                             ..default()
                         },
-                        DeathScreenEntity,
                     ));
+                    builder.spawn((
+                        Text::new(format!("size - {}", stats.final_number)),
+                        TextFont {
+                            font_size: 30.,
+                            ..default()
+                        },
+                    ));
+                    for line in stats.breakdown() {
+                        builder.spawn((
+                            Text::new(line),
+                            TextFont {
+                                font_size: 24.,
+                                ..default()
+                            },
+                        ));
+                    }
                     builder.spawn((
                         Text::new(high_score_text),
                         TextFont {
                             font_size: 30.,
                             ..default()
                         },
-                        DeathScreenEntity,
                     ));
+                    if new_high_score || score_table.awaiting_name() {
+                        spawn_text_entry(
+                            builder,
+                            HighScoreNameEntry,
+                            NAME_CHARSET,
+                            MAX_NAME_LENGTH,
+                        );
+                    }
+                    spawn_xp_summary(builder, &experience);
+                    builder.spawn((
+                        Text::default(),
+                        Prompt(vec![
+                            (MenuAction::PlayAgain, "play again"),
+                            (MenuAction::HighScores, "high scores"),
+                            (MenuAction::ShareCard, "share card"),
+                        ]),
+                        TextFont {
+                            font_size: 20.,
+                            ..default()
+                        },
+                    ));
+                    spawn_heatmap(builder, &heatmap);
+                    for line in run_log.summary_lines() {
+                        builder.spawn((
+                            Text::new(line),
+                            TextFont {
+                                font_size: 18.,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                        ));
+                    }
                 });
+            spawn_top_runs(builder, &score_table);
         });
 }
 
-fn restart_game(
-    keys: Res<ButtonInput<KeyCode>>,
-    mut next_game_state: ResMut<NextState<GameState>>,
-) {
-    if keys.just_pressed(KeyCode::KeyR) {
-        next_game_state.set(GameState::InGame);
+/// Saves `score` as the high score if it beats it, returning whether it did.
+fn record_high_score(score: i32, high_score: &mut HighScore, storage: &mut Storage) -> bool {
+    if score <= high_score.score {
+        return false;
     }
+
+    high_score.score = score;
+    high_score.name = None;
+    high_score.awaiting_name = true;
+    storage.save(&high_score.key, score);
+    storage.save(&HighScore::name_key(&high_score.key), "");
+    true
 }
 
-fn tick_stopwatch(mut stopwatch: ResMut<WallBounceStopwatch>, time: Res<Time>) {
-    stopwatch.0.tick(time.delta());
+impl HighScore {
+    fn name_key(key: &str) -> String {
+        format!("{key}_name")
+    }
+
+    /// What the end-of-run screens say about the high score when the run
+    /// didn't beat it.
+    fn describe(&self) -> String {
+        match &self.name {
+            Some(name) => format!("high score - {} by {name}", self.score),
+            None => format!("high score - {}", self.score),
+        }
+    }
 }
 
-fn keyboard_input(
-    keys: Res<ButtonInput<KeyCode>>,
-    mut movement_event_writer: EventWriter<MovementAction>,
+/// The letters a high score's name can use.
+const NAME_CHARSET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+const MAX_NAME_LENGTH: usize = 8;
+
+fn save_high_score_name(
+    mut entered_reader: EventReader<TextEntered>,
+    name_entry_query: Query<(), With<HighScoreNameEntry>>,
+    mut high_score: ResMut<HighScore>,
+    mut storage: ResMut<Storage>,
 ) {
-    if keys.pressed(KeyCode::KeyD) {
+    for TextEntered { entry, text } in entered_reader.read() {
+        if !name_entry_query.contains(*entry) || text.is_empty() || !high_score.awaiting_name {
+            continue;
+        }
+
+        high_score.awaiting_name = false;
+        high_score.name = Some(text.clone());
+        storage.save(&HighScore::name_key(&high_score.key), text);
+    }
+}
+
+fn restart_game(menu_input: MenuInput, mut next_game_state: ResMut<NextState<GameState>>) {
+    if menu_input.just_pressed(MenuAction::PlayAgain) {
+        next_game_state.set(GameState::InGame);
+    } else if menu_input.just_pressed(MenuAction::HighScores) {
+        next_game_state.set(GameState::HighScores);
+    }
+}
+
+fn movement_input(menu_input: MenuInput, mut movement_event_writer: EventWriter<MovementAction>) {
+    if menu_input.pressed(MenuAction::MoveRight) {
         movement_event_writer.send(MovementAction(1));
     }
-    if keys.pressed(KeyCode::KeyA) {
+    if menu_input.pressed(MenuAction::MoveLeft) {
         movement_event_writer.send(MovementAction(-1));
     }
 }
@@ -352,33 +1008,103 @@ fn movement(
     mut movement_event_reader: EventReader<MovementAction>,
     time: Res<Time>,
     mut player_query: Query<&mut LinearVelocity, With<Player>>,
-) {
+) -> Result<(), GameError> {
     let delta_time = time.delta_secs();
-    let mut player_velocity = player_query.single_mut();
+    let mut player_velocity = player_query
+        .get_single_mut()
+        .map_err(|_| GameError::MissingEntity("player"))?;
 
     for MovementAction(direction) in movement_event_reader.read() {
         player_velocity.x = 10_000. * delta_time * *direction as f32;
     }
+    Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_ball(
-    mut ball_spawn_timer: ResMut<BallSpawnTimer>,
-    time: Res<Time>,
+    game_timers: Res<GameTimers>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    mut game_rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    new_game_plus: Res<NewGamePlus>,
+    difficulty: Res<Difficulty>,
+    ball_kinds: Res<BallKinds>,
 ) {
-    if !ball_spawn_timer.0.tick(time.delta()).just_finished() {
+    if !game_timers.just_finished(BALL_SPAWN_TIMER) {
         return;
     }
-    let mut rng = rand::rng();
-    let number = rng.random_range((1 as i32)..100);
+    let rng = &mut game_rng.rng;
+    let ball_speed = new_game_plus.ball_speed(&config) * difficulty.ball_speed_multiplier();
 
-    let bound = Bound::random();
-    let starting_point = random_point_on_bound(bound);
-    let target = random_point_on_bound(bound.other_random());
+    let bound = Bound::random(rng);
+    let starting_point = random_point_on_bound(bound, rng);
+    let target = random_point_on_bound(bound.other_random(rng), rng);
     let movement_direction = (target - starting_point).normalize();
 
+    if rng.random_bool(config.swarm_chance.clamp(0., 1.)) {
+        spawn_swarm(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            rng,
+            starting_point,
+            movement_direction * ball_speed,
+        );
+        return;
+    }
+
+    let number = new_game_plus.ball_number(difficulty.ball_number(rng), &config);
+    let ball = spawn_numbered_ball(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        number,
+        starting_point,
+        movement_direction * ball_speed,
+    );
+    if let Some(kind) = ball_kinds.random(rng) {
+        kind.apply(&mut commands.entity(ball), &mut materials);
+    }
+}
+
+/// A cluster of tiny balls heading the same way, drifting apart as they go.
+/// They start scattered just inside the bound `starting_point` is on.
+fn spawn_swarm(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    rng: &mut impl Rng,
+    starting_point: Vec2,
+    velocity: Vec2,
+) {
+    let direction = velocity.normalize();
+    for _ in 0..rng.random_range(SWARM_SIZE) {
+        let offset = direction * rng.random_range(0. ..SWARM_SPREAD)
+            + direction.perp() * rng.random_range(-SWARM_SPREAD..SWARM_SPREAD);
+        let position = (starting_point + offset).clamp(Bound::arena().min, Bound::arena().max);
+        let heading = Vec2::from_angle(rng.random_range(-SWARM_DIVERGENCE..SWARM_DIVERGENCE));
+
+        spawn_numbered_ball(
+            commands,
+            meshes,
+            materials,
+            rng.random_range(SWARM_NUMBERS),
+            position,
+            heading.rotate(velocity),
+        );
+    }
+}
+
+fn spawn_numbered_ball(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    number: i32,
+    position: Vec2,
+    velocity: Vec2,
+) -> Entity {
     commands
         .spawn((
             Numbered(number),
@@ -386,87 +1112,327 @@ fn spawn_ball(
             InGameEntity,
             Mesh2d(meshes.add(Circle::new(number as f32 * SIZE_FACTOR / 2.))),
             MeshMaterial2d(materials.add(Color::srgb(1., 0., 0.))),
-            Transform::from_translation(starting_point.extend(0.)),
+            Transform::from_translation(RenderLayer::Balls.at(position)),
             RigidBody::Kinematic,
-            LinearVelocity(movement_direction * 100.),
+            LinearVelocity(velocity),
             Collider::circle(number as f32 * SIZE_FACTOR / 2.),
         ))
-        .with_children(|builder| {
-            builder.spawn((
-                Text2d::new(number.to_string()),
-                TextFont {
-                    font_size: number as f32 * FONT_SIZE_FACTOR / 2.,
-                    ..default()
-                },
-            ));
-        });
+        .id()
 }
 
-fn handle_hits(
-    mut player_query: Query<
-        (
-            &LinearVelocity,
-            &CollidingEntities,
-            &mut Numbered,
-            &mut Collider,
-            &mut Mesh2d,
-        ),
-        With<Player>,
-    >,
-    mut text_query: Query<(&mut Text2d, &mut TextFont), With<PlayerText>>,
-    ball_query: Query<&Numbered, (With<Ball>, Without<Player>)>,
+#[allow(clippy::type_complexity)]
+fn detect_hits(
+    player_query: Query<(&LinearVelocity, &CollidingEntities, &Numbered, &Transform), With<Player>>,
+    ball_query: Query<(&Numbered, &Transform, Option<&Fragment>), (With<Ball>, Without<Player>)>,
     wall_query: Query<&Wall>,
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    asset_server: Res<AssetServer>,
-    mut wall_bounce_stopwatch: ResMut<WallBounceStopwatch>,
-    mut next_game_state: ResMut<NextState<GameState>>,
+    config: Res<GameConfig>,
+    mut ball_eaten_writer: EventWriter<BallEaten>,
+    mut wall_hit_writer: EventWriter<WallHit>,
+    mut fatal_hit_writer: EventWriter<FatalHit>,
 ) {
-    for (player_velocity, hits, mut player_number, mut player_collider, mut player_mesh) in
-        player_query.iter_mut()
-    {
+    for (player_velocity, hits, Numbered(player_number), player_transform) in player_query.iter() {
+        // Eats earlier in the same tick count towards the later comparisons.
+        let mut player_number = *player_number;
+
         for hit_entity in hits.iter() {
-            if let Ok(Numbered(ball_number)) = ball_query.get(*hit_entity) {
-                if *ball_number > player_number.0 {
-                    next_game_state.set(GameState::DeathScreen);
+            if let Ok((Numbered(ball_number), ball_transform, fragment)) =
+                ball_query.get(*hit_entity)
+            {
+                if fragment.is_some_and(|fragment| !fragment.is_collectible()) {
+                    continue;
+                }
+                if *ball_number > player_number {
+                    fatal_hit_writer.send(FatalHit {
+                        ball: Some(*hit_entity),
+                    });
                     return;
                 }
 
-                let player_number_change = (*ball_number as f32 / 5.).ceil() as i32;
-
-                commands.entity(*hit_entity).despawn_recursive();
-                player_number.0 += player_number_change;
-
-                let new_size = player_number.0 as f32 * SIZE_FACTOR;
-                player_mesh.0 = meshes.add(Rectangle::new(new_size, new_size));
-                *player_collider = Collider::rectangle(new_size, new_size);
-
-                let (mut child_text, mut child_text_font) = text_query.single_mut();
-                child_text.0 = player_number.0.to_string();
-                child_text_font.font_size = player_number.0 as f32 * FONT_SIZE_FACTOR;
-                commands.spawn((
-                    AudioPlayer::new(asset_server.load("sounds/ball_eaten.ogg")),
-                    InGameEntity,
-                ));
-            } else if let Ok(_) = wall_query.get(*hit_entity) {
-                if player_velocity.length() > 30.
-                    && wall_bounce_stopwatch.0.elapsed_secs_f64() > 0.1
-                {
-                    wall_bounce_stopwatch.0.reset();
-                    commands.spawn((
-                        AudioPlayer::new(asset_server.load("sounds/wall_bounce.ogg")),
-                        InGameEntity,
-                    ));
+                let overflow;
+                (player_number, overflow) = grow(player_number, *ball_number, config.max_number);
+                let ball_position = ball_transform.translation.truncate();
+                let towards_player =
+                    (player_transform.translation.truncate() - ball_position).normalize_or_zero();
+                ball_eaten_writer.send(BallEaten {
+                    ball: *hit_entity,
+                    number: *ball_number,
+                    position: ball_position
+                        + towards_player * *ball_number as f32 * SIZE_FACTOR / 2.,
+                    points: points(*ball_number, player_velocity.length())
+                        + overflow * OVERFLOW_SCORE_RATE,
+                });
+            } else if wall_query.get(*hit_entity).is_ok() {
+                wall_hit_writer.send(WallHit {
+                    speed: player_velocity.length(),
+                });
+            }
+        }
+    }
+}
+
+/// How much eating a ball grows the player by, before the size cap.
+fn growth(ball_number: i32) -> i32 {
+    (ball_number as f32 / 5.).ceil() as i32
+}
+
+/// The player's number after eating a ball, and how much of the growth went
+/// over the size cap.
+fn grow(player_number: i32, ball_number: i32, max_number: Option<i32>) -> (i32, i32) {
+    let grown = player_number + growth(ball_number);
+
+    match max_number {
+        Some(max_number) if grown > max_number => {
+            let capped = max_number.max(player_number);
+            (capped, grown - capped)
+        }
+        _ => (grown, 0),
+    }
+}
+
+fn eat_balls(
+    mut ball_eaten_reader: EventReader<BallEaten>,
+    mut player_query: Query<(Entity, &mut Numbered), With<Player>>,
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut modifier_writer: EventWriter<RunModifier>,
+) {
+    let Ok((player, mut player_number)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    for BallEaten { ball, number, .. } in ball_eaten_reader.read() {
+        if let Some(ball) = commands.get_entity(*ball) {
+            ball.despawn_recursive();
+        }
+
+        let (grown, overflow) = grow(player_number.0, *number, config.max_number);
+        if config
+            .max_number
+            .is_some_and(|max_number| player_number.0 < max_number && grown >= max_number)
+        {
+            modifier_writer.send(RunModifier("reached the size cap".to_string()));
+        }
+        player_number.0 = grown;
+        if overflow > 0 {
+            commands.entity(player).insert(GoldenGlow {
+                remaining: GOLDEN_GLOW_DURATION,
+            });
+        }
+    }
+}
+
+/// Only the collider; the jelly outline is redrawn at the new size by itself.
+#[allow(clippy::type_complexity)]
+fn resize_player(
+    mut player_query: Query<(&Numbered, &mut Collider), (With<Player>, Changed<Numbered>)>,
+) {
+    for (Numbered(player_number), mut player_collider) in player_query.iter_mut() {
+        let new_size = *player_number as f32 * SIZE_FACTOR;
+        *player_collider = Collider::rectangle(new_size, new_size);
+    }
+}
+
+fn fade_golden_glow(
+    mut commands: Commands,
+    mut glow_query: Query<(Entity, &mut GoldenGlow, &MeshMaterial2d<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    skin: Res<Skin>,
+    time: Res<Time>,
+) {
+    for (entity, mut glow, material) in glow_query.iter_mut() {
+        glow.remaining -= time.delta_secs();
+
+        let Some(material) = materials.get_mut(&material.0) else {
+            continue;
+        };
+        if glow.remaining <= 0. {
+            material.color = skin.color();
+            commands.entity(entity).remove::<GoldenGlow>();
+        } else {
+            let glow_amount = glow.remaining / GOLDEN_GLOW_DURATION;
+            material.color = skin.color().mix(&GOLDEN_GLOW_COLOR, glow_amount);
+        }
+    }
+}
+
+fn update_player_text(
+    player_query: Query<&Numbered, (With<Player>, Changed<Numbered>)>,
+    mut text_query: Query<(&mut Text2d, &mut TextFont), With<PlayerText>>,
+    config: Res<GameConfig>,
+) {
+    let Ok(Numbered(player_number)) = player_query.get_single() else {
+        return;
+    };
+
+    for (mut text, mut text_font) in text_query.iter_mut() {
+        text.0 = format_number(*player_number, &config);
+        text_font.font_size = *player_number as f32 * FONT_SIZE_FACTOR * fit_to_ball(&text.0);
+    }
+}
+
+fn play_hit_sounds(
+    mut ball_eaten_reader: EventReader<BallEaten>,
+    mut wall_hit_reader: EventReader<WallHit>,
+    mut fatal_hit_reader: EventReader<FatalHit>,
+    player_query: Query<&Numbered, With<Player>>,
+    mut play_writer: EventWriter<PlaySound>,
+    mut game_timers: ResMut<GameTimers>,
+) {
+    let player_number = player_query
+        .get_single()
+        .map_or(0, |Numbered(number)| *number);
+
+    for BallEaten { number, .. } in ball_eaten_reader.read() {
+        play_writer.send(PlaySound(
+            if *number as f32 >= player_number as f32 * BIG_EAT_FRACTION {
+                SoundEvent::BigEat
+            } else {
+                SoundEvent::Eat
+            },
+        ));
+    }
+
+    for WallHit { speed } in wall_hit_reader.read() {
+        if *speed > 30. && game_timers.elapsed_secs(WALL_BOUNCE_STOPWATCH) > 0.1 {
+            game_timers.reset_stopwatch(WALL_BOUNCE_STOPWATCH);
+            play_writer.send(PlaySound(SoundEvent::WallBounce));
+        }
+    }
+
+    for _ in fatal_hit_reader.read() {
+        play_writer.send(PlaySound(SoundEvent::Hit));
+    }
+}
+
+/// A ball that hits a player above their checkpoint floor knocks them back
+/// down to it, and back to the arena's safest spawn point, and is destroyed,
+/// instead of ending the run. A shield from the shop does the same without
+/// the knock back. On Easy any hit just shrinks the player, and in modes
+/// where hits aren't fatal the ball is all that goes. Demo runs end straight
+/// back on the menu.
+#[allow(clippy::too_many_arguments)]
+fn end_run_on_fatal_hit(
+    mut commands: Commands,
+    mut fatal_hit_reader: EventReader<FatalHit>,
+    mut player_query: Query<(&mut Numbered, &mut Transform, &mut LinearVelocity), With<Player>>,
+    floor: Res<CheckpointFloor>,
+    layout: Res<ArenaLayout>,
+    grid: Res<BallGrid>,
+    difficulty: Res<Difficulty>,
+    new_game_plus: Res<NewGamePlus>,
+    config: Res<GameConfig>,
+    modes: Res<GameModes>,
+    demo: Res<Demo>,
+    mut shop_effects: ResMut<ShopEffects>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    for FatalHit { ball } in fatal_hit_reader.read() {
+        let floor = floor.0.filter(|_| difficulty.checkpoints_catch_hits());
+        let caught = match (ball, floor, player_query.get_single_mut()) {
+            (Some(ball), _, _) if !modes.selected().hits_are_fatal() => {
+                commands.entity(*ball).despawn_recursive();
+                true
+            }
+            (Some(ball), _, _) if shop_effects.shield => {
+                shop_effects.shield = false;
+                commands.entity(*ball).despawn_recursive();
+                toast_writer.send(ShowToast("Shield blocked the hit".to_string()));
+                true
+            }
+            (Some(ball), Some(floor), Ok((mut player_number, mut transform, mut velocity)))
+                if player_number.0 > floor =>
+            {
+                player_number.0 = floor;
+                let spawn = layout.safest_spawn(&grid, floor);
+                transform.translation = RenderLayer::Player.at(spawn.position);
+                transform.rotation = spawn.rotation();
+                velocity.0 = Vec2::ZERO;
+                commands.entity(*ball).despawn_recursive();
+                toast_writer.send(ShowToast(format!("Back to checkpoint {floor}")));
+                true
+            }
+            (Some(ball), _, Ok((mut player_number, ..))) => {
+                match difficulty.shrink_on_hit(player_number.0) {
+                    Some(shrunk) => {
+                        player_number.0 =
+                            new_game_plus.shrink_on_hit(player_number.0, shrunk, &config);
+                        commands.entity(*ball).despawn_recursive();
+                        true
+                    }
+                    None => false,
                 }
             }
+            _ => false,
+        };
+
+        if !caught {
+            next_game_state.set(if demo.running {
+                GameState::Menu
+            } else {
+                GameState::DeathScreen
+            });
+            return;
         }
     }
 }
 
-fn change_gravity(mut gravity: ResMut<Gravity>, keys: Res<ButtonInput<KeyCode>>) {
-    if keys.just_pressed(KeyCode::Space) {
-        gravity.0 = gravity.0 * -1.;
+/// Counts as a fatal hit, so the run ends the same way.
+fn end_run_on_shrinking(
+    player_query: Query<&Numbered, (With<Player>, Changed<Numbered>)>,
+    config: Res<GameConfig>,
+    mut fatal_hit_writer: EventWriter<FatalHit>,
+) {
+    let Some(lose_number) = config.lose_number else {
+        return;
+    };
+
+    if player_query
+        .iter()
+        .any(|Numbered(player_number)| *player_number <= lose_number)
+    {
+        fatal_hit_writer.send(FatalHit { ball: None });
+    }
+}
+
+fn announce_beaten_high_score(
+    player_query: Query<&Numbered, (With<Player>, Changed<Numbered>)>,
+    high_score: Res<HighScore>,
+    mut previous_number: Local<i32>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    let Ok(Numbered(player_number)) = player_query.get_single() else {
+        return;
+    };
+
+    if high_score.score > 0
+        && *previous_number <= high_score.score
+        && *player_number > high_score.score
+    {
+        toast_writer.send(ShowToast("New high score!".to_string()));
     }
+    *previous_number = *player_number;
+}
+
+fn change_gravity(
+    mut direction: ResMut<GravityDirection>,
+    menu_input: MenuInput,
+    mut game_timers: ResMut<GameTimers>,
+    mut flip_writer: EventWriter<GravityFlip>,
+) {
+    if !menu_input.just_pressed(MenuAction::FlipGravity) {
+        return;
+    }
+
+    let blocked = game_timers.elapsed_secs(GRAVITY_FLIP_STOPWATCH) < GRAVITY_FLIP_COOLDOWN;
+    if !blocked {
+        game_timers.reset_stopwatch(GRAVITY_FLIP_STOPWATCH);
+        direction.0 = -direction.0;
+    }
+    flip_writer.send(GravityFlip {
+        direction: direction.0,
+        blocked,
+    });
 }
 
 fn despawn_out_of_bounds_balls(
@@ -480,9 +1446,7 @@ fn despawn_out_of_bounds_balls(
     }
 }
 
-fn random_point_on_bound(bound: Bound) -> Vec2 {
-    let mut rng = rand::rng();
-
+fn random_point_on_bound(bound: Bound, rng: &mut impl Rng) -> Vec2 {
     match bound {
         Bound::UpperBound | Bound::LowerBound => Vec2::new(
             rng.random_range(Bound::LeftBound.value()..Bound::RightBound.value()),
@@ -496,8 +1460,8 @@ fn random_point_on_bound(bound: Bound) -> Vec2 {
 }
 
 fn is_out_of_bounds(point: Vec2) -> bool {
-    return point.x < Bound::LeftBound.value()
+    point.x < Bound::LeftBound.value()
         || point.x > Bound::RightBound.value()
         || point.y > Bound::UpperBound.value()
-        || point.y < Bound::LowerBound.value();
+        || point.y < Bound::LowerBound.value()
 }
@@ -0,0 +1,336 @@
+use crate::{
+    GameRng, GameState,
+    modes::GameModes,
+    new_game_plus::NewGamePlus,
+    profiles::Profiles,
+    prompts::{MenuAction, MenuInput, Prompt},
+    saved_run::{ResumeRun, has_saved_run, take_saved_run},
+    settings::{SettingsState, settings_closed},
+    storage::Storage,
+    text_entry::{TextEntered, TextEntrySet, no_text_entry, spawn_text_entry},
+    toast::ShowToast,
+    tutorial::{Tutorial, tutorial_done},
+    weekly::WeeklyPlaylist,
+};
+use bevy::prelude::*;
+
+const BUTTON_COLOR: Color = Color::srgb(0.3, 0.3, 0.3);
+const HOVERED_BUTTON_COLOR: Color = Color::srgb(0.4, 0.4, 0.4);
+
+/// The screen the game opens on, so a run only starts (and the arena is only
+/// built) once the player asks for one. The seed for the next run can be
+/// typed in here too, a saved run continued, New Game+ started once it's
+/// unlocked, the difficulty, game mode and skin picked, this week's playlist
+/// played, the tutorial started, and the achievements or credits read.
+pub struct MainMenuPlugin;
+
+#[derive(Component)]
+struct MainMenuColumn;
+
+#[derive(Component)]
+struct SeedEntry;
+
+#[derive(Component, Clone, Copy)]
+enum MenuButton {
+    Continue,
+    Play,
+    NewGamePlus,
+    Weekly,
+    Tutorial,
+    Mode,
+    Difficulty,
+    Cosmetics,
+    Achievements,
+    Seed,
+    Settings,
+    Credits,
+    Quit,
+}
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Menu), setup_main_menu)
+            .add_systems(Update, highlight_menu_buttons)
+            .add_systems(
+                Update,
+                (
+                    choose_menu_option.run_if(no_text_entry.and(settings_closed)),
+                    apply_seed.after(TextEntrySet),
+                )
+                    .run_if(in_state(GameState::Menu)),
+            );
+    }
+}
+
+fn setup_main_menu(
+    mut commands: Commands,
+    weekly_playlist: Res<WeeklyPlaylist>,
+    modes: Res<GameModes>,
+    profiles: Res<Profiles>,
+    new_game_plus: Res<NewGamePlus>,
+    storage: Res<Storage>,
+) {
+    let saved_run = has_saved_run(&storage);
+    let mut prompts = vec![(MenuAction::Play, "play")];
+    if saved_run {
+        prompts.push((MenuAction::Continue, "continue"));
+    }
+    if new_game_plus.unlocked {
+        prompts.push((MenuAction::PlayNewGamePlus, "new game+"));
+    }
+    prompts.extend([
+        (MenuAction::PlayWeekly, "weekly"),
+        (MenuAction::Tutorial, "tutorial"),
+        (MenuAction::ChooseMode, "mode"),
+        (MenuAction::Difficulty, "difficulty"),
+        (MenuAction::Cosmetics, "cosmetics"),
+        (MenuAction::Achievements, "achievements"),
+        (MenuAction::EnterSeed, "seed"),
+        (MenuAction::OpenSettings, "settings"),
+        (MenuAction::Credits, "credits"),
+        (MenuAction::Quit, "quit"),
+    ]);
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            StateScoped(GameState::Menu),
+        ))
+        .with_children(|builder| {
+            builder
+                .spawn((
+                    MainMenuColumn,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(20.),
+                        ..default()
+                    },
+                ))
+                .with_children(|builder| {
+                    builder.spawn((
+                        Text::new("shape-eater"),
+                        TextFont {
+                            font_size: 100.,
+                            ..default()
+                        },
+                    ));
+                    if profiles.in_use() {
+                        builder.spawn((
+                            Text::new(format!("playing as {}", profiles.current)),
+                            TextFont {
+                                font_size: 20.,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                        ));
+                    }
+                    if saved_run {
+                        spawn_menu_button(builder, MenuButton::Continue, "Continue");
+                    }
+                    spawn_menu_button(builder, MenuButton::Play, "Play");
+                    if new_game_plus.unlocked {
+                        spawn_menu_button(builder, MenuButton::NewGamePlus, "New Game+");
+                    }
+                    spawn_menu_button(builder, MenuButton::Weekly, "Weekly");
+                    builder.spawn((
+                        Text::new(weekly_playlist.name()),
+                        TextFont {
+                            font_size: 20.,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                    ));
+                    spawn_menu_button(builder, MenuButton::Tutorial, "Tutorial");
+                    if !tutorial_done(&storage) {
+                        builder.spawn((
+                            Text::new("new here? try the tutorial"),
+                            TextFont {
+                                font_size: 20.,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                        ));
+                    }
+                    spawn_menu_button(
+                        builder,
+                        MenuButton::Mode,
+                        &format!("Mode: {}", modes.selected().name()),
+                    );
+                    spawn_menu_button(builder, MenuButton::Difficulty, "Difficulty");
+                    spawn_menu_button(builder, MenuButton::Cosmetics, "Cosmetics");
+                    spawn_menu_button(builder, MenuButton::Achievements, "Achievements");
+                    spawn_menu_button(builder, MenuButton::Seed, "Seed");
+                    spawn_menu_button(builder, MenuButton::Settings, "Settings");
+                    spawn_menu_button(builder, MenuButton::Credits, "Credits");
+                    spawn_menu_button(builder, MenuButton::Quit, "Quit");
+                    builder.spawn((
+                        Text::default(),
+                        Prompt(prompts),
+                        TextFont {
+                            font_size: 20.,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                    ));
+                });
+        });
+}
+
+/// A clickable menu button, highlighted while hovered. `marker` tells the
+/// buttons on a screen apart.
+pub fn spawn_menu_button(builder: &mut ChildBuilder, marker: impl Component, label: &str) {
+    builder
+        .spawn((
+            marker,
+            Button,
+            Node {
+                width: Val::Px(240.),
+                height: Val::Px(60.),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 30.,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn highlight_menu_buttons(
+    mut button_query: Query<(&Interaction, &mut BackgroundColor), Changed<Interaction>>,
+) {
+    for (interaction, mut background) in button_query.iter_mut() {
+        background.0 = match interaction {
+            Interaction::None => BUTTON_COLOR,
+            Interaction::Hovered | Interaction::Pressed => HOVERED_BUTTON_COLOR,
+        };
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn choose_menu_option(
+    mut commands: Commands,
+    button_query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    column_query: Query<Entity, With<MainMenuColumn>>,
+    menu_input: MenuInput,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_settings_state: ResMut<NextState<SettingsState>>,
+    mut weekly_playlist: ResMut<WeeklyPlaylist>,
+    mut tutorial: ResMut<Tutorial>,
+    mut storage: ResMut<Storage>,
+    mut resume_run: ResMut<ResumeRun>,
+    mut new_game_plus: ResMut<NewGamePlus>,
+    mut exit_writer: EventWriter<AppExit>,
+) {
+    let clicked = button_query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| *button);
+
+    let chosen = if menu_input.just_pressed(MenuAction::Play) {
+        Some(MenuButton::Play)
+    } else if menu_input.just_pressed(MenuAction::Continue) {
+        Some(MenuButton::Continue)
+    } else if new_game_plus.unlocked && menu_input.just_pressed(MenuAction::PlayNewGamePlus) {
+        Some(MenuButton::NewGamePlus)
+    } else if menu_input.just_pressed(MenuAction::PlayWeekly) {
+        Some(MenuButton::Weekly)
+    } else if menu_input.just_pressed(MenuAction::Tutorial) {
+        Some(MenuButton::Tutorial)
+    } else if menu_input.just_pressed(MenuAction::ChooseMode) {
+        Some(MenuButton::Mode)
+    } else if menu_input.just_pressed(MenuAction::Difficulty) {
+        Some(MenuButton::Difficulty)
+    } else if menu_input.just_pressed(MenuAction::Cosmetics) {
+        Some(MenuButton::Cosmetics)
+    } else if menu_input.just_pressed(MenuAction::Achievements) {
+        Some(MenuButton::Achievements)
+    } else if menu_input.just_pressed(MenuAction::EnterSeed) {
+        Some(MenuButton::Seed)
+    } else if menu_input.just_pressed(MenuAction::OpenSettings) {
+        Some(MenuButton::Settings)
+    } else if menu_input.just_pressed(MenuAction::Credits) {
+        Some(MenuButton::Credits)
+    } else if menu_input.just_pressed(MenuAction::Quit) {
+        Some(MenuButton::Quit)
+    } else {
+        clicked
+    };
+
+    match chosen {
+        Some(MenuButton::Play) => next_game_state.set(GameState::InGame),
+        Some(MenuButton::Continue) => {
+            if let Some(saved_run) = take_saved_run(&mut storage) {
+                resume_run.0 = Some(saved_run);
+                next_game_state.set(GameState::InGame);
+            }
+        }
+        Some(MenuButton::NewGamePlus) => {
+            new_game_plus.start_next_loop();
+            next_game_state.set(GameState::InGame);
+        }
+        Some(MenuButton::Weekly) => {
+            weekly_playlist.active = true;
+            next_game_state.set(GameState::InGame);
+        }
+        Some(MenuButton::Tutorial) => {
+            tutorial.running = true;
+            next_game_state.set(GameState::InGame);
+        }
+        Some(MenuButton::Mode) => next_game_state.set(GameState::ModeSelect),
+        Some(MenuButton::Difficulty) => next_game_state.set(GameState::Difficulty),
+        Some(MenuButton::Cosmetics) => next_game_state.set(GameState::Cosmetics),
+        Some(MenuButton::Achievements) => next_game_state.set(GameState::Achievements),
+        Some(MenuButton::Seed) => {
+            for column in column_query.iter() {
+                commands.entity(column).with_children(|builder| {
+                    spawn_text_entry(builder, SeedEntry, "0123456789", 20);
+                });
+            }
+        }
+        Some(MenuButton::Settings) => next_settings_state.set(SettingsState::Open),
+        Some(MenuButton::Credits) => next_game_state.set(GameState::Credits),
+        Some(MenuButton::Quit) => {
+            exit_writer.send(AppExit::Success);
+        }
+        None => {}
+    }
+}
+
+/// Leaving the entry empty keeps the current seed.
+fn apply_seed(
+    mut entered_reader: EventReader<TextEntered>,
+    seed_entry_query: Query<(), With<SeedEntry>>,
+    mut game_rng: ResMut<GameRng>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    for TextEntered { entry, text } in entered_reader.read() {
+        if !seed_entry_query.contains(*entry) || text.is_empty() {
+            continue;
+        }
+
+        match text.parse() {
+            Ok(seed) => {
+                *game_rng = GameRng::new(seed);
+                toast_writer.send(ShowToast(format!("seed {seed}")));
+            }
+            Err(_) => {
+                toast_writer.send(ShowToast(format!("{text} is too big for a seed")));
+            }
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{
+    Ball, Bound, FONT_SIZE_FACTOR, GameState, InGameEntity, InGameSet, Numbered,
+    OVERFLOW_SCORE_RATE, Player, SIZE_FACTOR, Wall,
+    config::GameConfig,
+    grow,
+    layers::RenderLayer,
+    modes::{AppGameModeExt, GameMode, GameModes},
+    number_words::{fit_to_ball, format_number},
+    score::{Score, points},
+    setup_game,
+    tutorial::Tutorial,
+};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+const DIVIDER_THICKNESS: f32 = 10.;
+/// How far below the divider the player starts, at least.
+const SPAWN_DEPTH: f32 = 250.;
+const PARTNER_SPEED: f32 = 350.;
+/// Bigger balls closer than this, sideways, are run away from.
+const PARTNER_DANGER_DISTANCE: f32 = 200.;
+/// Close enough to a ball's column to stop and wait for it.
+const PARTNER_CHASE_TOLERANCE: f32 = 10.;
+const PARTNER_COLOR: Color = Color::srgb(0., 0.7, 0.7);
+const DIVIDER_COLOR: Color = Color::srgba(1., 1., 1., 0.2);
+
+/// A co-op mode where the arena is split in two across the middle. The
+/// player keeps to the bottom half and an AI partner plays the top, always
+/// at the player's height mirrored across the divider. Balls cross the
+/// divider freely, so what one half dodges the other gets to eat, and the
+/// partner's eats score points for the run too.
+pub struct MirrorPlugin;
+
+#[derive(Component)]
+struct MirrorPartner;
+
+#[derive(Component)]
+struct PartnerText;
+
+struct MirrorMode;
+
+impl Plugin for MirrorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_game_mode(MirrorMode)
+            .add_systems(
+                OnEnter(GameState::InGame),
+                split_arena.after(setup_game).run_if(split_arena_mode),
+            )
+            .add_systems(
+                FixedUpdate,
+                (mirror_partner, partner_hits, resize_partner)
+                    .chain()
+                    .in_set(InGameSet)
+                    .run_if(split_arena_mode),
+            );
+    }
+}
+
+impl GameMode for MirrorMode {
+    fn name(&self) -> &'static str {
+        "Mirror"
+    }
+
+    fn description(&self) -> &'static str {
+        "share a split arena with an AI partner"
+    }
+
+    fn split_arena(&self) -> bool {
+        true
+    }
+}
+
+/// The tutorial always has the arena to itself.
+fn split_arena_mode(modes: Res<GameModes>, tutorial: Res<Tutorial>) -> bool {
+    modes.selected().split_arena() && !tutorial.running
+}
+
+/// The divider is a wall, but only the player is dynamic, so it's the only
+/// thing it stops; balls are kinematic and pass straight through.
+fn split_arena(
+    mut commands: Commands,
+    mut player_query: Query<&mut Transform, With<Player>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    config: Res<GameConfig>,
+) {
+    let width = Bound::arena().width();
+    commands.spawn((
+        Wall,
+        InGameEntity,
+        Sprite {
+            color: DIVIDER_COLOR,
+            custom_size: Some(Vec2::new(width, DIVIDER_THICKNESS)),
+            ..default()
+        },
+        Transform::from_translation(RenderLayer::Walls.at(Vec2::ZERO)),
+        RigidBody::Static,
+        Collider::rectangle(width, DIVIDER_THICKNESS),
+    ));
+
+    let mut partner_position = Vec2::new(0., SPAWN_DEPTH);
+    for mut transform in player_query.iter_mut() {
+        transform.translation.y = -transform.translation.y.abs().max(SPAWN_DEPTH);
+        partner_position = Vec2::new(transform.translation.x, -transform.translation.y);
+    }
+
+    let size = config.starting_number as f32 * SIZE_FACTOR;
+    let text = format_number(config.starting_number, &config);
+    commands
+        .spawn((
+            MirrorPartner,
+            InGameEntity,
+            Numbered(config.starting_number),
+            Mesh2d(meshes.add(Rectangle::new(size, size))),
+            MeshMaterial2d(materials.add(PARTNER_COLOR)),
+            Transform::from_translation(RenderLayer::Player.at(partner_position)),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                PartnerText,
+                TextFont {
+                    font_size: config.starting_number as f32
+                        * FONT_SIZE_FACTOR
+                        * fit_to_ball(&text),
+                    ..default()
+                },
+                Text2d::new(text),
+            ));
+        });
+}
+
+/// Keeps the partner at the player's height on the other side of the
+/// divider, while it runs from the nearest bigger ball in its half if one is
+/// close, or heads for the nearest smaller one.
+#[allow(clippy::type_complexity)]
+fn mirror_partner(
+    mut partner_query: Query<(&Numbered, &mut Transform), With<MirrorPartner>>,
+    player_query: Query<&Transform, (With<Player>, Without<MirrorPartner>)>,
+    ball_query: Query<(&Numbered, &Transform), (With<Ball>, Without<MirrorPartner>)>,
+    time: Res<Time>,
+) {
+    let Ok((Numbered(partner_number), mut partner_transform)) = partner_query.get_single_mut()
+    else {
+        return;
+    };
+    if let Ok(player_transform) = player_query.get_single() {
+        partner_transform.translation.y = -player_transform.translation.y;
+    }
+    let partner_x = partner_transform.translation.x;
+
+    let nearest = |bigger: bool| {
+        ball_query
+            .iter()
+            .filter(|(_, transform)| transform.translation.y > 0.)
+            .filter(|(Numbered(number), _)| (number > partner_number) == bigger)
+            .map(|(_, transform)| transform.translation.x - partner_x)
+            .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+    };
+    let direction = match (nearest(true), nearest(false)) {
+        (Some(threat), _) if threat.abs() < PARTNER_DANGER_DISTANCE => -threat.signum(),
+        (_, Some(food)) if food.abs() > PARTNER_CHASE_TOLERANCE => food.signum(),
+        _ => return,
+    };
+
+    let half_size = *partner_number as f32 * SIZE_FACTOR / 2.;
+    let arena = Bound::arena();
+    partner_transform.translation.x = (partner_x + direction * PARTNER_SPEED * time.delta_secs())
+        .clamp(arena.min.x + half_size, arena.max.x - half_size);
+}
+
+/// The partner has no collider of its own, so it eats whatever ball it
+/// overlaps that's smaller than it. A bigger one knocks it back to the
+/// starting number instead of ending the run. Eats are scored as if the
+/// player had made them standing still.
+#[allow(clippy::type_complexity)]
+fn partner_hits(
+    mut commands: Commands,
+    mut partner_query: Query<(&mut Numbered, &Transform), With<MirrorPartner>>,
+    ball_query: Query<(Entity, &Numbered, &Transform), (With<Ball>, Without<MirrorPartner>)>,
+    mut score: ResMut<Score>,
+    config: Res<GameConfig>,
+) {
+    let Ok((mut partner_number, partner_transform)) = partner_query.get_single_mut() else {
+        return;
+    };
+    let partner_position = partner_transform.translation.truncate();
+
+    for (ball, Numbered(ball_number), ball_transform) in ball_query.iter() {
+        let reach = (partner_number.0 + ball_number) as f32 * SIZE_FACTOR / 2.;
+        if ball_transform
+            .translation
+            .truncate()
+            .distance(partner_position)
+            > reach
+        {
+            continue;
+        }
+
+        commands.entity(ball).despawn_recursive();
+        if *ball_number > partner_number.0 {
+            partner_number.0 = config.starting_number;
+        } else {
+            let (grown, overflow) = grow(partner_number.0, *ball_number, config.max_number);
+            score.0 += points(*ball_number, 0.) + overflow * OVERFLOW_SCORE_RATE;
+            partner_number.0 = grown;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn resize_partner(
+    mut partner_query: Query<(&Numbered, &mut Mesh2d), (With<MirrorPartner>, Changed<Numbered>)>,
+    mut text_query: Query<(&mut Text2d, &mut TextFont), With<PartnerText>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    config: Res<GameConfig>,
+) {
+    let Ok((Numbered(partner_number), mut mesh)) = partner_query.get_single_mut() else {
+        return;
+    };
+    let size = *partner_number as f32 * SIZE_FACTOR;
+    mesh.0 = meshes.add(Rectangle::new(size, size));
+
+    for (mut text, mut text_font) in text_query.iter_mut() {
+        text.0 = format_number(*partner_number, &config);
+        text_font.font_size = *partner_number as f32 * FONT_SIZE_FACTOR * fit_to_ball(&text.0);
+    }
+}
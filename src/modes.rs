@@ -0,0 +1,350 @@
+use crate::{
+    BALL_SPAWN_TIMER, FatalHit, GameState, InGameSet,
+    config::GameConfig,
+    difficulty::Difficulty,
+    menu::spawn_menu_button,
+    new_game_plus::NewGamePlus,
+    prompts::{MenuAction, MenuInput, Prompt},
+    shop::ShopEffects,
+    stats::RunStats,
+    storage::Storage,
+    timers::GameTimers,
+    toast::ShowToast,
+};
+use bevy::prelude::*;
+
+const MODE_KEY: &str = "game_mode";
+const TIMED_LIMIT: f32 = 90.;
+/// Survival spawns get this much more frequent every minute.
+const SURVIVAL_RAMP_PER_MINUTE: f32 = 0.5;
+
+/// Game modes are rulesets registered with [`AppGameModeExt::add_game_mode`]
+/// and listed on a mode select screen reached from the main menu, so adding
+/// one doesn't mean touching the menus. The chosen one is kept between
+/// sessions. A mode named on the command line is played this session
+/// instead.
+pub struct GameModePlugin(pub Option<String>);
+
+/// The mode named with `--mode`, if any.
+#[derive(Resource)]
+struct LaunchMode(Option<String>);
+
+/// The rules of a game mode, on top of the config. Everything but the name
+/// and description defaults to how a normal run plays.
+pub trait GameMode: Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    /// Runs end once they've lasted this long, in seconds.
+    fn time_limit(&self) -> Option<f32> {
+        None
+    }
+
+    /// Whether a hit from a bigger ball ends the run, rather than the ball
+    /// just going.
+    fn hits_are_fatal(&self) -> bool {
+        true
+    }
+
+    /// Whether the arena is split across the middle with an AI partner in
+    /// the top half, see [`crate::mirror`].
+    fn split_arena(&self) -> bool {
+        false
+    }
+
+    /// Scales the time between spawns, `elapsed` seconds into a run.
+    fn spawn_interval_multiplier(&self, _elapsed: f32) -> f32 {
+        1.
+    }
+}
+
+/// Every registered mode, in the order they're listed, and which one runs
+/// are played in.
+#[derive(Resource)]
+pub struct GameModes {
+    modes: Vec<Box<dyn GameMode>>,
+    selected: usize,
+}
+
+pub trait AppGameModeExt {
+    fn add_game_mode(&mut self, mode: impl GameMode) -> &mut Self;
+}
+
+#[derive(Component, Clone, Copy)]
+enum ModeButton {
+    Choose(usize),
+    Back,
+}
+
+struct ClassicMode;
+
+struct TimedMode;
+
+struct SurvivalMode;
+
+struct ZenMode;
+
+impl Plugin for GameModePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_game_mode(TimedMode)
+            .add_game_mode(SurvivalMode)
+            .add_game_mode(ZenMode)
+            .insert_resource(LaunchMode(self.0.clone()))
+            .add_systems(Startup, (load_game_mode, apply_launch_mode).chain())
+            .add_systems(OnEnter(GameState::InGame), pace_ball_spawns)
+            .add_systems(OnEnter(GameState::ModeSelect), setup_mode_select_screen)
+            .add_systems(
+                FixedUpdate,
+                (pace_ball_spawns, end_timed_runs).in_set(InGameSet),
+            )
+            .add_systems(
+                Update,
+                choose_game_mode.run_if(in_state(GameState::ModeSelect)),
+            );
+    }
+}
+
+impl Default for GameModes {
+    fn default() -> Self {
+        GameModes {
+            modes: vec![Box::new(ClassicMode)],
+            selected: 0,
+        }
+    }
+}
+
+impl GameModes {
+    pub fn selected(&self) -> &dyn GameMode {
+        self.modes[self.selected].as_ref()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.modes.iter().map(|mode| mode.name())
+    }
+
+    /// Whether a mode by that name is registered to be picked.
+    pub fn select(&mut self, name: &str) -> bool {
+        let Some(index) = self.modes.iter().position(|mode| mode.name() == name) else {
+            return false;
+        };
+        self.selected = index;
+        true
+    }
+}
+
+impl AppGameModeExt for App {
+    fn add_game_mode(&mut self, mode: impl GameMode) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<GameModes>()
+            .modes
+            .push(Box::new(mode));
+        self
+    }
+}
+
+impl GameMode for ClassicMode {
+    fn name(&self) -> &'static str {
+        "Classic"
+    }
+
+    fn description(&self) -> &'static str {
+        "eat smaller numbers, avoid bigger ones"
+    }
+}
+
+impl GameMode for TimedMode {
+    fn name(&self) -> &'static str {
+        "Timed"
+    }
+
+    fn description(&self) -> &'static str {
+        "grow as big as you can in 90 seconds"
+    }
+
+    fn time_limit(&self) -> Option<f32> {
+        Some(TIMED_LIMIT)
+    }
+}
+
+impl GameMode for SurvivalMode {
+    fn name(&self) -> &'static str {
+        "Survival"
+    }
+
+    fn description(&self) -> &'static str {
+        "balls come faster the longer you last"
+    }
+
+    fn spawn_interval_multiplier(&self, elapsed: f32) -> f32 {
+        1. / (1. + SURVIVAL_RAMP_PER_MINUTE * elapsed / 60.)
+    }
+}
+
+impl GameMode for ZenMode {
+    fn name(&self) -> &'static str {
+        "Zen"
+    }
+
+    fn description(&self) -> &'static str {
+        "bigger balls can't hurt you"
+    }
+
+    fn hits_are_fatal(&self) -> bool {
+        false
+    }
+}
+
+impl ModeButton {
+    fn label(self, modes: &GameModes) -> String {
+        match self {
+            ModeButton::Choose(index) if index == modes.selected => {
+                format!("> {} <", modes.modes[index].name())
+            }
+            ModeButton::Choose(index) => modes.modes[index].name().to_string(),
+            ModeButton::Back => "Back".to_string(),
+        }
+    }
+}
+
+/// Exits with the modes there are if it isn't one of them. Modes are only
+/// all registered once the app is built, so this can't be checked sooner.
+fn apply_launch_mode(
+    launch_mode: Res<LaunchMode>,
+    mut modes: ResMut<GameModes>,
+    mut exit_writer: EventWriter<AppExit>,
+) {
+    let Some(name) = &launch_mode.0 else {
+        return;
+    };
+
+    if !modes.select(name) {
+        let names: Vec<&str> = modes.names().collect();
+        error!(
+            "unknown mode `{name}`, expected one of: {}",
+            names.join(", ")
+        );
+        exit_writer.send(AppExit::from_code(2));
+    }
+}
+
+/// Falls back to Classic if the saved mode isn't registered any more.
+fn load_game_mode(storage: Res<Storage>, mut modes: ResMut<GameModes>) {
+    if let Some(name) = storage.load::<String>(MODE_KEY) {
+        modes.selected = modes
+            .modes
+            .iter()
+            .position(|mode| mode.name() == name)
+            .unwrap_or(0);
+    }
+}
+
+/// Between the config, the difficulty, New Game+, the mode and anything bought
+/// in the shop, how long the next ball spawn is.
+fn pace_ball_spawns(
+    mut game_timers: ResMut<GameTimers>,
+    modes: Res<GameModes>,
+    difficulty: Res<Difficulty>,
+    new_game_plus: Res<NewGamePlus>,
+    config: Res<GameConfig>,
+    stats: Res<RunStats>,
+    shop_effects: Res<ShopEffects>,
+) {
+    game_timers.set_duration(
+        BALL_SPAWN_TIMER,
+        config.ball_spawn_interval
+            * difficulty.spawn_interval_multiplier()
+            * new_game_plus.spawn_interval_multiplier(&config)
+            * modes.selected().spawn_interval_multiplier(stats.duration)
+            * shop_effects.spawn_interval_multiplier(),
+    );
+}
+
+/// Ends the run the same way a fatal hit does, once per run.
+fn end_timed_runs(
+    modes: Res<GameModes>,
+    stats: Res<RunStats>,
+    mut timed_out: Local<bool>,
+    mut fatal_hit_writer: EventWriter<FatalHit>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    let Some(time_limit) = modes.selected().time_limit() else {
+        return;
+    };
+    if stats.duration < time_limit {
+        *timed_out = false;
+    } else if !*timed_out {
+        *timed_out = true;
+        fatal_hit_writer.send(FatalHit { ball: None });
+        toast_writer.send(ShowToast("Time's up!".to_string()));
+    }
+}
+
+fn setup_mode_select_screen(mut commands: Commands, modes: Res<GameModes>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(10.),
+                ..default()
+            },
+            StateScoped(GameState::ModeSelect),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new("Mode"),
+                TextFont {
+                    font_size: 60.,
+                    ..default()
+                },
+            ));
+            for (index, mode) in modes.modes.iter().enumerate() {
+                let button = ModeButton::Choose(index);
+                spawn_menu_button(builder, button, &button.label(&modes));
+                builder.spawn((
+                    Text::new(mode.description()),
+                    TextFont {
+                        font_size: 20.,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                ));
+            }
+            spawn_menu_button(builder, ModeButton::Back, &ModeButton::Back.label(&modes));
+            builder.spawn((
+                Text::default(),
+                Prompt(vec![(MenuAction::Quit, "back")]),
+                TextFont {
+                    font_size: 20.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+/// Clicking a mode picks it and goes back to the menu.
+fn choose_game_mode(
+    button_query: Query<(&Interaction, &ModeButton), Changed<Interaction>>,
+    menu_input: MenuInput,
+    mut modes: ResMut<GameModes>,
+    mut storage: ResMut<Storage>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    let clicked = button_query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| *button);
+
+    if let Some(ModeButton::Choose(index)) = clicked {
+        modes.selected = index;
+        storage.save(MODE_KEY, modes.selected().name());
+    }
+    if menu_input.just_pressed(MenuAction::Quit) || clicked.is_some() {
+        next_game_state.set(GameState::Menu);
+    }
+}
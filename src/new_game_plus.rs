@@ -0,0 +1,109 @@
+use crate::{GameState, config::GameConfig, storage::Storage};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+const UNLOCKED_KEY: &str = "new_game_plus_unlocked";
+
+/// New Game+ is unlocked by winning once, and from then on can be started
+/// from the menu as well as from the victory screen. Every win in it starts
+/// another loop, layering its [`NewGamePlusProfile`] over the normal config
+/// once more for each loop, until the player goes back to the menu. Anything
+/// unlocked along the way stays unlocked. Its runs keep a high score of
+/// their own.
+pub struct NewGamePlusPlugin;
+
+#[derive(Resource, Default)]
+pub struct NewGamePlus {
+    pub unlocked: bool,
+    /// How many times over New Game+ has been won into, 0 outside it.
+    pub loop_count: u32,
+}
+
+/// What each loop of New Game+ changes relative to the one before, starting
+/// from a normal run.
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NewGamePlusProfile {
+    pub ball_number_shift: i32,
+    pub ball_speed_multiplier: f32,
+    pub spawn_interval_multiplier: f32,
+    /// Scales how much a hit that doesn't end the run shrinks the player.
+    pub hit_penalty_multiplier: f32,
+}
+
+impl Default for NewGamePlusProfile {
+    fn default() -> Self {
+        NewGamePlusProfile {
+            ball_number_shift: 25,
+            ball_speed_multiplier: 1.25,
+            spawn_interval_multiplier: 0.85,
+            hit_penalty_multiplier: 1.5,
+        }
+    }
+}
+
+impl Plugin for NewGamePlusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NewGamePlus>()
+            .add_systems(Startup, load_new_game_plus)
+            .add_systems(OnEnter(GameState::Menu), leave_new_game_plus);
+    }
+}
+
+impl NewGamePlus {
+    pub fn unlock(&mut self, storage: &mut Storage) {
+        if !self.unlocked {
+            self.unlocked = true;
+            storage.save(UNLOCKED_KEY, true);
+        }
+    }
+
+    pub fn active(&self) -> bool {
+        self.loop_count > 0
+    }
+
+    /// Goes on into the next loop, or into the first from a normal run.
+    pub fn start_next_loop(&mut self) {
+        self.loop_count += 1;
+    }
+
+    pub fn ball_number(&self, number: i32, config: &GameConfig) -> i32 {
+        number + config.new_game_plus.ball_number_shift * self.loop_count as i32
+    }
+
+    pub fn ball_speed(&self, config: &GameConfig) -> f32 {
+        config.ball_speed * self.per_loop(config.new_game_plus.ball_speed_multiplier)
+    }
+
+    pub fn spawn_interval_multiplier(&self, config: &GameConfig) -> f32 {
+        self.per_loop(config.new_game_plus.spawn_interval_multiplier)
+    }
+
+    /// Takes the shrink from a hit that doesn't end the run, from
+    /// `player_number` to `shrunk`, and makes it harsher for every loop.
+    pub fn shrink_on_hit(&self, player_number: i32, shrunk: i32, config: &GameConfig) -> i32 {
+        let lost = (player_number - shrunk) as f32
+            * self.per_loop(config.new_game_plus.hit_penalty_multiplier);
+        (player_number - lost.round() as i32).max(1)
+    }
+
+    fn per_loop(&self, multiplier: f32) -> f32 {
+        multiplier.powi(self.loop_count as i32)
+    }
+
+    pub fn high_score_key(&self) -> &'static str {
+        if self.active() {
+            "high_score_new_game_plus"
+        } else {
+            "high_score"
+        }
+    }
+}
+
+fn load_new_game_plus(storage: Res<Storage>, mut new_game_plus: ResMut<NewGamePlus>) {
+    new_game_plus.unlocked = storage.load(UNLOCKED_KEY).unwrap_or(false);
+}
+
+fn leave_new_game_plus(mut new_game_plus: ResMut<NewGamePlus>) {
+    new_game_plus.loop_count = 0;
+}
@@ -0,0 +1,82 @@
+use crate::{GameState, storage::Storage};
+use bevy::prelude::*;
+
+const NEWS: &str = include_str!("../assets/news.txt");
+const SEEN_VERSION_KEY: &str = "news_seen_version";
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A "what's new" screen shown once after updating to a new version, before
+/// the main menu.
+pub struct NewsPlugin;
+
+impl Plugin for NewsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::News), setup_news_screen)
+            .add_systems(Update, dismiss_news.run_if(in_state(GameState::News)));
+    }
+}
+
+/// Whether this version's news hasn't been shown yet.
+pub fn has_unseen_news(storage: &Storage) -> bool {
+    storage.load::<String>(SEEN_VERSION_KEY).as_deref() != Some(VERSION)
+}
+
+fn setup_news_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            StateScoped(GameState::News),
+        ))
+        .with_children(|builder| {
+            builder
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(10.),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder.spawn((
+                        Text::new(format!("What's new in {VERSION}")),
+                        TextFont {
+                            font_size: 60.,
+                            ..default()
+                        },
+                    ));
+                    for line in NEWS.lines().filter(|line| !line.trim().is_empty()) {
+                        builder.spawn((
+                            Text::new(line),
+                            TextFont {
+                                font_size: 26.,
+                                ..default()
+                            },
+                        ));
+                    }
+                    builder.spawn((
+                        Text::new("press any key to continue"),
+                        TextFont {
+                            font_size: 20.,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                    ));
+                });
+        });
+}
+
+fn dismiss_news(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut storage: ResMut<Storage>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if keys.get_just_pressed().next().is_some() {
+        storage.save(SEEN_VERSION_KEY, VERSION);
+        next_game_state.set(GameState::Menu);
+    }
+}
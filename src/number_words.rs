@@ -0,0 +1,94 @@
+use crate::config::GameConfig;
+use serde::Deserialize;
+
+const ENGLISH: [&str; 21] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+    "twenty",
+];
+const FRENCH: [&str; 21] = [
+    "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf", "dix", "onze",
+    "douze", "treize", "quatorze", "quinze", "seize", "dix-sept", "dix-huit", "dix-neuf", "vingt",
+];
+const SPANISH: [&str; 21] = [
+    "cero",
+    "uno",
+    "dos",
+    "tres",
+    "cuatro",
+    "cinco",
+    "seis",
+    "siete",
+    "ocho",
+    "nueve",
+    "diez",
+    "once",
+    "doce",
+    "trece",
+    "catorce",
+    "quince",
+    "dieciséis",
+    "diecisiete",
+    "dieciocho",
+    "diecinueve",
+    "veinte",
+];
+/// Numbers up to this many characters long fit in their ball at full size.
+const FULL_SIZE_CHARACTERS: usize = 3;
+
+/// Which language small numbers are spelled out in, set with `number_words`
+/// in the config for kids learning their numbers.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub enum NumberLanguage {
+    English,
+    French,
+    Spanish,
+}
+
+impl NumberLanguage {
+    fn words(self) -> &'static [&'static str] {
+        match self {
+            NumberLanguage::English => &ENGLISH,
+            NumberLanguage::French => &FRENCH,
+            NumberLanguage::Spanish => &SPANISH,
+        }
+    }
+}
+
+/// How a number is written on the player and the balls: in words when it's
+/// below `number_words_below` and number words are on, otherwise in digits.
+pub fn format_number(number: i32, config: &GameConfig) -> String {
+    let word = config
+        .number_words
+        .filter(|_| number < config.number_words_below)
+        .and_then(|language| language.words().get(usize::try_from(number).ok()?));
+
+    match word {
+        Some(word) => word.to_string(),
+        None => number.to_string(),
+    }
+}
+
+/// How much smaller than usual `text` has to be drawn to still fit in its
+/// ball, for numbers written as words.
+pub fn fit_to_ball(text: &str) -> f32 {
+    (FULL_SIZE_CHARACTERS as f32 / text.chars().count() as f32).min(1.)
+}
@@ -0,0 +1,259 @@
+use crate::{
+    Bound, GameState, InGameEntity, InGameSet, Player, arena::ArenaKind, config::GameConfig,
+    gravity::GravityDirection, layers::RenderLayer,
+};
+use bevy::prelude::*;
+
+/// How far the nearest layer shifts for every unit the player moves.
+const PLAYER_SHIFT: f32 = 0.04;
+/// How far the nearest layer leans towards the way gravity points.
+const GRAVITY_SHIFT: f32 = 20.;
+/// How quickly layers catch up with where they should be, per second.
+const PARALLAX_SMOOTHING: f32 = 4.;
+/// Layers cover this much past the arena on every side, so their edges
+/// never show as they shift.
+const PARALLAX_MARGIN: f32 = 60.;
+
+/// A few faint layers behind the arena's decorations that drift a little
+/// against the player's movement and lean with gravity, nearer layers more,
+/// giving the flat arena some depth. Each arena has its own set. Layers only
+/// ever move as a whole, and stay put with reduced motion on.
+pub struct ParallaxPlugin;
+
+struct ParallaxLayer {
+    /// From 0 for far away to 1 for right behind the arena.
+    depth: f32,
+    color: Color,
+    pattern: Pattern,
+}
+
+enum Pattern {
+    Dots {
+        spacing: f32,
+        radius: f32,
+    },
+    /// Horizontal bands.
+    Stripes {
+        spacing: f32,
+        width: f32,
+    },
+}
+
+#[derive(Component)]
+struct Parallax {
+    depth: f32,
+}
+
+impl Plugin for ParallaxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::InGame), spawn_parallax_layers)
+            .add_systems(Update, shift_parallax_layers.in_set(InGameSet));
+    }
+}
+
+/// Everything wrong with an arena's layers, for `--validate`.
+pub fn problems(kind: ArenaKind) -> Vec<String> {
+    let layers = parallax_layers(kind);
+    let mut problems = Vec::new();
+
+    for (index, layer) in layers.iter().enumerate() {
+        let number = index + 1;
+        if !(0. ..=1.).contains(&layer.depth) {
+            problems.push(format!("layer {number} has a depth outside 0 to 1"));
+        }
+        if index > 0 && layer.depth < layers[index - 1].depth {
+            problems.push(format!("layer {number} is farther than the one before it"));
+        }
+        let (spacing, size) = match layer.pattern {
+            Pattern::Dots { spacing, radius } => (spacing, radius * 2.),
+            Pattern::Stripes { spacing, width } => (spacing, width),
+        };
+        // Spacing steps through the whole area, so it has to get somewhere.
+        if spacing <= 0. {
+            problems.push(format!("layer {number} has a spacing that isn't above 0"));
+        } else if size >= spacing {
+            problems.push(format!(
+                "layer {number}'s shapes are as wide as their spacing"
+            ));
+        }
+        if size <= 0. {
+            problems.push(format!("layer {number}'s shapes have no size"));
+        }
+    }
+
+    problems
+}
+
+/// The layers behind an arena, far to near.
+fn parallax_layers(kind: ArenaKind) -> Vec<ParallaxLayer> {
+    match kind {
+        ArenaKind::Classic => vec![
+            ParallaxLayer {
+                depth: 0.3,
+                color: Color::srgba(0.6, 0.7, 1., 0.04),
+                pattern: Pattern::Dots {
+                    spacing: 90.,
+                    radius: 2.,
+                },
+            },
+            ParallaxLayer {
+                depth: 0.7,
+                color: Color::srgba(0.6, 0.7, 1., 0.06),
+                pattern: Pattern::Dots {
+                    spacing: 160.,
+                    radius: 4.,
+                },
+            },
+        ],
+        ArenaKind::Workshop => vec![
+            ParallaxLayer {
+                depth: 0.3,
+                color: Color::srgba(1., 0.7, 0.4, 0.03),
+                pattern: Pattern::Stripes {
+                    spacing: 120.,
+                    width: 30.,
+                },
+            },
+            ParallaxLayer {
+                depth: 0.6,
+                color: Color::srgba(1., 0.7, 0.4, 0.05),
+                pattern: Pattern::Dots {
+                    spacing: 140.,
+                    radius: 3.,
+                },
+            },
+        ],
+        ArenaKind::Drift => vec![
+            ParallaxLayer {
+                depth: 0.2,
+                color: Color::srgba(0.4, 1., 0.9, 0.03),
+                pattern: Pattern::Stripes {
+                    spacing: 80.,
+                    width: 6.,
+                },
+            },
+            ParallaxLayer {
+                depth: 0.5,
+                color: Color::srgba(0.4, 1., 0.9, 0.04),
+                pattern: Pattern::Stripes {
+                    spacing: 200.,
+                    width: 14.,
+                },
+            },
+            ParallaxLayer {
+                depth: 0.9,
+                color: Color::srgba(0.4, 1., 0.9, 0.05),
+                pattern: Pattern::Dots {
+                    spacing: 180.,
+                    radius: 3.,
+                },
+            },
+        ],
+        ArenaKind::Vault => vec![
+            ParallaxLayer {
+                depth: 0.3,
+                color: Color::srgba(1., 0.85, 0.3, 0.03),
+                pattern: Pattern::Dots {
+                    spacing: 70.,
+                    radius: 1.5,
+                },
+            },
+            ParallaxLayer {
+                depth: 0.6,
+                color: Color::srgba(1., 0.85, 0.3, 0.04),
+                pattern: Pattern::Dots {
+                    spacing: 130.,
+                    radius: 3.,
+                },
+            },
+            ParallaxLayer {
+                depth: 0.9,
+                color: Color::srgba(1., 0.85, 0.3, 0.05),
+                pattern: Pattern::Stripes {
+                    spacing: 240.,
+                    width: 40.,
+                },
+            },
+        ],
+    }
+}
+
+fn spawn_parallax_layers(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let area = Bound::arena().inflate(PARALLAX_MARGIN);
+
+    for (index, layer) in parallax_layers(config.arena).into_iter().enumerate() {
+        let material = materials.add(layer.color);
+        let (mesh, positions) = match layer.pattern {
+            Pattern::Dots { spacing, radius } => {
+                let mut positions = Vec::new();
+                let mut x = (area.min.x / spacing).ceil() * spacing;
+                while x <= area.max.x {
+                    let mut y = (area.min.y / spacing).ceil() * spacing;
+                    while y <= area.max.y {
+                        positions.push(Vec2::new(x, y));
+                        y += spacing;
+                    }
+                    x += spacing;
+                }
+                (meshes.add(Circle::new(radius)), positions)
+            }
+            Pattern::Stripes { spacing, width } => {
+                let mut positions = Vec::new();
+                let mut y = (area.min.y / spacing).ceil() * spacing;
+                while y <= area.max.y {
+                    positions.push(Vec2::new(0., y));
+                    y += spacing;
+                }
+                (meshes.add(Rectangle::new(area.width(), width)), positions)
+            }
+        };
+
+        // Nearer layers draw over farther ones, still behind the decorations.
+        let z = RenderLayer::Backdrop.z() + index as f32 * 0.1;
+        commands
+            .spawn((
+                Parallax { depth: layer.depth },
+                InGameEntity,
+                Transform::from_xyz(0., 0., z),
+                Visibility::default(),
+            ))
+            .with_children(|builder| {
+                for position in positions {
+                    builder.spawn((
+                        Mesh2d(mesh.clone()),
+                        MeshMaterial2d(material.clone()),
+                        Transform::from_translation(position.extend(0.)),
+                    ));
+                }
+            });
+    }
+}
+
+fn shift_parallax_layers(
+    config: Res<GameConfig>,
+    gravity_direction: Res<GravityDirection>,
+    player_query: Query<&Transform, (With<Player>, Without<Parallax>)>,
+    mut layer_query: Query<(&Parallax, &mut Transform)>,
+    time: Res<Time>,
+) {
+    if config.reduced_motion {
+        return;
+    }
+    let player_position = player_query
+        .get_single()
+        .map_or(Vec2::ZERO, |transform| transform.translation.truncate());
+
+    let catch_up = (PARALLAX_SMOOTHING * time.delta_secs()).min(1.);
+    for (parallax, mut transform) in layer_query.iter_mut() {
+        let target = (-player_position * PLAYER_SHIFT
+            + gravity_direction.0.as_vec2() * GRAVITY_SHIFT)
+            * parallax.depth;
+        let offset = transform.translation.truncate().lerp(target, catch_up);
+        transform.translation = offset.extend(transform.translation.z);
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{
+    GameState, InGameSet, Player, RunState,
+    confirm_quit::confirm_quit_closed,
+    heatmap::{PositionHeatmap, spawn_heatmap},
+    menu::spawn_menu_button,
+    prompts::{AnyInput, MenuAction, MenuInput, Prompt},
+    saved_run::SaveRun,
+    settings::{SettingsState, settings_closed},
+    tutorial::Tutorial,
+};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+const IDLE_TIMEOUT: f32 = 20.;
+const STATIONARY_SPEED: f32 = 5.;
+
+/// Freezes physics while a run is paused. Escape pauses into a menu with
+/// Resume, Restart, Settings, Photo mode, Save and quit, and Quit; the run
+/// also pauses by itself when nobody has touched the controls for a while and
+/// the player is sitting still, so a run doesn't quietly die while someone is
+/// away.
+pub struct PausePlugin;
+
+#[derive(Resource, Default)]
+struct IdleTime(f32);
+
+/// Whether the pause menu or the idle screen shows while paused.
+#[derive(Resource, Default, Clone, Copy, PartialEq)]
+pub enum PauseReason {
+    #[default]
+    Requested,
+    Idle,
+}
+
+#[derive(Component, Clone, Copy)]
+enum PauseButton {
+    Resume,
+    Restart,
+    Settings,
+    PhotoMode,
+    SaveAndQuit,
+    Quit,
+}
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<IdleTime>()
+            .init_resource::<PauseReason>()
+            .add_systems(
+                OnEnter(RunState::Paused),
+                (pause_physics, setup_pause_screen),
+            )
+            .add_systems(OnExit(RunState::Paused), resume_physics)
+            .add_systems(Update, (request_pause, detect_idle).in_set(InGameSet))
+            .add_systems(
+                Update,
+                (resume_on_input, choose_pause_option).run_if(
+                    in_state(RunState::Paused)
+                        .and(settings_closed)
+                        .and(confirm_quit_closed),
+                ),
+            );
+    }
+}
+
+fn detect_idle(
+    mut any_input: AnyInput,
+    player_query: Query<&LinearVelocity, With<Player>>,
+    mut idle_time: ResMut<IdleTime>,
+    time: Res<Time>,
+    mut pause_reason: ResMut<PauseReason>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+) {
+    let stationary = player_query
+        .iter()
+        .all(|velocity| velocity.length() < STATIONARY_SPEED);
+
+    if !stationary || any_input.pressed() || any_input.mouse_moved() {
+        idle_time.0 = 0.;
+        return;
+    }
+
+    idle_time.0 += time.delta_secs();
+    if idle_time.0 >= IDLE_TIMEOUT {
+        *pause_reason = PauseReason::Idle;
+        next_run_state.set(RunState::Paused);
+    }
+}
+
+fn request_pause(
+    menu_input: MenuInput,
+    mut pause_reason: ResMut<PauseReason>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+) {
+    if menu_input.just_pressed(MenuAction::Pause) {
+        *pause_reason = PauseReason::Requested;
+        next_run_state.set(RunState::Paused);
+    }
+}
+
+fn resume_on_input(
+    any_input: AnyInput,
+    pause_reason: Res<PauseReason>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+) {
+    if *pause_reason != PauseReason::Idle {
+        return;
+    }
+
+    if any_input.just_pressed() {
+        next_run_state.set(RunState::Playing);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn choose_pause_option(
+    button_query: Query<(&Interaction, &PauseButton), Changed<Interaction>>,
+    menu_input: MenuInput,
+    pause_reason: Res<PauseReason>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_settings_state: ResMut<NextState<SettingsState>>,
+    tutorial: Res<Tutorial>,
+    mut save_writer: EventWriter<SaveRun>,
+) {
+    if *pause_reason != PauseReason::Requested {
+        return;
+    }
+
+    let clicked = button_query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| *button);
+    let chosen = if menu_input.just_pressed(MenuAction::Pause) {
+        Some(PauseButton::Resume)
+    } else if menu_input.just_pressed(MenuAction::PlayAgain) {
+        Some(PauseButton::Restart)
+    } else if menu_input.just_pressed(MenuAction::OpenSettings) {
+        Some(PauseButton::Settings)
+    } else if menu_input.just_pressed(MenuAction::PhotoMode) {
+        Some(PauseButton::PhotoMode)
+    } else if menu_input.just_pressed(MenuAction::SaveRun) && !tutorial.running {
+        Some(PauseButton::SaveAndQuit)
+    } else if menu_input.just_pressed(MenuAction::QuitRun) {
+        Some(PauseButton::Quit)
+    } else {
+        clicked
+    };
+
+    match chosen {
+        Some(PauseButton::Resume) => next_run_state.set(RunState::Playing),
+        Some(PauseButton::Restart) => next_game_state.set(GameState::Restarting),
+        Some(PauseButton::Settings) => next_settings_state.set(SettingsState::Open),
+        Some(PauseButton::PhotoMode) => next_run_state.set(RunState::Photo),
+        Some(PauseButton::SaveAndQuit) => {
+            save_writer.send(SaveRun);
+        }
+        Some(PauseButton::Quit) => next_game_state.set(GameState::Menu),
+        None => {}
+    }
+}
+
+fn pause_physics(mut physics_time: ResMut<Time<Physics>>) {
+    physics_time.pause();
+}
+
+fn resume_physics(mut physics_time: ResMut<Time<Physics>>, mut idle_time: ResMut<IdleTime>) {
+    physics_time.unpause();
+    idle_time.0 = 0.;
+}
+
+fn setup_pause_screen(
+    mut commands: Commands,
+    pause_reason: Res<PauseReason>,
+    heatmap: Res<PositionHeatmap>,
+    tutorial: Res<Tutorial>,
+) {
+    let mut screen = commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            row_gap: Val::Px(20.),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.5)),
+        StateScoped(RunState::Paused),
+    ));
+
+    match *pause_reason {
+        PauseReason::Requested => screen.with_children(|builder| {
+            builder.spawn((
+                Text::new("Paused"),
+                TextFont {
+                    font_size: 60.,
+                    ..default()
+                },
+            ));
+            spawn_menu_button(builder, PauseButton::Resume, "Resume");
+            spawn_menu_button(builder, PauseButton::Restart, "Restart");
+            spawn_menu_button(builder, PauseButton::Settings, "Settings");
+            spawn_menu_button(builder, PauseButton::PhotoMode, "Photo mode");
+            // Tutorial runs aren't worth coming back to.
+            if !tutorial.running {
+                spawn_menu_button(builder, PauseButton::SaveAndQuit, "Save and quit");
+            }
+            spawn_menu_button(builder, PauseButton::Quit, "Quit");
+            let mut prompts = vec![
+                (MenuAction::Pause, "resume"),
+                (MenuAction::PlayAgain, "restart"),
+                (MenuAction::OpenSettings, "settings"),
+                (MenuAction::PhotoMode, "photo mode"),
+            ];
+            if !tutorial.running {
+                prompts.push((MenuAction::SaveRun, "save and quit"));
+            }
+            prompts.push((MenuAction::QuitRun, "quit to menu"));
+            builder.spawn((
+                Text::default(),
+                Prompt(prompts),
+                TextFont {
+                    font_size: 20.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+            spawn_heatmap(builder, &heatmap);
+        }),
+        PauseReason::Idle => screen.with_children(|builder| {
+            builder.spawn((
+                Text::new("Are you still there?"),
+                TextFont {
+                    font_size: 60.,
+                    ..default()
+                },
+            ));
+            builder.spawn((
+                Text::new("press any key to continue"),
+                TextFont {
+                    font_size: 24.,
+                    ..default()
+                },
+            ));
+        }),
+    };
+}
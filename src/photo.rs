@@ -0,0 +1,181 @@
+use crate::{
+    GameRng, RunState,
+    prompts::{MenuAction, MenuInput},
+    toast::ShowToast,
+};
+use avian2d::prelude::*;
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    render::view::screenshot::{Screenshot, save_to_disk},
+    utils::SystemTime,
+};
+use std::path::PathBuf;
+
+/// In world units per second at normal zoom.
+const PAN_SPEED: f32 = 600.;
+/// How much one notch of the scroll wheel zooms by.
+const ZOOM_STEP: f32 = 1.1;
+/// In zoom steps per second, for the keys and triggers.
+const ZOOM_SPEED: f32 = 8.;
+/// The projection scale, smaller being closer in.
+const ZOOM_RANGE: std::ops::RangeInclusive<f32> = 0.25..=3.;
+const STICK_DEAD_ZONE: f32 = 0.2;
+
+/// Entered from the pause menu: the run stays frozen, the HUD and every other
+/// bit of UI goes, and the camera can be panned and zoomed freely to line up
+/// a shot of the arena, which Space saves as a PNG. Leaving puts the camera
+/// back where it was and returns to the pause menu.
+pub struct PhotoModePlugin;
+
+#[derive(Resource, Default)]
+struct PhotoMode {
+    /// UI roots that were showing before, to bring back afterwards.
+    hidden: Vec<Entity>,
+    camera: Option<(Transform, f32)>,
+}
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhotoMode>()
+            .add_systems(OnEnter(RunState::Photo), enter_photo_mode)
+            .add_systems(OnExit(RunState::Photo), leave_photo_mode)
+            .add_systems(
+                Update,
+                (move_photo_camera, take_photo, close_photo_mode)
+                    .chain()
+                    .run_if(in_state(RunState::Photo)),
+            );
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn photo_path(file_name: String) -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("shape-eater")
+        .join("photos")
+        .join(file_name)
+}
+
+/// Downloaded under just its name.
+#[cfg(target_arch = "wasm32")]
+fn photo_path(file_name: String) -> PathBuf {
+    PathBuf::from(file_name)
+}
+
+#[allow(clippy::type_complexity)]
+fn enter_photo_mode(
+    mut photo_mode: ResMut<PhotoMode>,
+    mut physics_time: ResMut<Time<Physics>>,
+    mut ui_query: Query<(Entity, &mut Visibility), (With<Node>, Without<Parent>)>,
+    camera_query: Query<(&Transform, &OrthographicProjection), With<Camera2d>>,
+) {
+    physics_time.pause();
+
+    photo_mode.hidden.clear();
+    for (entity, mut visibility) in ui_query.iter_mut() {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+            photo_mode.hidden.push(entity);
+        }
+    }
+    photo_mode.camera = camera_query
+        .get_single()
+        .ok()
+        .map(|(transform, projection)| (*transform, projection.scale));
+}
+
+fn leave_photo_mode(
+    mut photo_mode: ResMut<PhotoMode>,
+    mut physics_time: ResMut<Time<Physics>>,
+    mut visibility_query: Query<&mut Visibility>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+) {
+    physics_time.unpause();
+
+    for entity in photo_mode.hidden.drain(..) {
+        if let Ok(mut visibility) = visibility_query.get_mut(entity) {
+            *visibility = Visibility::Inherited;
+        }
+    }
+    if let Some((transform, scale)) = photo_mode.camera.take() {
+        for (mut camera_transform, mut projection) in camera_query.iter_mut() {
+            *camera_transform = transform;
+            projection.scale = scale;
+        }
+    }
+}
+
+/// Arrow keys or WASD and the left stick pan; the scroll wheel, + and -, or
+/// the triggers zoom.
+fn move_photo_camera(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut wheel_reader: EventReader<MouseWheel>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+    time: Res<Time<Real>>,
+) {
+    let pressed = |codes: [KeyCode; 2]| keys.any_pressed(codes) as i32 as f32;
+    let mut pan = Vec2::new(
+        pressed([KeyCode::ArrowRight, KeyCode::KeyD])
+            - pressed([KeyCode::ArrowLeft, KeyCode::KeyA]),
+        pressed([KeyCode::ArrowUp, KeyCode::KeyW]) - pressed([KeyCode::ArrowDown, KeyCode::KeyS]),
+    );
+    let mut zoom = pressed([KeyCode::Minus, KeyCode::NumpadSubtract])
+        - pressed([KeyCode::Equal, KeyCode::NumpadAdd]);
+    for gamepad in gamepads.iter() {
+        let stick = gamepad.left_stick();
+        if stick.length() > STICK_DEAD_ZONE {
+            pan += stick;
+        }
+        zoom += gamepad.pressed(GamepadButton::LeftTrigger2) as i32 as f32
+            - gamepad.pressed(GamepadButton::RightTrigger2) as i32 as f32;
+    }
+
+    let mut zoom_factor = ZOOM_STEP.powf(zoom * ZOOM_SPEED * time.delta_secs());
+    for wheel in wheel_reader.read() {
+        let notches = match wheel.unit {
+            MouseScrollUnit::Line => wheel.y,
+            MouseScrollUnit::Pixel => wheel.y / 100.,
+        };
+        zoom_factor *= ZOOM_STEP.powf(-notches);
+    }
+
+    for (mut transform, mut projection) in camera_query.iter_mut() {
+        projection.scale =
+            (projection.scale * zoom_factor).clamp(*ZOOM_RANGE.start(), *ZOOM_RANGE.end());
+        let step = pan.clamp_length_max(1.) * PAN_SPEED * projection.scale * time.delta_secs();
+        transform.translation += step.extend(0.);
+    }
+}
+
+fn take_photo(
+    mut commands: Commands,
+    menu_input: MenuInput,
+    game_rng: Res<GameRng>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    if !menu_input.just_pressed(MenuAction::TakePhoto) {
+        return;
+    }
+
+    let taken_at = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let path = photo_path(format!("photo-{}-{taken_at}.png", game_rng.seed));
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Err(error) = path.parent().map_or(Ok(()), std::fs::create_dir_all) {
+        warn!("failed to create the photos directory: {error}");
+    }
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(path.clone()));
+    toast_writer.send(ShowToast(format!("Saved {}", path.display())));
+}
+
+fn close_photo_mode(menu_input: MenuInput, mut next_run_state: ResMut<NextState<RunState>>) {
+    if menu_input.just_pressed(MenuAction::Pause) {
+        next_run_state.set(RunState::Paused);
+    }
+}
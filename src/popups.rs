@@ -0,0 +1,174 @@
+use crate::{BallEaten, GameState, InGameSet, config::GameConfig, layers::RenderLayer};
+use bevy::prelude::*;
+
+const POPUP_LIFETIME: f32 = 1.;
+const POPUP_RISE: f32 = 40.;
+const POPUP_FONT_SIZE: f32 = 24.;
+/// Eats closer together than this keep the combo going.
+const COMBO_WINDOW: f32 = 1.;
+/// The combo length each tier starts at, and its popup color.
+const COMBO_TIERS: [(u32, Color); 4] = [
+    (1, Color::WHITE),
+    (3, Color::srgb(1., 0.9, 0.3)),
+    (6, Color::srgb(1., 0.55, 0.1)),
+    (10, Color::srgb(1., 0.3, 0.8)),
+];
+
+/// Shows the points each eaten ball scored as a number that rises from where
+/// the player touched it and fades out over a second, colored by how long
+/// the current combo of quick eats is. Popups are pooled like ball labels,
+/// and not shown at all in reduced motion.
+pub struct ScorePopupPlugin;
+
+#[derive(Component)]
+struct ScorePopup {
+    origin: Vec2,
+    age: f32,
+}
+
+#[derive(Resource, Default)]
+struct PopupPool(Vec<Entity>);
+
+#[derive(Resource, Default)]
+struct Combo {
+    length: u32,
+    since_last_eat: f32,
+}
+
+impl Plugin for ScorePopupPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PopupPool>()
+            .init_resource::<Combo>()
+            .add_systems(OnEnter(GameState::InGame), reset_combo)
+            .add_systems(OnExit(GameState::InGame), recycle_all_popups)
+            .add_systems(
+                Update,
+                (spawn_score_popups, animate_score_popups)
+                    .chain()
+                    .in_set(InGameSet),
+            );
+    }
+}
+
+fn combo_color(length: u32) -> Color {
+    COMBO_TIERS
+        .iter()
+        .rev()
+        .find(|(start, _)| length >= *start)
+        .map_or(Color::WHITE, |(_, color)| *color)
+}
+
+fn reset_combo(mut combo: ResMut<Combo>) {
+    *combo = Combo::default();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_score_popups(
+    mut commands: Commands,
+    mut pool: ResMut<PopupPool>,
+    mut combo: ResMut<Combo>,
+    mut ball_eaten_reader: EventReader<BallEaten>,
+    mut popup_query: Query<(
+        &mut ScorePopup,
+        &mut Text2d,
+        &mut TextColor,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+) {
+    combo.since_last_eat += time.delta_secs();
+
+    for BallEaten {
+        position, points, ..
+    } in ball_eaten_reader.read()
+    {
+        if combo.since_last_eat > COMBO_WINDOW {
+            combo.length = 0;
+        }
+        combo.length += 1;
+        combo.since_last_eat = 0.;
+
+        if config.reduced_motion {
+            continue;
+        }
+
+        let text = format!("+{points}");
+        let color = combo_color(combo.length);
+        let pooled_popup = pool
+            .0
+            .pop()
+            .and_then(|popup| popup_query.get_mut(popup).ok());
+
+        if let Some((mut popup, mut popup_text, mut text_color, mut transform, mut visibility)) =
+            pooled_popup
+        {
+            *popup = ScorePopup {
+                origin: *position,
+                age: 0.,
+            };
+            popup_text.0 = text;
+            text_color.0 = color;
+            transform.translation = RenderLayer::Popups.at(*position);
+            *visibility = Visibility::Inherited;
+        } else {
+            commands.spawn((
+                ScorePopup {
+                    origin: *position,
+                    age: 0.,
+                },
+                Text2d::new(text),
+                TextFont {
+                    font_size: POPUP_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(color),
+                Transform::from_translation(RenderLayer::Popups.at(*position)),
+            ));
+        }
+    }
+}
+
+fn animate_score_popups(
+    mut pool: ResMut<PopupPool>,
+    mut popup_query: Query<(
+        Entity,
+        &mut ScorePopup,
+        &mut Transform,
+        &mut TextColor,
+        &mut Visibility,
+    )>,
+    time: Res<Time>,
+) {
+    for (entity, mut popup, mut transform, mut text_color, mut visibility) in popup_query.iter_mut()
+    {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+
+        popup.age += time.delta_secs();
+        if popup.age >= POPUP_LIFETIME {
+            *visibility = Visibility::Hidden;
+            pool.0.push(entity);
+            continue;
+        }
+
+        let progress = popup.age / POPUP_LIFETIME;
+        transform.translation =
+            RenderLayer::Popups.at(popup.origin + Vec2::Y * POPUP_RISE * progress);
+        text_color.0.set_alpha(1. - progress);
+    }
+}
+
+fn recycle_all_popups(
+    mut pool: ResMut<PopupPool>,
+    mut popup_query: Query<(Entity, &mut Visibility), With<ScorePopup>>,
+) {
+    for (entity, mut visibility) in popup_query.iter_mut() {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+            pool.0.push(entity);
+        }
+    }
+}
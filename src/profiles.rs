@@ -0,0 +1,86 @@
+use crate::storage::Storage;
+use bevy::prelude::*;
+
+const DEFAULT_PROFILE: &str = "default";
+const PROFILES_KEY: &str = "profiles";
+const LAST_PROFILE_KEY: &str = "last_profile";
+
+/// Everyone sharing a machine can keep a profile of their own, picked with
+/// `--profile <name>` at launch, or otherwise whichever was played last.
+/// Each profile's settings, stats and high scores are saved apart from the
+/// others'. The default profile keeps the data saved before there were
+/// profiles.
+#[derive(Resource)]
+pub struct Profiles {
+    pub current: String,
+    /// Every profile played on this machine, oldest first.
+    pub known: Vec<String>,
+}
+
+impl Profiles {
+    /// Picks the profile to play as, remembering it in `shared` for next
+    /// time.
+    pub fn select(requested: Option<String>, shared: &mut Storage) -> Self {
+        let mut known: Vec<String> = shared
+            .load::<String>(PROFILES_KEY)
+            .map(|saved| {
+                saved
+                    .split(',')
+                    .filter(|name| is_valid_name(name))
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let current = requested
+            .or_else(|| shared.load(LAST_PROFILE_KEY))
+            .filter(|name| is_valid_name(name))
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+        if !known.contains(&current) {
+            known.push(current.clone());
+            shared.save(PROFILES_KEY, known.join(","));
+        }
+        shared.save(LAST_PROFILE_KEY, &current);
+
+        Profiles { current, known }
+    }
+
+    /// Where the current profile's data is kept.
+    pub fn storage(&self) -> Storage {
+        Storage::for_platform((self.current != DEFAULT_PROFILE).then_some(self.current.as_str()))
+    }
+
+    /// Whether there's more than the default profile around, so it's worth
+    /// saying whose this is.
+    pub fn in_use(&self) -> bool {
+        self.known.iter().any(|name| name != DEFAULT_PROFILE)
+    }
+}
+
+/// Letters, digits, `-` and `_` only, since profiles name directories.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::storage::FileStorage;
+
+    #[test]
+    fn named_profiles_save_next_to_the_profile_list() {
+        let root = std::env::temp_dir().join(format!("shape-eater-test-{}", std::process::id()));
+        let mut shared = Storage(Box::new(FileStorage::in_dir(root.clone(), None)));
+        Profiles::select(Some("sam".to_string()), &mut shared);
+
+        let mut profile = Storage(Box::new(FileStorage::in_dir(root.clone(), Some("sam"))));
+        profile.save("high_score", 42);
+        let saved = profile.load::<i32>("high_score");
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(saved, Some(42));
+    }
+}
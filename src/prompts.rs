@@ -0,0 +1,332 @@
+use crate::settings::{MovementKeys, Settings};
+use bevy::{
+    ecs::system::SystemParam,
+    input::{InputSystem, mouse::MouseMotion},
+    prelude::*,
+    ui::UiSystem,
+};
+
+const SONY_VENDOR_ID: u16 = 0x054c;
+
+/// Menu actions, and moving, flipping gravity, shedding and rewinding during
+/// a run, can be triggered from the keyboard or any gamepad, and the prompts
+/// naming them show the keys or buttons of whichever device was used last,
+/// switching as soon as a different one is touched.
+pub struct PromptPlugin;
+
+#[derive(Resource, Default, Clone, Copy, PartialEq)]
+pub enum InputDevice {
+    #[default]
+    Keyboard,
+    Xbox,
+    PlayStation,
+}
+
+#[derive(Clone, Copy)]
+pub enum MenuAction {
+    Play,
+    Quit,
+    Pause,
+    QuitRun,
+    PlayAgain,
+    NewGamePlus,
+    /// New Game+ from the menu, as opposed to on from a win.
+    PlayNewGamePlus,
+    HighScores,
+    CycleMode,
+    CycleArena,
+    CycleSort,
+    EnterSeed,
+    OpenSettings,
+    VolumeDown,
+    VolumeUp,
+    ToggleFullscreen,
+    ToggleMovementKeys,
+    ToggleHaptics,
+    PlayWeekly,
+    Tutorial,
+    Credits,
+    ShareCard,
+    CycleCursor,
+    Difficulty,
+    ChooseMode,
+    SaveRun,
+    Continue,
+    PhotoMode,
+    TakePhoto,
+    Cosmetics,
+    Achievements,
+    /// Held rather than pressed, on the keys picked in the settings.
+    MoveLeft,
+    MoveRight,
+    FlipGravity,
+    Shed,
+    Rewind,
+}
+
+/// Reads [`MenuAction`]s from the keyboard and every connected gamepad.
+#[derive(SystemParam)]
+pub struct MenuInput<'w, 's> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+    settings: Res<'w, Settings>,
+    device: Res<'w, InputDevice>,
+}
+
+/// Reads whether anything at all was touched, on the keyboard, the mouse or
+/// any gamepad, for telling when the player is around.
+#[derive(SystemParam)]
+pub struct AnyInput<'w, 's> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse_buttons: Res<'w, ButtonInput<MouseButton>>,
+    mouse_motion: EventReader<'w, 's, MouseMotion>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+/// Text listing what each action does, kept in sync with the
+/// [`InputDevice`]. The text it's on is overwritten.
+#[derive(Component)]
+pub struct Prompt(pub Vec<(MenuAction, &'static str)>);
+
+impl Plugin for PromptPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputDevice>()
+            .add_systems(PreUpdate, detect_input_device.after(InputSystem))
+            .add_systems(PostUpdate, update_prompts.before(UiSystem::Prepare));
+    }
+}
+
+impl MenuAction {
+    fn key(self, movement_keys: MovementKeys) -> KeyCode {
+        match self {
+            MenuAction::Play => KeyCode::Enter,
+            MenuAction::Quit => KeyCode::Escape,
+            MenuAction::Pause => KeyCode::Escape,
+            MenuAction::QuitRun => KeyCode::KeyQ,
+            MenuAction::PlayAgain => KeyCode::KeyR,
+            MenuAction::NewGamePlus => KeyCode::KeyN,
+            MenuAction::PlayNewGamePlus => KeyCode::KeyN,
+            MenuAction::HighScores => KeyCode::KeyH,
+            MenuAction::CycleMode => KeyCode::KeyM,
+            MenuAction::CycleArena => KeyCode::KeyA,
+            MenuAction::CycleSort => KeyCode::KeyS,
+            MenuAction::EnterSeed => KeyCode::KeyS,
+            MenuAction::OpenSettings => KeyCode::KeyO,
+            MenuAction::VolumeDown => KeyCode::ArrowLeft,
+            MenuAction::VolumeUp => KeyCode::ArrowRight,
+            MenuAction::ToggleFullscreen => KeyCode::KeyF,
+            MenuAction::ToggleMovementKeys => KeyCode::KeyK,
+            MenuAction::ToggleHaptics => KeyCode::KeyV,
+            MenuAction::PlayWeekly => KeyCode::KeyW,
+            MenuAction::Tutorial => KeyCode::KeyT,
+            MenuAction::Credits => KeyCode::KeyC,
+            MenuAction::ShareCard => KeyCode::KeyP,
+            MenuAction::CycleCursor => KeyCode::KeyC,
+            MenuAction::Difficulty => KeyCode::KeyD,
+            MenuAction::ChooseMode => KeyCode::KeyM,
+            MenuAction::SaveRun => KeyCode::KeyS,
+            MenuAction::Continue => KeyCode::KeyR,
+            MenuAction::PhotoMode => KeyCode::KeyP,
+            MenuAction::TakePhoto => KeyCode::Space,
+            MenuAction::Cosmetics => KeyCode::KeyL,
+            MenuAction::Achievements => KeyCode::KeyB,
+            MenuAction::MoveLeft => movement_keys.keys().0,
+            MenuAction::MoveRight => movement_keys.keys().1,
+            MenuAction::FlipGravity => KeyCode::Space,
+            MenuAction::Shed => KeyCode::KeyS,
+            MenuAction::Rewind => KeyCode::KeyZ,
+        }
+    }
+
+    fn button(self) -> GamepadButton {
+        match self {
+            MenuAction::Play => GamepadButton::South,
+            MenuAction::Quit => GamepadButton::East,
+            MenuAction::Pause => GamepadButton::Start,
+            MenuAction::QuitRun => GamepadButton::Select,
+            MenuAction::PlayAgain => GamepadButton::South,
+            MenuAction::NewGamePlus => GamepadButton::West,
+            MenuAction::PlayNewGamePlus => GamepadButton::LeftThumb,
+            MenuAction::HighScores => GamepadButton::North,
+            MenuAction::CycleMode => GamepadButton::LeftTrigger,
+            MenuAction::CycleArena => GamepadButton::RightTrigger,
+            MenuAction::CycleSort => GamepadButton::East,
+            MenuAction::EnterSeed => GamepadButton::West,
+            MenuAction::OpenSettings => GamepadButton::North,
+            MenuAction::VolumeDown => GamepadButton::DPadLeft,
+            MenuAction::VolumeUp => GamepadButton::DPadRight,
+            MenuAction::ToggleFullscreen => GamepadButton::North,
+            MenuAction::ToggleMovementKeys => GamepadButton::West,
+            MenuAction::ToggleHaptics => GamepadButton::DPadUp,
+            MenuAction::PlayWeekly => GamepadButton::RightTrigger,
+            MenuAction::Tutorial => GamepadButton::LeftTrigger,
+            MenuAction::Credits => GamepadButton::Select,
+            MenuAction::ShareCard => GamepadButton::DPadUp,
+            MenuAction::CycleCursor => GamepadButton::DPadDown,
+            MenuAction::Difficulty => GamepadButton::DPadUp,
+            MenuAction::ChooseMode => GamepadButton::DPadLeft,
+            MenuAction::SaveRun => GamepadButton::West,
+            MenuAction::Continue => GamepadButton::DPadRight,
+            MenuAction::PhotoMode => GamepadButton::DPadUp,
+            MenuAction::TakePhoto => GamepadButton::South,
+            MenuAction::Cosmetics => GamepadButton::DPadDown,
+            MenuAction::Achievements => GamepadButton::RightThumb,
+            MenuAction::MoveLeft => GamepadButton::DPadLeft,
+            MenuAction::MoveRight => GamepadButton::DPadRight,
+            MenuAction::FlipGravity => GamepadButton::South,
+            MenuAction::Shed => GamepadButton::West,
+            MenuAction::Rewind => GamepadButton::North,
+        }
+    }
+
+    fn glyph(self, device: InputDevice, movement_keys: MovementKeys) -> &'static str {
+        let (keyboard, xbox, playstation) = match self {
+            MenuAction::Play => ("Enter", "A", "Cross"),
+            MenuAction::Quit => ("Esc", "B", "Circle"),
+            MenuAction::Pause => ("Esc", "Menu", "Options"),
+            MenuAction::QuitRun => ("Q", "View", "Create"),
+            MenuAction::PlayAgain => ("R", "A", "Cross"),
+            MenuAction::NewGamePlus => ("N", "X", "Square"),
+            MenuAction::PlayNewGamePlus => ("N", "LS", "L3"),
+            MenuAction::HighScores => ("H", "Y", "Triangle"),
+            MenuAction::CycleMode => ("M", "LB", "L1"),
+            MenuAction::CycleArena => ("A", "RB", "R1"),
+            MenuAction::CycleSort => ("S", "B", "Circle"),
+            MenuAction::EnterSeed => ("S", "X", "Square"),
+            MenuAction::OpenSettings => ("O", "Y", "Triangle"),
+            MenuAction::VolumeDown => ("Left", "Left", "Left"),
+            MenuAction::VolumeUp => ("Right", "Right", "Right"),
+            MenuAction::ToggleFullscreen => ("F", "Y", "Triangle"),
+            MenuAction::ToggleMovementKeys => ("K", "X", "Square"),
+            MenuAction::ToggleHaptics => ("V", "Up", "Up"),
+            MenuAction::PlayWeekly => ("W", "RB", "R1"),
+            MenuAction::Tutorial => ("T", "LB", "L1"),
+            MenuAction::Credits => ("C", "View", "Create"),
+            MenuAction::ShareCard => ("P", "Up", "Up"),
+            MenuAction::CycleCursor => ("C", "Down", "Down"),
+            MenuAction::Difficulty => ("D", "Up", "Up"),
+            MenuAction::ChooseMode => ("M", "Left", "Left"),
+            MenuAction::SaveRun => ("S", "X", "Square"),
+            MenuAction::Continue => ("R", "Right", "Right"),
+            MenuAction::PhotoMode => ("P", "Up", "Up"),
+            MenuAction::TakePhoto => ("Space", "A", "Cross"),
+            MenuAction::Cosmetics => ("L", "Down", "Down"),
+            MenuAction::Achievements => ("B", "RS", "R3"),
+            MenuAction::MoveLeft => match movement_keys {
+                MovementKeys::AD => ("A", "Left", "Left"),
+                MovementKeys::Arrows => ("Left", "Left", "Left"),
+            },
+            MenuAction::MoveRight => match movement_keys {
+                MovementKeys::AD => ("D", "Right", "Right"),
+                MovementKeys::Arrows => ("Right", "Right", "Right"),
+            },
+            MenuAction::FlipGravity => ("Space", "A", "Cross"),
+            MenuAction::Shed => ("S", "X", "Square"),
+            MenuAction::Rewind => ("Z", "Y", "Triangle"),
+        };
+
+        match device {
+            InputDevice::Keyboard => keyboard,
+            InputDevice::Xbox => xbox,
+            InputDevice::PlayStation => playstation,
+        }
+    }
+}
+
+impl MenuInput<'_, '_> {
+    pub fn just_pressed(&self, action: MenuAction) -> bool {
+        self.keys
+            .just_pressed(action.key(self.settings.movement_keys))
+            || self
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.just_pressed(action.button()))
+    }
+
+    pub fn pressed(&self, action: MenuAction) -> bool {
+        self.keys.pressed(action.key(self.settings.movement_keys))
+            || self
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.pressed(action.button()))
+    }
+
+    /// The key or button for `action` on the device used last, for text
+    /// that isn't a [`Prompt`].
+    pub fn glyph(&self, action: MenuAction) -> &'static str {
+        action.glyph(*self.device, self.settings.movement_keys)
+    }
+}
+
+impl AnyInput<'_, '_> {
+    /// A key or button went down this frame.
+    pub fn just_pressed(&self) -> bool {
+        self.keys.get_just_pressed().next().is_some()
+            || self.mouse_buttons.get_just_pressed().next().is_some()
+            || self
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.get_just_pressed().next().is_some())
+    }
+
+    /// A key or button is held down.
+    pub fn pressed(&self) -> bool {
+        self.keys.get_pressed().next().is_some()
+            || self.mouse_buttons.get_pressed().next().is_some()
+            || self
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.get_pressed().next().is_some())
+    }
+
+    pub fn mouse_moved(&mut self) -> bool {
+        self.mouse_motion.read().count() > 0
+    }
+}
+
+fn detect_input_device(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut device: ResMut<InputDevice>,
+) {
+    let touched = if keys.get_just_pressed().next().is_some() {
+        InputDevice::Keyboard
+    } else if let Some(gamepad) = gamepads
+        .iter()
+        .find(|gamepad| gamepad.get_just_pressed().next().is_some())
+    {
+        if gamepad.vendor_id() == Some(SONY_VENDOR_ID) {
+            InputDevice::PlayStation
+        } else {
+            InputDevice::Xbox
+        }
+    } else {
+        return;
+    };
+
+    device.set_if_neq(touched);
+}
+
+fn update_prompts(
+    device: Res<InputDevice>,
+    settings: Res<Settings>,
+    mut prompt_query: Query<(Ref<Prompt>, &mut Text)>,
+) {
+    for (prompt, mut text) in prompt_query.iter_mut() {
+        if !prompt.is_changed() && !device.is_changed() && !settings.is_changed() {
+            continue;
+        }
+
+        text.0 = prompt
+            .0
+            .iter()
+            .map(|(action, label)| {
+                format!(
+                    "{} - {label}",
+                    action.glyph(*device, settings.movement_keys)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("    ");
+    }
+}
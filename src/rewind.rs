@@ -0,0 +1,470 @@
+use crate::{
+    Ball, FIXED_TIMESTEP_HZ, GameState, InGameEntity, InGameSet, Numbered, Player, RunState,
+    ball_kinds::{BallKinds, OfKind},
+    config::GameConfig,
+    eggs::{Egg, spawn_egg},
+    prompts::{MenuAction, MenuInput, Prompt},
+    recorded_run,
+    safe_area::KeepInSafeArea,
+    score::Score,
+    shedding::Fragment,
+    spawn_numbered_ball,
+    stats::{RunModifier, RunStats},
+    storage::Storage,
+    toast::ShowToast,
+};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use std::collections::{HashSet, VecDeque};
+
+const UNLOCKED_KEY: &str = "rewind_unlocked";
+const REWIND_SECONDS: f64 = 3.;
+const CHARGES_PER_RUN: u32 = 1;
+/// Reaching this number in any run unlocks rewinding for good.
+const UNLOCK_NUMBER: i32 = 100;
+/// Snapshots stepped back through per fixed tick, so rewinding plays back
+/// faster than the run went forward.
+const PLAYBACK_SPEED: usize = 2;
+
+/// Once unlocked, [`MenuAction::Rewind`] spends a charge to rewind the last few seconds
+/// of the run: the player and the balls play back in reverse, then the run
+/// carries on from there. A rolling buffer of snapshots is kept every fixed
+/// tick for this. Points and eating stats go back with the balls, so eating
+/// them again isn't worth anything extra, and eggs go back to how far along
+/// they were. Shed fragments aren't rewound.
+pub struct RewindPlugin;
+
+#[derive(Resource, Default)]
+struct Rewind {
+    unlocked: bool,
+    charges: u32,
+    history: VecDeque<Snapshot>,
+    /// The snapshot playback got to, which the run resumes from.
+    rewound_to: Option<Snapshot>,
+}
+
+struct Snapshot {
+    player_number: i32,
+    player: BodySnapshot,
+    balls: Vec<(Entity, BallSnapshot)>,
+    eggs: Vec<(Entity, EggSnapshot)>,
+    score: i32,
+    balls_eaten: u32,
+    largest_ball_eaten: i32,
+}
+
+#[derive(Clone, Copy)]
+struct BodySnapshot {
+    position: Vec2,
+    rotation: Rotation,
+    linear_velocity: Vec2,
+    angular_velocity: f32,
+}
+
+struct BallSnapshot {
+    number: i32,
+    kind: Option<String>,
+    body: BodySnapshot,
+}
+
+#[derive(Clone, Copy)]
+struct EggSnapshot {
+    number: i32,
+    position: Vec2,
+    facing: Vec2,
+    incubated: f32,
+}
+
+#[derive(Component)]
+struct RewindIndicator;
+
+/// The charges left, after the indicator's prompt.
+#[derive(Component)]
+struct RewindCharges;
+
+impl Plugin for RewindPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rewind>()
+            .add_systems(Startup, load_rewind)
+            .add_systems(OnEnter(GameState::InGame), start_run_rewind)
+            .add_systems(
+                Update,
+                (
+                    unlock_rewind.run_if(recorded_run),
+                    start_rewind,
+                    update_rewind_indicator,
+                )
+                    .in_set(InGameSet),
+            )
+            .add_systems(FixedUpdate, record_snapshot.in_set(InGameSet))
+            .add_systems(OnEnter(RunState::Rewinding), pause_physics_for_rewind)
+            .add_systems(
+                FixedUpdate,
+                play_rewind.run_if(in_state(RunState::Rewinding)),
+            )
+            .add_systems(OnExit(RunState::Rewinding), resume_from_rewind);
+    }
+}
+
+impl BodySnapshot {
+    fn new(
+        position: &Position,
+        rotation: &Rotation,
+        linear_velocity: &LinearVelocity,
+        angular_velocity: &AngularVelocity,
+    ) -> Self {
+        BodySnapshot {
+            position: position.0,
+            rotation: *rotation,
+            linear_velocity: linear_velocity.0,
+            angular_velocity: angular_velocity.0,
+        }
+    }
+}
+
+fn load_rewind(storage: Res<Storage>, mut rewind: ResMut<Rewind>) {
+    rewind.unlocked = storage.load(UNLOCKED_KEY).unwrap_or(false);
+}
+
+fn start_run_rewind(mut commands: Commands, mut rewind: ResMut<Rewind>) {
+    rewind.charges = if rewind.unlocked { CHARGES_PER_RUN } else { 0 };
+    rewind.history.clear();
+    rewind.rewound_to = None;
+
+    commands
+        .spawn((
+            RewindIndicator,
+            InGameEntity,
+            Text::default(),
+            Prompt(Vec::new()),
+            TextFont {
+                font_size: 20.,
+                ..default()
+            },
+            KeepInSafeArea,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(60.),
+                left: Val::Px(20.),
+                ..default()
+            },
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                RewindCharges,
+                TextSpan::default(),
+                TextFont {
+                    font_size: 20.,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn unlock_rewind(
+    player_query: Query<&Numbered, (With<Player>, Changed<Numbered>)>,
+    mut rewind: ResMut<Rewind>,
+    mut storage: ResMut<Storage>,
+    menu_input: MenuInput,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    if rewind.unlocked
+        || !player_query
+            .iter()
+            .any(|Numbered(number)| *number >= UNLOCK_NUMBER)
+    {
+        return;
+    }
+
+    rewind.unlocked = true;
+    rewind.charges = CHARGES_PER_RUN;
+    storage.save(UNLOCKED_KEY, true);
+    toast_writer.send(ShowToast(format!(
+        "Rewind unlocked! Press {}",
+        menu_input.glyph(MenuAction::Rewind)
+    )));
+}
+
+#[allow(clippy::type_complexity)]
+fn record_snapshot(
+    mut rewind: ResMut<Rewind>,
+    player_query: Query<
+        (
+            &Numbered,
+            &Position,
+            &Rotation,
+            &LinearVelocity,
+            &AngularVelocity,
+        ),
+        With<Player>,
+    >,
+    ball_query: Query<
+        (
+            Entity,
+            &Numbered,
+            &Position,
+            &Rotation,
+            &LinearVelocity,
+            &AngularVelocity,
+            Option<&OfKind>,
+        ),
+        (With<Ball>, Without<Fragment>),
+    >,
+    egg_query: Query<(Entity, &Egg, &Transform)>,
+    score: Res<Score>,
+    stats: Res<RunStats>,
+) {
+    if !rewind.unlocked {
+        return;
+    }
+    let Ok((Numbered(player_number), position, rotation, linear_velocity, angular_velocity)) =
+        player_query.get_single()
+    else {
+        return;
+    };
+
+    let balls = ball_query
+        .iter()
+        .map(
+            |(
+                ball,
+                Numbered(number),
+                position,
+                rotation,
+                linear_velocity,
+                angular_velocity,
+                kind,
+            )| {
+                (
+                    ball,
+                    BallSnapshot {
+                        number: *number,
+                        kind: kind.map(|OfKind(kind)| kind.clone()),
+                        body: BodySnapshot::new(
+                            position,
+                            rotation,
+                            linear_velocity,
+                            angular_velocity,
+                        ),
+                    },
+                )
+            },
+        )
+        .collect();
+    let eggs = egg_query
+        .iter()
+        .map(|(entity, egg, transform)| {
+            (
+                entity,
+                EggSnapshot {
+                    number: egg.number,
+                    position: transform.translation.truncate(),
+                    facing: egg.facing,
+                    incubated: egg.incubated(),
+                },
+            )
+        })
+        .collect();
+
+    rewind.history.push_back(Snapshot {
+        player_number: *player_number,
+        player: BodySnapshot::new(position, rotation, linear_velocity, angular_velocity),
+        balls,
+        eggs,
+        score: score.0,
+        balls_eaten: stats.balls_eaten,
+        largest_ball_eaten: stats.largest_ball_eaten,
+    });
+    let capacity = (REWIND_SECONDS * FIXED_TIMESTEP_HZ) as usize;
+    while rewind.history.len() > capacity {
+        rewind.history.pop_front();
+    }
+}
+
+fn start_rewind(
+    menu_input: MenuInput,
+    mut rewind: ResMut<Rewind>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+    mut modifier_writer: EventWriter<RunModifier>,
+) {
+    if !menu_input.just_pressed(MenuAction::Rewind)
+        || rewind.charges == 0
+        || rewind.history.is_empty()
+    {
+        return;
+    }
+
+    rewind.charges -= 1;
+    next_run_state.set(RunState::Rewinding);
+    modifier_writer.send(RunModifier("rewound".to_string()));
+}
+
+fn update_rewind_indicator(
+    rewind: Res<Rewind>,
+    mut indicator_query: Query<&mut Prompt, With<RewindIndicator>>,
+    mut charges_query: Query<&mut TextSpan, With<RewindCharges>>,
+) {
+    if !rewind.is_changed() {
+        return;
+    }
+
+    for mut prompt in indicator_query.iter_mut() {
+        prompt.0 = match rewind.charges {
+            0 => Vec::new(),
+            _ => vec![(MenuAction::Rewind, "rewind")],
+        };
+    }
+    for mut span in charges_query.iter_mut() {
+        span.0 = match rewind.charges {
+            0 => String::new(),
+            charges => format!(" x{charges}"),
+        };
+    }
+}
+
+fn pause_physics_for_rewind(mut physics_time: ResMut<Time<Physics>>) {
+    physics_time.pause();
+}
+
+fn place_body(
+    body: &BodySnapshot,
+    position: &mut Position,
+    rotation: &mut Rotation,
+    transform: &mut Transform,
+) {
+    position.0 = body.position;
+    *rotation = body.rotation;
+    transform.translation = body.position.extend(transform.translation.z);
+    transform.rotation = Quat::from_rotation_z(body.rotation.as_radians());
+}
+
+#[allow(clippy::type_complexity)]
+fn play_rewind(
+    mut rewind: ResMut<Rewind>,
+    mut player_query: Query<
+        (&mut Numbered, &mut Position, &mut Rotation, &mut Transform),
+        With<Player>,
+    >,
+    mut ball_query: Query<
+        (&mut Position, &mut Rotation, &mut Transform),
+        (With<Ball>, Without<Player>),
+    >,
+    mut next_run_state: ResMut<NextState<RunState>>,
+) {
+    for _ in 0..PLAYBACK_SPEED {
+        if let Some(snapshot) = rewind.history.pop_back() {
+            rewind.rewound_to = Some(snapshot);
+        }
+    }
+    if rewind.history.is_empty() {
+        next_run_state.set(RunState::Playing);
+    }
+
+    let Some(snapshot) = &rewind.rewound_to else {
+        return;
+    };
+    for (mut number, mut position, mut rotation, mut transform) in player_query.iter_mut() {
+        if number.0 != snapshot.player_number {
+            number.0 = snapshot.player_number;
+        }
+        place_body(
+            &snapshot.player,
+            &mut position,
+            &mut rotation,
+            &mut transform,
+        );
+    }
+    for (ball, BallSnapshot { body, .. }) in &snapshot.balls {
+        if let Ok((mut position, mut rotation, mut transform)) = ball_query.get_mut(*ball) {
+            place_body(body, &mut position, &mut rotation, &mut transform);
+        }
+    }
+}
+
+/// Balls eaten since the snapshot come back, and ones spawned since it go
+/// away, so the arena matches it. What eating them scored goes too.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn resume_from_rewind(
+    mut commands: Commands,
+    mut physics_time: ResMut<Time<Physics>>,
+    mut rewind: ResMut<Rewind>,
+    mut player_query: Query<(&mut LinearVelocity, &mut AngularVelocity), With<Player>>,
+    mut ball_query: Query<
+        (Entity, &mut LinearVelocity, &mut AngularVelocity),
+        (With<Ball>, Without<Player>, Without<Fragment>),
+    >,
+    mut egg_query: Query<(Entity, &mut Egg)>,
+    config: Res<GameConfig>,
+    ball_kinds: Res<BallKinds>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut score: ResMut<Score>,
+    mut stats: ResMut<RunStats>,
+) {
+    physics_time.unpause();
+    let Some(snapshot) = rewind.rewound_to.take() else {
+        return;
+    };
+
+    score.0 = snapshot.score;
+    stats.balls_eaten = snapshot.balls_eaten;
+    stats.largest_ball_eaten = snapshot.largest_ball_eaten;
+
+    for (mut linear_velocity, mut angular_velocity) in player_query.iter_mut() {
+        linear_velocity.0 = snapshot.player.linear_velocity;
+        angular_velocity.0 = snapshot.player.angular_velocity;
+    }
+
+    let mut restored = HashSet::new();
+    for (ball, BallSnapshot { number, kind, body }) in &snapshot.balls {
+        if let Ok((_, mut linear_velocity, mut angular_velocity)) = ball_query.get_mut(*ball) {
+            linear_velocity.0 = body.linear_velocity;
+            angular_velocity.0 = body.angular_velocity;
+            restored.insert(*ball);
+            continue;
+        }
+
+        let ball = spawn_numbered_ball(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            *number,
+            body.position,
+            body.linear_velocity,
+        );
+        let mut ball = commands.entity(ball);
+        ball.insert(AngularVelocity(body.angular_velocity));
+        if let Some(kind) = kind.as_ref().and_then(|kind| ball_kinds.get(kind)) {
+            kind.apply(&mut ball, &mut materials);
+        }
+    }
+    for (ball, _, _) in ball_query.iter() {
+        if !restored.contains(&ball) {
+            commands.entity(ball).despawn_recursive();
+        }
+    }
+
+    let mut restored = HashSet::new();
+    for (entity, egg) in &snapshot.eggs {
+        if let Ok((_, mut live_egg)) = egg_query.get_mut(*entity) {
+            live_egg.set_incubated(egg.incubated);
+            restored.insert(*entity);
+        } else {
+            spawn_egg(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                &config,
+                egg.number,
+                egg.position,
+                egg.facing,
+                egg.incubated,
+            );
+        }
+    }
+    for (entity, _) in egg_query.iter() {
+        if !restored.contains(&entity) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowResized, WindowScaleFactorChanged},
+};
+use std::ops::RangeInclusive;
+
+/// The window size the UI is laid out for, in logical pixels.
+const REFERENCE_SIZE: Vec2 = Vec2::new(1280., 720.);
+const UI_SCALE_RANGE: RangeInclusive<f32> = 0.5..=2.;
+
+/// Keeps the HUD readable and on screen whatever display the game ends up
+/// on. The UI is laid out in logical pixels for a 1280x720 window and scaled
+/// to fit the window's actual logical size, which already accounts for the
+/// monitor's scale factor. Anything marked [`KeepInSafeArea`] is also pushed
+/// in from the edges by the display's safe-area insets, so notches and
+/// rounded corners on phones don't clip it. Both are worked out again
+/// whenever the window is resized or moved to a monitor with a different
+/// scale factor.
+pub struct SafeAreaPlugin;
+
+/// How far in from each edge of the window, in logical pixels, is clear of
+/// notches and rounded corners. All zero outside web builds.
+#[derive(Resource, Default, Clone, Copy, PartialEq)]
+pub struct SafeAreaInsets {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+/// Marks a UI node positioned from the window's edges in pixels, to be moved
+/// inside the safe area.
+#[derive(Component)]
+pub struct KeepInSafeArea;
+
+/// The node's own offsets, before the insets were added.
+#[derive(Component)]
+struct SafeAreaBase {
+    top: Val,
+    right: Val,
+    bottom: Val,
+    left: Val,
+}
+
+impl Plugin for SafeAreaPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SafeAreaInsets>()
+            .add_systems(Startup, update_display_metrics)
+            .add_systems(
+                Update,
+                (
+                    update_display_metrics
+                        .run_if(on_event::<WindowResized>.or(on_event::<WindowScaleFactorChanged>)),
+                    apply_safe_area,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_insets() -> SafeAreaInsets {
+    SafeAreaInsets::default()
+}
+
+/// Browsers only hand the insets to CSS, so they're read back off the
+/// computed padding of a hidden element padded by them.
+#[cfg(target_arch = "wasm32")]
+fn read_insets() -> SafeAreaInsets {
+    let read = || -> Option<SafeAreaInsets> {
+        let window = web_sys::window()?;
+        let document = window.document()?;
+        let probe = document.create_element("div").ok()?;
+        probe
+            .set_attribute(
+                "style",
+                "position: fixed; visibility: hidden; pointer-events: none; \
+                 padding: env(safe-area-inset-top) env(safe-area-inset-right) \
+                 env(safe-area-inset-bottom) env(safe-area-inset-left);",
+            )
+            .ok()?;
+        document.body()?.append_child(&probe).ok()?;
+
+        let style = window.get_computed_style(&probe).ok().flatten();
+        let side = |name: &str| {
+            style
+                .as_ref()
+                .and_then(|style| style.get_property_value(name).ok())
+                .and_then(|value| value.trim_end_matches("px").parse().ok())
+                .unwrap_or(0.)
+        };
+        let insets = SafeAreaInsets {
+            top: side("padding-top"),
+            right: side("padding-right"),
+            bottom: side("padding-bottom"),
+            left: side("padding-left"),
+        };
+        probe.remove();
+        Some(insets)
+    };
+    read().unwrap_or_default()
+}
+
+fn update_display_metrics(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut ui_scale: ResMut<UiScale>,
+    mut insets: ResMut<SafeAreaInsets>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    let fit = window.size() / REFERENCE_SIZE;
+    let scale = fit
+        .min_element()
+        .clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end());
+    if ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
+    insets.set_if_neq(read_insets());
+}
+
+/// Insets are in window pixels while node offsets get scaled with the rest of
+/// the UI, so they're scaled down to match.
+#[allow(clippy::type_complexity)]
+fn apply_safe_area(
+    mut commands: Commands,
+    insets: Res<SafeAreaInsets>,
+    ui_scale: Res<UiScale>,
+    new_node_query: Query<(Entity, &Node), (With<KeepInSafeArea>, Without<SafeAreaBase>)>,
+    mut node_query: Query<(Ref<SafeAreaBase>, &mut Node), With<KeepInSafeArea>>,
+) {
+    for (entity, node) in new_node_query.iter() {
+        commands.entity(entity).insert(SafeAreaBase {
+            top: node.top,
+            right: node.right,
+            bottom: node.bottom,
+            left: node.left,
+        });
+    }
+
+    let changed = insets.is_changed() || ui_scale.is_changed();
+    let inset = |base: Val, by: f32| match base {
+        Val::Px(offset) => Val::Px(offset + by / ui_scale.0),
+        other => other,
+    };
+    for (base, mut node) in node_query.iter_mut() {
+        if !changed && !base.is_added() {
+            continue;
+        }
+
+        node.top = inset(base.top, insets.top);
+        node.right = inset(base.right, insets.right);
+        node.bottom = inset(base.bottom, insets.bottom);
+        node.left = inset(base.left, insets.left);
+    }
+}
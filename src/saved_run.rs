@@ -0,0 +1,507 @@
+use crate::{
+    Ball, GameRng, GameState, Numbered, Player, RunState,
+    ball_kinds::{BallKinds, OfKind},
+    checkpoints::CheckpointFloor,
+    config::GameConfig,
+    difficulty::Difficulty,
+    eggs::{Egg, spawn_egg},
+    gravity::GravityDirection,
+    modes::GameModes,
+    new_game_plus::NewGamePlus,
+    score::Score,
+    shedding::{Fragment, spawn_fragment},
+    shop::{Coins, ShopEffects, Wave},
+    spawn_numbered_ball,
+    stats::RunStats,
+    storage::{Storage, Versioned},
+    timers::GameTimers,
+    toast::ShowToast,
+    weekly::WeeklyPlaylist,
+};
+use avian2d::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+const SAVED_RUN_KEY: &str = "saved_run";
+
+/// A run can be saved from the pause menu and picked up again later with
+/// Continue on the main menu. The save holds the mode, difficulty and New
+/// Game+ loop it was played in, the player, every live ball and egg, gravity,
+/// the gameplay timers, the shop's waves and the run's stats so far, and is
+/// used up once continued. The run's seed is kept too, though what spawns
+/// after resuming won't match an unbroken run. Weekly playlist runs and split
+/// arena modes can't be saved, since their playlist and AI partner can't be
+/// brought back.
+pub struct SavedRunPlugin;
+
+/// Asks for the run in progress to be saved, then goes back to the menu.
+#[derive(Event)]
+pub struct SaveRun;
+
+/// The run to pick up once the next one starts.
+#[derive(Resource, Default)]
+pub struct ResumeRun(pub Option<SavedRun>);
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedRun {
+    seed: u64,
+    /// The name of its game mode.
+    mode: String,
+    difficulty: String,
+    new_game_plus_loop: u32,
+    player: SavedBody,
+    balls: Vec<SavedBody>,
+    eggs: Vec<SavedEgg>,
+    gravity_direction: [f32; 2],
+    timers: Vec<(String, f32)>,
+    duration: f32,
+    balls_eaten: u32,
+    largest_ball_eaten: i32,
+    peak_number: i32,
+    gravity_flips: u32,
+    wall_bounces: u32,
+    score: i32,
+    checkpoint_floor: Option<i32>,
+    coins: u32,
+    wave: u32,
+    wave_elapsed: f32,
+    slow_spawns: bool,
+    shield: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedBody {
+    number: i32,
+    position: [f32; 2],
+    velocity: [f32; 2],
+    /// Missing from bodies saved before version 3, like the rest below.
+    #[serde(default)]
+    spin: f32,
+    /// The name of a ball's kind, if it has one.
+    #[serde(default)]
+    kind: Option<String>,
+    /// How old a shed fragment was, in seconds.
+    #[serde(default)]
+    fragment_age: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedEgg {
+    number: i32,
+    position: [f32; 2],
+    facing: [f32; 2],
+    incubated: f32,
+}
+
+/// What a run has built up besides its bodies and clocks.
+#[derive(SystemParam)]
+struct RunProgress<'w> {
+    stats: ResMut<'w, RunStats>,
+    score: ResMut<'w, Score>,
+    checkpoint_floor: ResMut<'w, CheckpointFloor>,
+    coins: ResMut<'w, Coins>,
+    wave: ResMut<'w, Wave>,
+    effects: ResMut<'w, ShopEffects>,
+}
+
+impl Plugin for SavedRunPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SaveRun>()
+            .init_resource::<ResumeRun>()
+            // Before anything entering `InGame` reads them.
+            .add_systems(OnExit(GameState::Menu), restore_run_choices)
+            // After everything entering `InGame` has set up a fresh run.
+            .add_systems(OnEnter(RunState::Countdown), resume_run)
+            .add_systems(Update, save_run.run_if(in_state(GameState::InGame)));
+    }
+}
+
+impl std::fmt::Display for SavedRun {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let saved = ron::to_string(self).map_err(|_| std::fmt::Error)?;
+        f.write_str(&saved)
+    }
+}
+
+impl std::str::FromStr for SavedRun {
+    type Err = ron::error::SpannedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ron::from_str(s)
+    }
+}
+
+impl Versioned for SavedRun {
+    const VERSION: u32 = 3;
+
+    fn migrate(version: u32, saved: &str) -> Option<String> {
+        let fields = saved.trim_end().strip_suffix(')')?;
+        match version {
+            // Saves from before runs had a score apart from the number.
+            1 => Some(format!("{fields},score:0)")),
+            // Saves from before the rest of a run's state was kept, which
+            // could only have been Classic on Normal outside New Game+.
+            2 => Some(format!(
+                "{fields},mode:\"Classic\",difficulty:\"Normal\",new_game_plus_loop:0,eggs:[],\
+                 gravity_flips:0,wall_bounces:0,checkpoint_floor:None,coins:0,wave:1,\
+                 wave_elapsed:0.0,slow_spawns:false,shield:false)"
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl SavedBody {
+    fn new(Numbered(number): &Numbered, transform: &Transform, velocity: &LinearVelocity) -> Self {
+        SavedBody {
+            number: *number,
+            position: transform.translation.truncate().into(),
+            velocity: velocity.0.into(),
+            spin: 0.,
+            kind: None,
+            fragment_age: None,
+        }
+    }
+}
+
+/// Whether there's a run to continue.
+pub fn has_saved_run(storage: &Storage) -> bool {
+    load_saved_run(storage).is_some()
+}
+
+/// Takes the saved run out of storage, so it can only be continued once.
+pub fn take_saved_run(storage: &mut Storage) -> Option<SavedRun> {
+    let saved_run = load_saved_run(storage)?;
+    storage.save(SAVED_RUN_KEY, "");
+    Some(saved_run)
+}
+
+/// A continued run leaves an empty save behind, which isn't worth a warning.
+fn load_saved_run(storage: &Storage) -> Option<SavedRun> {
+    storage
+        .load::<String>(SAVED_RUN_KEY)
+        .filter(|saved| !saved.is_empty())?;
+    storage.load_versioned(SAVED_RUN_KEY)
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn save_run(
+    mut save_reader: EventReader<SaveRun>,
+    player_query: Query<(&Numbered, &Transform, &LinearVelocity), With<Player>>,
+    ball_query: Query<
+        (
+            &Numbered,
+            &Transform,
+            &LinearVelocity,
+            &AngularVelocity,
+            Option<&OfKind>,
+            Option<&Fragment>,
+        ),
+        With<Ball>,
+    >,
+    egg_query: Query<(&Egg, &Transform)>,
+    direction: Res<GravityDirection>,
+    game_timers: Res<GameTimers>,
+    game_rng: Res<GameRng>,
+    modes: Res<GameModes>,
+    difficulty: Res<Difficulty>,
+    new_game_plus: Res<NewGamePlus>,
+    weekly_playlist: Res<WeeklyPlaylist>,
+    progress: RunProgress,
+    mut storage: ResMut<Storage>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    if save_reader.read().count() == 0 {
+        return;
+    }
+    if weekly_playlist.active || modes.selected().split_arena() {
+        toast_writer.send(ShowToast("This run can't be saved".to_string()));
+        return;
+    }
+    let Ok((number, transform, velocity)) = player_query.get_single() else {
+        return;
+    };
+
+    let balls = ball_query
+        .iter()
+        .map(
+            |(number, transform, velocity, spin, kind, fragment)| SavedBody {
+                spin: spin.0,
+                kind: kind.map(|OfKind(kind)| kind.clone()),
+                fragment_age: fragment.map(Fragment::age),
+                ..SavedBody::new(number, transform, velocity)
+            },
+        )
+        .collect();
+    let eggs = egg_query
+        .iter()
+        .map(|(egg, transform)| SavedEgg {
+            number: egg.number,
+            position: transform.translation.truncate().into(),
+            facing: egg.facing.into(),
+            incubated: egg.incubated(),
+        })
+        .collect();
+    let stats = &progress.stats;
+    let saved_run = SavedRun {
+        seed: game_rng.seed,
+        mode: modes.selected().name().to_string(),
+        difficulty: difficulty.to_string(),
+        new_game_plus_loop: new_game_plus.loop_count,
+        player: SavedBody::new(number, transform, velocity),
+        balls,
+        eggs,
+        gravity_direction: direction.0.as_vec2().into(),
+        timers: game_timers.progress(),
+        duration: stats.duration,
+        balls_eaten: stats.balls_eaten,
+        largest_ball_eaten: stats.largest_ball_eaten,
+        peak_number: stats.peak_number,
+        gravity_flips: stats.gravity_flips,
+        wall_bounces: stats.wall_bounces,
+        score: progress.score.0,
+        checkpoint_floor: progress.checkpoint_floor.0,
+        coins: progress.coins.0,
+        wave: progress.wave.number,
+        wave_elapsed: progress.wave.elapsed,
+        slow_spawns: progress.effects.slow_spawns,
+        shield: progress.effects.shield,
+    };
+    storage.save_versioned(SAVED_RUN_KEY, &saved_run);
+    toast_writer.send(ShowToast("Run saved".to_string()));
+    next_game_state.set(GameState::Menu);
+}
+
+/// Picks the mode, difficulty and New Game+ loop the run was saved in. A
+/// mode that isn't registered any more leaves the current one picked.
+fn restore_run_choices(
+    resume_run: Res<ResumeRun>,
+    mut modes: ResMut<GameModes>,
+    mut difficulty: ResMut<Difficulty>,
+    mut new_game_plus: ResMut<NewGamePlus>,
+) {
+    let Some(saved_run) = &resume_run.0 else {
+        return;
+    };
+
+    modes.select(&saved_run.mode);
+    if let Ok(saved_difficulty) = saved_run.difficulty.parse() {
+        *difficulty = saved_difficulty;
+    }
+    new_game_plus.loop_count = saved_run.new_game_plus_loop;
+}
+
+/// Swaps the fresh run's player, balls and clocks for the saved ones. Balls
+/// the arena stocked itself, like a vault's treasure, are replaced too.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn resume_run(
+    mut commands: Commands,
+    mut resume_run: ResMut<ResumeRun>,
+    mut player_query: Query<(&mut Numbered, &mut Transform, &mut LinearVelocity), With<Player>>,
+    ball_query: Query<Entity, With<Ball>>,
+    mut direction: ResMut<GravityDirection>,
+    mut game_timers: ResMut<GameTimers>,
+    mut progress: RunProgress,
+    mut game_rng: ResMut<GameRng>,
+    ball_kinds: Res<BallKinds>,
+    config: Res<GameConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let Some(saved_run) = resume_run.0.take() else {
+        return;
+    };
+
+    for (mut number, mut transform, mut velocity) in player_query.iter_mut() {
+        number.0 = saved_run.player.number;
+        transform.translation.x = saved_run.player.position[0];
+        transform.translation.y = saved_run.player.position[1];
+        velocity.0 = saved_run.player.velocity.into();
+    }
+    for ball in ball_query.iter() {
+        commands.entity(ball).despawn_recursive();
+    }
+    for ball in &saved_run.balls {
+        let entity = match ball.fragment_age {
+            Some(age) => spawn_fragment(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                ball.position.into(),
+                ball.velocity.into(),
+                age,
+            ),
+            None => spawn_numbered_ball(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                ball.number,
+                ball.position.into(),
+                ball.velocity.into(),
+            ),
+        };
+        let mut entity = commands.entity(entity);
+        entity.insert(AngularVelocity(ball.spin));
+        if let Some(kind) = ball.kind.as_ref().and_then(|kind| ball_kinds.get(kind)) {
+            kind.apply(&mut entity, &mut materials);
+        }
+    }
+    for egg in &saved_run.eggs {
+        spawn_egg(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &config,
+            egg.number,
+            egg.position.into(),
+            egg.facing.into(),
+            egg.incubated,
+        );
+    }
+
+    if let Ok(saved_direction) = Dir2::new(saved_run.gravity_direction.into()) {
+        direction.0 = saved_direction;
+    }
+    game_timers.restore_progress(&saved_run.timers);
+    let stats = &mut progress.stats;
+    stats.duration = saved_run.duration;
+    stats.balls_eaten = saved_run.balls_eaten;
+    stats.largest_ball_eaten = saved_run.largest_ball_eaten;
+    stats.peak_number = saved_run.peak_number;
+    stats.gravity_flips = saved_run.gravity_flips;
+    stats.wall_bounces = saved_run.wall_bounces;
+    progress.score.0 = saved_run.score;
+    progress.checkpoint_floor.0 = saved_run.checkpoint_floor;
+    progress.coins.0 = saved_run.coins;
+    *progress.wave = Wave {
+        number: saved_run.wave,
+        elapsed: saved_run.wave_elapsed,
+    };
+    *progress.effects = ShopEffects {
+        slow_spawns: saved_run.slow_spawns,
+        shield: saved_run.shield,
+    };
+    *game_rng = GameRng {
+        fresh: false,
+        ..GameRng::new(saved_run.seed)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved_run() -> SavedRun {
+        SavedRun {
+            seed: 7,
+            mode: "Zen".to_string(),
+            difficulty: "Hard".to_string(),
+            new_game_plus_loop: 2,
+            player: SavedBody {
+                number: 12,
+                position: [10., -20.],
+                velocity: [0., 0.],
+                spin: 0.,
+                kind: None,
+                fragment_age: None,
+            },
+            balls: vec![
+                SavedBody {
+                    number: 4,
+                    position: [100., 50.],
+                    velocity: [-30., 15.],
+                    spin: 1.5,
+                    kind: Some("rubber".to_string()),
+                    fragment_age: None,
+                },
+                SavedBody {
+                    number: 1,
+                    position: [20., -10.],
+                    velocity: [300., 0.],
+                    spin: 0.,
+                    kind: None,
+                    fragment_age: Some(0.5),
+                },
+            ],
+            eggs: vec![SavedEgg {
+                number: 6,
+                position: [0., 280.],
+                facing: [0., -1.],
+                incubated: 1.25,
+            }],
+            gravity_direction: [0., -1.],
+            timers: vec![("ball_spawn".to_string(), 0.25)],
+            duration: 42.5,
+            balls_eaten: 3,
+            largest_ball_eaten: 8,
+            peak_number: 12,
+            gravity_flips: 5,
+            wall_bounces: 2,
+            score: 90,
+            checkpoint_floor: Some(25),
+            coins: 14,
+            wave: 3,
+            wave_elapsed: 10.5,
+            slow_spawns: true,
+            shield: false,
+        }
+    }
+
+    #[test]
+    fn saved_runs_round_trip() {
+        let mut storage = Storage::in_memory();
+        storage.save_versioned(SAVED_RUN_KEY, &saved_run());
+
+        let loaded = load_saved_run(&storage).unwrap();
+        assert_eq!(loaded.to_string(), saved_run().to_string());
+    }
+
+    #[test]
+    fn loads_version_1_runs_without_a_score() {
+        let mut storage = Storage::in_memory();
+        storage.save(
+            SAVED_RUN_KEY,
+            "version 1\n(seed:7,player:(number:12,position:(10.0,-20.0),velocity:(0.0,0.0)),\
+             balls:[],gravity_direction:(0.0,-1.0),timers:[],duration:42.5,balls_eaten:3,\
+             largest_ball_eaten:8,peak_number:12)",
+        );
+
+        let loaded = load_saved_run(&storage).unwrap();
+        assert_eq!(loaded.score, 0);
+        assert_eq!(loaded.player.number, 12);
+        assert_eq!(loaded.peak_number, 12);
+        assert_eq!(loaded.mode, "Classic");
+    }
+
+    #[test]
+    fn loads_version_2_runs_as_classic_on_normal() {
+        let mut storage = Storage::in_memory();
+        storage.save(
+            SAVED_RUN_KEY,
+            "version 2\n(seed:7,player:(number:12,position:(10.0,-20.0),velocity:(0.0,0.0)),\
+             balls:[(number:4,position:(100.0,50.0),velocity:(-30.0,15.0))],\
+             gravity_direction:(0.0,-1.0),timers:[],duration:42.5,balls_eaten:3,\
+             largest_ball_eaten:8,peak_number:12,score:90)",
+        );
+
+        let loaded = load_saved_run(&storage).unwrap();
+        assert_eq!(loaded.score, 90);
+        assert_eq!(loaded.mode, "Classic");
+        assert_eq!(loaded.difficulty, "Normal");
+        assert_eq!(loaded.new_game_plus_loop, 0);
+        assert_eq!(loaded.wave, 1);
+        assert!(loaded.eggs.is_empty());
+        assert_eq!(loaded.balls[0].kind, None);
+        assert_eq!(loaded.balls[0].spin, 0.);
+    }
+
+    #[test]
+    fn continuing_uses_up_the_save() {
+        let mut storage = Storage::in_memory();
+        storage.save_versioned(SAVED_RUN_KEY, &saved_run());
+
+        assert!(take_saved_run(&mut storage).is_some());
+        assert!(!has_saved_run(&storage));
+    }
+}
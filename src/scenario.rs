@@ -0,0 +1,306 @@
+use crate::{
+    GameState, Numbered, Player, RunState, config::GameConfig, score::Score, settings::Settings,
+    stats::RunStats, stress::FrameTimes,
+};
+use bevy::{input::InputSystem, prelude::*, time::TimeUpdateStrategy};
+use serde::Deserialize;
+use std::{path::Path, time::Duration};
+
+/// Every frame of a scenario is exactly this long, in seconds.
+const FRAME_TIME: f32 = 1. / 60.;
+/// How much longer than its last check a scenario's run may take to get
+/// there, in seconds of run time, before it's given up on.
+const TIMEOUT_GRACE: f32 = 60.;
+
+/// `--scenario <file.ron>` plays a scripted run without a window instead of
+/// the game, to lock down how gameplay plays out. The scenario seeds the run,
+/// gives it a config, holds keys down at set times and checks on the run at
+/// others, e.g. that the player's number is between 20 and 60 thirty seconds
+/// in. Frames step a fixed 60th of a second and nothing is read from or saved
+/// to the player's data, so a scenario plays out the same way every time.
+/// Every check is printed, and the exit code is 1 if any failed.
+pub struct ScenarioPlugin(pub Option<Scenario>);
+
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub seed: u64,
+    #[serde(default)]
+    pub config: GameConfig,
+    /// How many extra balls to flood the arena with at the start, see
+    /// [`crate::stress`].
+    #[serde(default)]
+    pub stress_balls: usize,
+    #[serde(default)]
+    inputs: Vec<ScriptedInput>,
+    checks: Vec<Check>,
+}
+
+/// Holds `key` down from `at` seconds into the run for `hold` seconds, or
+/// for a single frame without it.
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScriptedInput {
+    at: f32,
+    #[serde(default)]
+    hold: f32,
+    key: ScriptedKey,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+enum ScriptedKey {
+    Left,
+    Right,
+    FlipGravity,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Check {
+    at: f32,
+    expect: Expectation,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+enum Expectation {
+    /// Between the two, inclusive.
+    PlayerNumber(i32, i32),
+    /// Between the two, inclusive.
+    Score(i32, i32),
+    Alive,
+    /// Frames so far took at most this many milliseconds on average, by the
+    /// wall clock.
+    FrameTime(f32),
+    /// The run has ended by then, without being won.
+    Dead,
+}
+
+#[derive(Resource)]
+struct ScenarioRun {
+    scenario: Scenario,
+    started: bool,
+    next_check: usize,
+    failures: usize,
+}
+
+impl Plugin for ScenarioPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(mut scenario) = self.0.clone() else {
+            return;
+        };
+        scenario.checks.sort_by(|a, b| a.at.total_cmp(&b.at));
+
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            FRAME_TIME,
+        )))
+        .insert_resource(ScenarioRun {
+            scenario,
+            started: false,
+            next_check: 0,
+            failures: 0,
+        })
+        .add_systems(
+            Update,
+            start_scenario_run.run_if(in_state(GameState::Menu).or(in_state(GameState::News))),
+        )
+        .add_systems(
+            PreUpdate,
+            press_scripted_keys
+                .after(InputSystem)
+                .run_if(in_state(RunState::Playing)),
+        )
+        .add_systems(
+            Update,
+            (run_checks, give_up_on_stuck_run).run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(OnEnter(GameState::DeathScreen), finish_ended_run)
+        .add_systems(OnEnter(GameState::Victory), finish_ended_run);
+    }
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| format!("couldn't read {}: {error}", path.display()))?;
+
+        ron::from_str(&contents)
+            .map_err(|error| format!("invalid scenario {}: {error}", path.display()))
+    }
+}
+
+impl ScenarioRun {
+    fn report(&mut self, check: &Check, result: Result<(), String>) {
+        match result {
+            Ok(()) => println!("ok     {:>6.1}s {:?}", check.at, check.expect),
+            Err(error) => {
+                println!("FAILED {:>6.1}s {:?}: {error}", check.at, check.expect);
+                self.failures += 1;
+            }
+        }
+    }
+
+    fn exit(&self) -> AppExit {
+        let checks = self.scenario.checks.len();
+        if self.failures == 0 {
+            println!("all {checks} checks passed");
+            AppExit::Success
+        } else {
+            println!("{} of {checks} checks failed", self.failures);
+            AppExit::from_code(1)
+        }
+    }
+
+    fn remaining_checks(&self) -> Vec<Check> {
+        self.scenario.checks[self.next_check..].to_vec()
+    }
+}
+
+fn start_scenario_run(
+    mut scenario_run: ResMut<ScenarioRun>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if !scenario_run.started {
+        scenario_run.started = true;
+        next_game_state.set(GameState::InGame);
+    }
+}
+
+fn press_scripted_keys(
+    scenario_run: Res<ScenarioRun>,
+    stats: Res<RunStats>,
+    settings: Res<Settings>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+) {
+    let (left, right) = settings.movement_keys.keys();
+    let key_code = |key: ScriptedKey| match key {
+        ScriptedKey::Left => left,
+        ScriptedKey::Right => right,
+        ScriptedKey::FlipGravity => KeyCode::Space,
+    };
+
+    let held: Vec<KeyCode> = scenario_run
+        .scenario
+        .inputs
+        .iter()
+        .filter(|input| (input.at..input.at + input.hold.max(FRAME_TIME)).contains(&stats.duration))
+        .map(|input| key_code(input.key))
+        .collect();
+    for key in [
+        ScriptedKey::Left,
+        ScriptedKey::Right,
+        ScriptedKey::FlipGravity,
+    ]
+    .map(key_code)
+    {
+        if held.contains(&key) {
+            keys.press(key);
+        } else if keys.pressed(key) {
+            keys.release(key);
+        }
+    }
+}
+
+fn run_checks(
+    mut scenario_run: ResMut<ScenarioRun>,
+    stats: Res<RunStats>,
+    score: Res<Score>,
+    frame_times: Res<FrameTimes>,
+    player_query: Query<&Numbered, With<Player>>,
+    mut exit_writer: EventWriter<AppExit>,
+) {
+    let player_number = player_query
+        .get_single()
+        .ok()
+        .map(|Numbered(number)| *number);
+    let in_range = |name: &str, value: i32, min: i32, max: i32| {
+        if (min..=max).contains(&value) {
+            Ok(())
+        } else {
+            Err(format!("{name} was {value}"))
+        }
+    };
+
+    while let Some(check) = scenario_run
+        .scenario
+        .checks
+        .get(scenario_run.next_check)
+        .filter(|check| check.at <= stats.duration)
+        .cloned()
+    {
+        let result = match check.expect {
+            Expectation::PlayerNumber(min, max) => match player_number {
+                Some(number) => in_range("the player's number", number, min, max),
+                None => Err("there was no player".to_string()),
+            },
+            Expectation::Score(min, max) => in_range("the score", score.0, min, max),
+            Expectation::Alive => Ok(()),
+            Expectation::FrameTime(budget) if frame_times.mean() <= budget => Ok(()),
+            Expectation::FrameTime(_) => Err("over budget".to_string()),
+            Expectation::Dead => Err("the run was still going".to_string()),
+        };
+        scenario_run.report(&check, result);
+        // Worth seeing even when it's within budget, to compare runs by.
+        if let Expectation::FrameTime(_) = check.expect {
+            println!(
+                "       frames took {:.2}ms on average, {:.2}ms at worst",
+                frame_times.mean(),
+                frame_times.worst()
+            );
+        }
+        scenario_run.next_check += 1;
+    }
+
+    if scenario_run.next_check == scenario_run.scenario.checks.len() {
+        exit_writer.send(scenario_run.exit());
+    }
+}
+
+/// Idle runs pause themselves, and nothing will ever unpause one here, or
+/// leave the shop between waves.
+fn give_up_on_stuck_run(
+    mut scenario_run: ResMut<ScenarioRun>,
+    run_state: Res<State<RunState>>,
+    stats: Res<RunStats>,
+    mut exit_writer: EventWriter<AppExit>,
+) {
+    let last_check = scenario_run
+        .scenario
+        .checks
+        .last()
+        .map_or(0., |check| check.at);
+    let error = if *run_state.get() == RunState::Paused {
+        format!("the run paused itself at {:.1}s", stats.duration)
+    } else if *run_state.get() == RunState::Shop {
+        format!("the run stopped at the shop at {:.1}s", stats.duration)
+    } else if stats.duration > last_check + TIMEOUT_GRACE {
+        "timed out".to_string()
+    } else {
+        return;
+    };
+
+    for check in scenario_run.remaining_checks() {
+        scenario_run.report(&check, Err(error.clone()));
+    }
+    scenario_run.next_check = scenario_run.scenario.checks.len();
+    exit_writer.send(scenario_run.exit());
+}
+
+/// Checks still to come once the run is over only pass if they expected it.
+fn finish_ended_run(
+    mut scenario_run: ResMut<ScenarioRun>,
+    game_state: Res<State<GameState>>,
+    stats: Res<RunStats>,
+    mut exit_writer: EventWriter<AppExit>,
+) {
+    let won = *game_state.get() == GameState::Victory;
+    for check in scenario_run.remaining_checks() {
+        let result = match check.expect {
+            Expectation::Dead if !won => Ok(()),
+            _ if won => Err(format!("the run was won at {:.1}s", stats.duration)),
+            _ => Err(format!("the run ended at {:.1}s", stats.duration)),
+        };
+        scenario_run.report(&check, result);
+    }
+    scenario_run.next_check = scenario_run.scenario.checks.len();
+    exit_writer.send(scenario_run.exit());
+}
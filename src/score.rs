@@ -0,0 +1,38 @@
+use crate::{BallEaten, GameState, InGameSet};
+use bevy::prelude::*;
+
+/// At this speed a ball is worth twice as much, in px/s.
+const DOUBLE_POINTS_SPEED: f32 = 500.;
+
+/// A run's score, kept apart from the player's number: every ball eaten is
+/// worth its number in points, more if the player was moving fast when they
+/// ate it. The number shrinks on hits and stops at the size cap, where the
+/// growth it misses out on is worth extra points instead, but the score only
+/// ever goes up, so it's what runs are compared by.
+pub struct ScorePlugin;
+
+#[derive(Resource, Default)]
+pub struct Score(pub i32);
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Score>()
+            .add_systems(OnEnter(GameState::InGame), reset_score)
+            .add_systems(Update, award_points.in_set(InGameSet));
+    }
+}
+
+/// What eating a ball is worth at a speed.
+pub fn points(ball_number: i32, speed: f32) -> i32 {
+    (ball_number as f32 * (1. + speed / DOUBLE_POINTS_SPEED)).round() as i32
+}
+
+fn reset_score(mut score: ResMut<Score>) {
+    score.0 = 0;
+}
+
+fn award_points(mut ball_eaten_reader: EventReader<BallEaten>, mut score: ResMut<Score>) {
+    for BallEaten { points, .. } in ball_eaten_reader.read() {
+        score.0 += points;
+    }
+}
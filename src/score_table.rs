@@ -0,0 +1,655 @@
+use crate::{
+    CurrentScore, GameState, HighScoreNameEntry,
+    config::GameConfig,
+    difficulty::Difficulty,
+    finished_run,
+    modes::GameModes,
+    new_game_plus::NewGamePlus,
+    prompts::{MenuAction, MenuInput, Prompt},
+    record_final_score, recorded_run,
+    stats::RunStats,
+    storage::{Storage, Versioned},
+    text_entry::{TextEntered, TextEntrySet},
+    weekly::WeeklyPlaylist,
+};
+use bevy::{prelude::*, utils::SystemTime};
+use std::{fmt, str::FromStr};
+
+const SCORE_TABLE_KEY: &str = "score_table";
+const MAX_RECORDS: usize = 100;
+const VISIBLE_ROWS: usize = 10;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const LATEST_RECORD_COLOR: Color = Color::srgb(1., 0.8, 0.2);
+
+/// Every finished run is kept (up to [`MAX_RECORDS`] of the best) with enough
+/// about it to tell runs apart, and the high-score screen lists them filtered
+/// by mode and arena and sorted by score, survival time or peak number. The
+/// death screen shows the top runs with the one just played picked out, and
+/// asks for a name if it made it in.
+pub struct ScoreTablePlugin;
+
+struct ScoreRecord {
+    score: i32,
+    duration: f32,
+    peak_number: i32,
+    arena: String,
+    new_game_plus: bool,
+    /// The week whose playlist the run was played in, if any.
+    playlist: Option<String>,
+    /// In days since the epoch, 0 for runs from before dates were kept.
+    date: i64,
+    /// Typed in on the death screen, if it was.
+    name: Option<String>,
+    /// The name of the game mode it was played in.
+    mode: String,
+    difficulty: String,
+}
+
+/// Saved as one record per line, fields separated by spaces. Runs outside a
+/// playlist or without a name save `-` for them.
+#[derive(Resource, Default)]
+pub struct ScoreTable {
+    records: Vec<ScoreRecord>,
+    /// Where the run just played went, until the next one.
+    latest: Option<usize>,
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+enum ModeFilter {
+    #[default]
+    All,
+    Normal,
+    NewGamePlus,
+    /// This week's playlist.
+    Weekly,
+    /// A registered game mode, by name.
+    Game(&'static str),
+}
+
+#[derive(Clone, Copy, Default)]
+enum SortBy {
+    #[default]
+    Score,
+    Duration,
+    PeakNumber,
+}
+
+#[derive(Resource, Default)]
+struct ScoreTableView {
+    mode: ModeFilter,
+    arena: Option<String>,
+    sort: SortBy,
+}
+
+#[derive(Component)]
+struct ScoreTableScreenEntity;
+
+impl Plugin for ScoreTablePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScoreTable>()
+            .init_resource::<ScoreTableView>()
+            .add_systems(Startup, load_score_table)
+            .add_systems(
+                OnExit(GameState::InGame),
+                record_run
+                    .after(record_final_score)
+                    .run_if(recorded_run.and(finished_run)),
+            )
+            .add_systems(OnEnter(GameState::InGame), forget_latest_record)
+            .add_systems(Update, name_latest_record.after(TextEntrySet))
+            .add_systems(OnEnter(GameState::HighScores), reset_score_table_view)
+            .add_systems(
+                Update,
+                (
+                    change_score_table_view,
+                    show_score_table.run_if(resource_changed::<ScoreTableView>),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::HighScores)),
+            );
+    }
+}
+
+impl fmt::Display for ScoreTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for record in &self.records {
+            writeln!(
+                f,
+                "{} {} {} {} {} {} {} {} {} {}",
+                record.score,
+                record.duration,
+                record.peak_number,
+                record.arena,
+                record.new_game_plus,
+                record.playlist.as_deref().unwrap_or("-"),
+                record.date,
+                record.name.as_deref().unwrap_or("-"),
+                record.mode,
+                record.difficulty
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ScoreTable {
+    type Err = std::convert::Infallible;
+
+    /// Lines that don't parse are dropped rather than failing the whole table.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let records = s
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                Some(ScoreRecord {
+                    score: fields.next()?.parse().ok()?,
+                    duration: fields.next()?.parse().ok()?,
+                    peak_number: fields.next()?.parse().ok()?,
+                    arena: fields.next()?.to_string(),
+                    new_game_plus: fields.next()?.parse().ok()?,
+                    playlist: Some(fields.next()?)
+                        .filter(|playlist| *playlist != "-")
+                        .map(str::to_string),
+                    date: fields.next()?.parse().ok()?,
+                    name: Some(fields.next()?)
+                        .filter(|name| *name != "-")
+                        .map(str::to_string),
+                    mode: fields.next()?.to_string(),
+                    difficulty: fields.next()?.to_string(),
+                })
+            })
+            .collect();
+
+        Ok(ScoreTable {
+            records,
+            latest: None,
+        })
+    }
+}
+
+impl Versioned for ScoreTable {
+    const VERSION: u32 = 3;
+
+    fn migrate(version: u32, saved: &str) -> Option<String> {
+        match version {
+            // Untagged saves may have records from before playlists existed,
+            // without that field.
+            0 => Some(
+                saved
+                    .lines()
+                    .map(|line| match line.split_whitespace().count() {
+                        5 => format!("{line} -"),
+                        _ => line.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            // Records from before dates and names were kept.
+            1 => Some(
+                saved
+                    .lines()
+                    .map(|line| format!("{line} 0 -"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            // Records from before the mode and difficulty were kept, when
+            // every run was Classic on Normal.
+            2 => Some(
+                saved
+                    .lines()
+                    .map(|line| format!("{line} Classic Normal"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl ModeFilter {
+    /// Goes through every registered game mode after the kinds of run.
+    fn next(self, modes: &GameModes) -> Self {
+        let mut names = modes.names();
+        let next_game = match self {
+            ModeFilter::All => return ModeFilter::Normal,
+            ModeFilter::Normal => return ModeFilter::NewGamePlus,
+            ModeFilter::NewGamePlus => return ModeFilter::Weekly,
+            ModeFilter::Weekly => names.next(),
+            ModeFilter::Game(current) => names.skip_while(|name| *name != current).nth(1),
+        };
+        next_game.map_or(ModeFilter::All, ModeFilter::Game)
+    }
+
+    fn matches(self, record: &ScoreRecord, week: &str) -> bool {
+        match self {
+            ModeFilter::All => true,
+            ModeFilter::Normal => !record.new_game_plus && record.playlist.is_none(),
+            ModeFilter::NewGamePlus => record.new_game_plus,
+            ModeFilter::Weekly => record.playlist.as_deref() == Some(week),
+            ModeFilter::Game(name) => record.mode == name,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ModeFilter::All => "all",
+            ModeFilter::Normal => "normal",
+            ModeFilter::NewGamePlus => "New Game+",
+            ModeFilter::Weekly => "this week",
+            ModeFilter::Game(name) => name,
+        }
+    }
+}
+
+impl SortBy {
+    fn next(self) -> Self {
+        match self {
+            SortBy::Score => SortBy::Duration,
+            SortBy::Duration => SortBy::PeakNumber,
+            SortBy::PeakNumber => SortBy::Score,
+        }
+    }
+
+    fn key(self, record: &ScoreRecord) -> f32 {
+        match self {
+            SortBy::Score => record.score as f32,
+            SortBy::Duration => record.duration,
+            SortBy::PeakNumber => record.peak_number as f32,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SortBy::Score => "score",
+            SortBy::Duration => "time",
+            SortBy::PeakNumber => "peak number",
+        }
+    }
+}
+
+fn today() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |since| (since.as_secs() / SECONDS_PER_DAY) as i64)
+}
+
+/// Like `2026-10-14`, for a day since the epoch.
+fn format_date(days_since_epoch: i64) -> String {
+    // From Howard Hinnant's `civil_from_days`.
+    let days = days_since_epoch + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!("{year}-{month:02}-{day:02}")
+}
+
+impl ScoreRecord {
+    /// One line of a table, after its rank.
+    fn describe(&self) -> String {
+        let mut parts = vec![self.score.to_string()];
+        parts.extend(self.name.clone());
+        parts.extend([
+            format!("{:.1}s", self.duration),
+            format!("peak {}", self.peak_number),
+            self.arena.clone(),
+        ]);
+        if self.mode != "Classic" {
+            parts.push(self.mode.clone());
+        }
+        if self.difficulty != "Normal" {
+            parts.push(self.difficulty.clone());
+        }
+        if self.new_game_plus {
+            parts.push("New Game+".to_string());
+        }
+        if let Some(week) = &self.playlist {
+            parts.push(week.clone());
+        }
+        if self.date > 0 {
+            parts.push(format_date(self.date));
+        }
+        parts.join(" - ")
+    }
+}
+
+impl ScoreTable {
+    /// Whether the run just played made the top of the table and hasn't been
+    /// given a name yet.
+    pub fn awaiting_name(&self) -> bool {
+        self.latest
+            .is_some_and(|latest| latest < VISIBLE_ROWS && self.records[latest].name.is_none())
+    }
+}
+
+/// The best runs of all, with the one just played picked out.
+pub fn spawn_top_runs(builder: &mut ChildBuilder, table: &ScoreTable) {
+    builder
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.),
+            ..default()
+        })
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new(format!("top {VISIBLE_ROWS}")),
+                TextFont {
+                    font_size: 30.,
+                    ..default()
+                },
+            ));
+            for (rank, record) in table.records.iter().take(VISIBLE_ROWS).enumerate() {
+                let color = if table.latest == Some(rank) {
+                    LATEST_RECORD_COLOR
+                } else {
+                    Color::WHITE
+                };
+                builder.spawn((
+                    Text::new(format!("{}. {}", rank + 1, record.describe())),
+                    TextFont {
+                        font_size: 18.,
+                        ..default()
+                    },
+                    TextColor(color),
+                ));
+            }
+            if table.awaiting_name() {
+                builder.spawn((
+                    Text::new("your run made it! enter your name"),
+                    TextFont {
+                        font_size: 18.,
+                        ..default()
+                    },
+                    TextColor(LATEST_RECORD_COLOR),
+                ));
+            }
+        });
+}
+
+fn load_score_table(storage: Res<Storage>, mut table: ResMut<ScoreTable>) {
+    if let Some(loaded) = storage.load_versioned(SCORE_TABLE_KEY) {
+        *table = loaded;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record_run(
+    mut table: ResMut<ScoreTable>,
+    mut storage: ResMut<Storage>,
+    current_score: Res<CurrentScore>,
+    stats: Res<RunStats>,
+    config: Res<GameConfig>,
+    new_game_plus: Res<NewGamePlus>,
+    weekly_playlist: Res<WeeklyPlaylist>,
+    modes: Res<GameModes>,
+    difficulty: Res<Difficulty>,
+) {
+    table.records.push(ScoreRecord {
+        score: current_score.0,
+        duration: stats.duration,
+        peak_number: stats.peak_number,
+        arena: format!("{:?}", config.arena),
+        new_game_plus: new_game_plus.active(),
+        playlist: weekly_playlist.active.then(|| weekly_playlist.week.clone()),
+        date: today(),
+        name: None,
+        mode: modes.selected().name().to_string(),
+        difficulty: difficulty.to_string(),
+    });
+    table
+        .records
+        .sort_by_key(|record| std::cmp::Reverse(record.score));
+    // The sort is stable, so the new record comes after any it ties with.
+    let placed = table
+        .records
+        .iter()
+        .filter(|record| record.score >= current_score.0)
+        .count();
+    table.latest = (placed <= MAX_RECORDS).then(|| placed - 1);
+    table.records.truncate(MAX_RECORDS);
+
+    storage.save_versioned(SCORE_TABLE_KEY, &*table);
+}
+
+fn forget_latest_record(mut table: ResMut<ScoreTable>) {
+    table.latest = None;
+}
+
+fn name_latest_record(
+    mut entered_reader: EventReader<TextEntered>,
+    name_entry_query: Query<(), With<HighScoreNameEntry>>,
+    mut table: ResMut<ScoreTable>,
+    mut storage: ResMut<Storage>,
+) {
+    for TextEntered { entry, text } in entered_reader.read() {
+        if !name_entry_query.contains(*entry) || text.is_empty() {
+            continue;
+        }
+        let Some(latest) = table.latest else {
+            continue;
+        };
+
+        table.records[latest].name = Some(text.clone());
+        storage.save_versioned(SCORE_TABLE_KEY, &*table);
+    }
+}
+
+fn reset_score_table_view(mut view: ResMut<ScoreTableView>) {
+    *view = ScoreTableView::default();
+}
+
+fn change_score_table_view(
+    menu_input: MenuInput,
+    table: Res<ScoreTable>,
+    modes: Res<GameModes>,
+    mut view: ResMut<ScoreTableView>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if menu_input.just_pressed(MenuAction::CycleMode) {
+        view.mode = view.mode.next(&modes);
+    }
+    if menu_input.just_pressed(MenuAction::CycleSort) {
+        view.sort = view.sort.next();
+    }
+    if menu_input.just_pressed(MenuAction::CycleArena) {
+        let mut arenas: Vec<&String> = table.records.iter().map(|record| &record.arena).collect();
+        arenas.sort();
+        arenas.dedup();
+
+        // Cycles through the arenas that have been played, then back to all.
+        view.arena = match &view.arena {
+            None => arenas.first().map(|arena| arena.to_string()),
+            Some(current) => arenas
+                .iter()
+                .find(|arena| arena.as_str() > current.as_str())
+                .map(|arena| arena.to_string()),
+        };
+    }
+    if menu_input.just_pressed(MenuAction::PlayAgain) {
+        next_game_state.set(GameState::InGame);
+    }
+}
+
+fn show_score_table(
+    mut commands: Commands,
+    table: Res<ScoreTable>,
+    view: Res<ScoreTableView>,
+    weekly_playlist: Res<WeeklyPlaylist>,
+    screen_query: Query<Entity, With<ScoreTableScreenEntity>>,
+) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let mut records: Vec<&ScoreRecord> = table
+        .records
+        .iter()
+        .filter(|record| view.mode.matches(record, &weekly_playlist.week))
+        .filter(|record| {
+            view.arena
+                .as_ref()
+                .is_none_or(|arena| record.arena == *arena)
+        })
+        .collect();
+    records.sort_by(|a, b| view.sort.key(b).total_cmp(&view.sort.key(a)));
+
+    let filters = format!(
+        "mode - {}    arena - {}    sort - {}",
+        view.mode.name(),
+        view.arena.as_deref().unwrap_or("all"),
+        view.sort.name()
+    );
+    let rows: Vec<String> = if records.is_empty() {
+        vec!["no runs yet".to_string()]
+    } else {
+        records
+            .iter()
+            .take(VISIBLE_ROWS)
+            .enumerate()
+            .map(|(rank, record)| format!("{}. {}", rank + 1, record.describe()))
+            .collect()
+    };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ScoreTableScreenEntity,
+            StateScoped(GameState::HighScores),
+        ))
+        .with_children(|builder| {
+            builder
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(6.),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder.spawn((
+                        Text::new("High scores"),
+                        TextFont {
+                            font_size: 60.,
+                            ..default()
+                        },
+                    ));
+                    builder.spawn((
+                        Text::new(filters),
+                        TextFont {
+                            font_size: 24.,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                    ));
+                    for row in rows {
+                        builder.spawn((
+                            Text::new(row),
+                            TextFont {
+                                font_size: 30.,
+                                ..default()
+                            },
+                        ));
+                    }
+                    builder.spawn((
+                        Text::default(),
+                        Prompt(vec![
+                            (MenuAction::CycleMode, "mode"),
+                            (MenuAction::CycleArena, "arena"),
+                            (MenuAction::CycleSort, "sort"),
+                            (MenuAction::PlayAgain, "play again"),
+                        ]),
+                        TextFont {
+                            font_size: 20.,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                    ));
+                });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(saved: &str) -> ScoreTable {
+        let mut storage = Storage::in_memory();
+        storage.save(SCORE_TABLE_KEY, saved);
+        storage.load_versioned(SCORE_TABLE_KEY).unwrap()
+    }
+
+    #[test]
+    fn loads_untagged_records_from_before_playlists() {
+        let table = load("120 45.5 30 Classic false\n80 20 12 Drift true");
+
+        assert_eq!(table.records.len(), 2);
+        let record = &table.records[0];
+        assert_eq!(record.score, 120);
+        assert_eq!(record.duration, 45.5);
+        assert_eq!(record.peak_number, 30);
+        assert_eq!(record.arena, "Classic");
+        assert!(!record.new_game_plus);
+        assert_eq!(record.playlist, None);
+        assert_eq!(record.date, 0);
+        assert_eq!(record.name, None);
+        assert!(table.records[1].new_game_plus);
+    }
+
+    #[test]
+    fn loads_untagged_records_with_playlists() {
+        let table = load("50 10 8 Vault false 2026-W03");
+
+        assert_eq!(table.records[0].playlist.as_deref(), Some("2026-W03"));
+        assert_eq!(table.records[0].date, 0);
+    }
+
+    #[test]
+    fn loads_version_1_records() {
+        let table = load("version 1\n50 10 8 Workshop false -\n60 12 9 Classic true 2026-W10");
+
+        assert_eq!(table.records.len(), 2);
+        assert_eq!(table.records[0].playlist, None);
+        assert_eq!(table.records[1].playlist.as_deref(), Some("2026-W10"));
+        assert_eq!(table.records[1].name, None);
+    }
+
+    #[test]
+    fn loads_version_2_records_as_classic_on_normal() {
+        let table = load("version 2\n300 95.25 44 Drift true - 20500 Ada");
+
+        let record = &table.records[0];
+        assert_eq!(record.name.as_deref(), Some("Ada"));
+        assert_eq!(record.mode, "Classic");
+        assert_eq!(record.difficulty, "Normal");
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let table = load("version 3\n300 95.25 44 Drift true - 20500 Ada Zen Easy");
+        let mut storage = Storage::in_memory();
+        storage.save_versioned(SCORE_TABLE_KEY, &table);
+
+        let loaded: ScoreTable = storage.load_versioned(SCORE_TABLE_KEY).unwrap();
+        assert_eq!(loaded.to_string(), table.to_string());
+        let record = &loaded.records[0];
+        assert_eq!(record.date, 20500);
+        assert_eq!(record.name.as_deref(), Some("Ada"));
+        assert_eq!(record.mode, "Zen");
+        assert_eq!(record.difficulty, "Easy");
+    }
+}
@@ -0,0 +1,481 @@
+use crate::{
+    confirm_quit::confirm_quit_closed,
+    menu::spawn_menu_button,
+    prompts::{MenuAction, MenuInput, Prompt},
+    storage::{Storage, Versioned},
+};
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowMode},
+};
+use std::{fmt, str::FromStr};
+
+const SETTINGS_KEY: &str = "settings";
+const VOLUME_STEP: u32 = 10;
+
+/// Player preferences that survive restarts, saved to storage whenever they
+/// change. The settings screen opens over whatever screen it was opened from
+/// (the main menu or the pause menu), which waits behind it until it closes.
+pub struct SettingsPlugin;
+
+#[derive(Resource, Clone, Copy, PartialEq)]
+pub struct Settings {
+    /// In percent.
+    pub volume: u32,
+    pub fullscreen: bool,
+    pub movement_keys: MovementKeys,
+    pub cursor: CursorMode,
+    /// Gamepad and device vibration, see [`crate::haptics`].
+    pub haptics: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum MovementKeys {
+    AD,
+    Arrows,
+}
+
+/// See [`crate::cursor`].
+#[derive(Clone, Copy, PartialEq)]
+pub enum CursorMode {
+    /// The themed cursor, hidden during play.
+    Themed,
+    /// The system cursor, hidden during play.
+    System,
+    /// The system cursor, never hidden.
+    AlwaysShown,
+}
+
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SettingsState {
+    #[default]
+    Closed,
+    Open,
+}
+
+#[derive(Component, Clone, Copy, PartialEq)]
+enum SettingsButton {
+    Volume,
+    Fullscreen,
+    MovementKeys,
+    Cursor,
+    Haptics,
+    Back,
+}
+
+/// Read apart from changing the [`Settings`], as [`MenuInput`] reads them
+/// for the movement keys.
+enum SettingsChange {
+    VolumeDown,
+    VolumeUp,
+    Press(SettingsButton),
+}
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<SettingsState>()
+            .enable_state_scoped_entities::<SettingsState>()
+            .add_systems(OnEnter(SettingsState::Open), setup_settings_screen)
+            .add_systems(
+                Update,
+                (
+                    read_settings_input
+                        .pipe(change_settings)
+                        .run_if(confirm_quit_closed),
+                    show_settings,
+                )
+                    .chain()
+                    .run_if(in_state(SettingsState::Open)),
+            )
+            .add_systems(
+                Update,
+                (apply_settings, save_settings).run_if(resource_changed::<Settings>),
+            );
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            volume: 100,
+            fullscreen: true,
+            movement_keys: MovementKeys::AD,
+            cursor: CursorMode::Themed,
+            haptics: true,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load(storage: &Storage) -> Self {
+        storage.load_versioned(SETTINGS_KEY).unwrap_or_default()
+    }
+
+    /// What clicking `button` does. Volume goes up a step, wrapping back to
+    /// silent after full.
+    fn change(&mut self, button: SettingsButton) {
+        match button {
+            SettingsButton::Volume => {
+                self.volume = (self.volume + VOLUME_STEP) % (100 + VOLUME_STEP)
+            }
+            SettingsButton::Fullscreen => self.fullscreen = !self.fullscreen,
+            SettingsButton::MovementKeys => {
+                self.movement_keys = match self.movement_keys {
+                    MovementKeys::AD => MovementKeys::Arrows,
+                    MovementKeys::Arrows => MovementKeys::AD,
+                }
+            }
+            SettingsButton::Cursor => {
+                self.cursor = match self.cursor {
+                    CursorMode::Themed => CursorMode::System,
+                    CursorMode::System => CursorMode::AlwaysShown,
+                    CursorMode::AlwaysShown => CursorMode::Themed,
+                }
+            }
+            SettingsButton::Haptics => self.haptics = !self.haptics,
+            SettingsButton::Back => {}
+        }
+    }
+}
+
+impl fmt::Display for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.volume, self.fullscreen, self.movement_keys, self.cursor, self.haptics
+        )
+    }
+}
+
+impl FromStr for Settings {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.split_whitespace();
+        let mut field = || fields.next().ok_or(());
+
+        Ok(Settings {
+            volume: field()?.parse::<u32>().map_err(|_| ())?.min(100),
+            fullscreen: field()?.parse().map_err(|_| ())?,
+            movement_keys: field()?.parse()?,
+            cursor: field()?.parse()?,
+            haptics: field()?.parse().map_err(|_| ())?,
+        })
+    }
+}
+
+impl Versioned for Settings {
+    const VERSION: u32 = 3;
+
+    fn migrate(version: u32, saved: &str) -> Option<String> {
+        match version {
+            // Untagged saves were already in the first format.
+            0 => Some(saved.to_string()),
+            1 => Some(format!("{} {}", saved.trim(), CursorMode::Themed)),
+            2 => Some(format!("{} true", saved.trim())),
+            _ => None,
+        }
+    }
+}
+
+impl MovementKeys {
+    /// The keys for moving left and right.
+    pub fn keys(self) -> (KeyCode, KeyCode) {
+        match self {
+            MovementKeys::AD => (KeyCode::KeyA, KeyCode::KeyD),
+            MovementKeys::Arrows => (KeyCode::ArrowLeft, KeyCode::ArrowRight),
+        }
+    }
+}
+
+impl fmt::Display for MovementKeys {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            MovementKeys::AD => "ad",
+            MovementKeys::Arrows => "arrows",
+        })
+    }
+}
+
+impl FromStr for MovementKeys {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ad" => Ok(MovementKeys::AD),
+            "arrows" => Ok(MovementKeys::Arrows),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for CursorMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            CursorMode::Themed => "themed",
+            CursorMode::System => "system",
+            CursorMode::AlwaysShown => "shown",
+        })
+    }
+}
+
+impl FromStr for CursorMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "themed" => Ok(CursorMode::Themed),
+            "system" => Ok(CursorMode::System),
+            "shown" => Ok(CursorMode::AlwaysShown),
+            _ => Err(()),
+        }
+    }
+}
+
+impl SettingsButton {
+    const ROWS: [SettingsButton; 6] = [
+        SettingsButton::Volume,
+        SettingsButton::Fullscreen,
+        SettingsButton::MovementKeys,
+        SettingsButton::Cursor,
+        SettingsButton::Haptics,
+        SettingsButton::Back,
+    ];
+
+    fn label(self, settings: &Settings) -> String {
+        match self {
+            SettingsButton::Volume => format!("Volume {}%", settings.volume),
+            SettingsButton::Fullscreen if settings.fullscreen => "Fullscreen".to_string(),
+            SettingsButton::Fullscreen => "Windowed".to_string(),
+            SettingsButton::MovementKeys => match settings.movement_keys {
+                MovementKeys::AD => "Move with A/D".to_string(),
+                MovementKeys::Arrows => "Move with arrows".to_string(),
+            },
+            SettingsButton::Cursor => match settings.cursor {
+                CursorMode::Themed => "Themed cursor".to_string(),
+                CursorMode::System => "System cursor".to_string(),
+                CursorMode::AlwaysShown => "Cursor always shown".to_string(),
+            },
+            SettingsButton::Haptics if settings.haptics => "Vibration on".to_string(),
+            SettingsButton::Haptics => "Vibration off".to_string(),
+            SettingsButton::Back => "Back".to_string(),
+        }
+    }
+}
+
+/// Run condition for screens the settings screen can open over, so they don't
+/// also react to its input.
+pub fn settings_closed(settings_state: Res<State<SettingsState>>) -> bool {
+    *settings_state.get() == SettingsState::Closed
+}
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    *global_volume = GlobalVolume::new(settings.volume as f32 / 100.);
+
+    let mode = if settings.fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Primary)
+    } else {
+        WindowMode::Windowed
+    };
+    // Left alone at startup, where the window was already opened in the saved
+    // mode unless `--windowed` overrode it.
+    if settings.is_added() {
+        return;
+    }
+    for mut window in window_query.iter_mut() {
+        window.mode = mode;
+    }
+}
+
+fn save_settings(settings: Res<Settings>, mut storage: ResMut<Storage>) {
+    if !settings.is_added() {
+        storage.save_versioned(SETTINGS_KEY, &*settings);
+    }
+}
+
+fn setup_settings_screen(mut commands: Commands, settings: Res<Settings>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(20.),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0., 0., 0., 0.8)),
+            GlobalZIndex(5),
+            StateScoped(SettingsState::Open),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new("Settings"),
+                TextFont {
+                    font_size: 60.,
+                    ..default()
+                },
+            ));
+            for button in SettingsButton::ROWS {
+                spawn_menu_button(builder, button, &button.label(&settings));
+            }
+            builder.spawn((
+                Text::default(),
+                Prompt(vec![
+                    (MenuAction::VolumeDown, "quieter"),
+                    (MenuAction::VolumeUp, "louder"),
+                    (MenuAction::ToggleFullscreen, "fullscreen"),
+                    (MenuAction::ToggleMovementKeys, "movement keys"),
+                    (MenuAction::CycleCursor, "cursor"),
+                    (MenuAction::ToggleHaptics, "vibration"),
+                    (MenuAction::Quit, "back"),
+                ]),
+                TextFont {
+                    font_size: 20.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+/// Clicking a row changes it; every row also has its own key or button.
+fn read_settings_input(
+    button_query: Query<(&Interaction, &SettingsButton), Changed<Interaction>>,
+    menu_input: MenuInput,
+) -> Vec<SettingsChange> {
+    let mut changes: Vec<SettingsChange> = [
+        (MenuAction::VolumeDown, SettingsChange::VolumeDown),
+        (MenuAction::VolumeUp, SettingsChange::VolumeUp),
+        (
+            MenuAction::ToggleFullscreen,
+            SettingsChange::Press(SettingsButton::Fullscreen),
+        ),
+        (
+            MenuAction::ToggleMovementKeys,
+            SettingsChange::Press(SettingsButton::MovementKeys),
+        ),
+        (
+            MenuAction::CycleCursor,
+            SettingsChange::Press(SettingsButton::Cursor),
+        ),
+        (
+            MenuAction::ToggleHaptics,
+            SettingsChange::Press(SettingsButton::Haptics),
+        ),
+        (
+            MenuAction::Quit,
+            SettingsChange::Press(SettingsButton::Back),
+        ),
+    ]
+    .into_iter()
+    .filter(|(action, _)| menu_input.just_pressed(*action))
+    .map(|(_, change)| change)
+    .collect();
+
+    let clicked = button_query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| *button);
+    changes.extend(clicked.map(SettingsChange::Press));
+    changes
+}
+
+fn change_settings(
+    In(changes): In<Vec<SettingsChange>>,
+    mut settings: ResMut<Settings>,
+    mut next_settings_state: ResMut<NextState<SettingsState>>,
+) {
+    for change in changes {
+        match change {
+            SettingsChange::VolumeDown => {
+                settings.volume = settings.volume.saturating_sub(VOLUME_STEP)
+            }
+            SettingsChange::VolumeUp => settings.volume = (settings.volume + VOLUME_STEP).min(100),
+            SettingsChange::Press(SettingsButton::Back) => {
+                next_settings_state.set(SettingsState::Closed);
+                return;
+            }
+            SettingsChange::Press(button) => settings.change(button),
+        }
+    }
+}
+
+fn show_settings(
+    settings: Res<Settings>,
+    button_query: Query<(&SettingsButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (button, children) in button_query.iter() {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.0 = button.label(&settings);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(saved: &str) -> Option<Settings> {
+        let mut storage = Storage::in_memory();
+        storage.save(SETTINGS_KEY, saved);
+        storage.load_versioned(SETTINGS_KEY)
+    }
+
+    #[test]
+    fn loads_untagged_settings() {
+        let settings = load("40 false arrows").unwrap();
+        assert!(
+            settings
+                == Settings {
+                    volume: 40,
+                    fullscreen: false,
+                    movement_keys: MovementKeys::Arrows,
+                    cursor: CursorMode::Themed,
+                    haptics: true,
+                }
+        );
+    }
+
+    #[test]
+    fn loads_version_1_settings() {
+        let settings = load("version 1\n70 true ad").unwrap();
+        assert_eq!(settings.volume, 70);
+        assert!(settings.cursor == CursorMode::Themed);
+    }
+
+    #[test]
+    fn loads_version_2_settings() {
+        let settings = load("version 2\n70 true ad system").unwrap();
+        assert!(settings.cursor == CursorMode::System);
+        assert!(settings.haptics);
+    }
+
+    #[test]
+    fn settings_round_trip() {
+        let settings = Settings {
+            volume: 30,
+            fullscreen: false,
+            movement_keys: MovementKeys::Arrows,
+            cursor: CursorMode::AlwaysShown,
+            haptics: false,
+        };
+        let mut storage = Storage::in_memory();
+        storage.save_versioned(SETTINGS_KEY, &settings);
+
+        assert!(Settings::load(&storage) == settings);
+    }
+}
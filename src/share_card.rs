@@ -0,0 +1,199 @@
+use crate::{
+    BACKGROUND_COLOR, CurrentScore, GameRng, GameState,
+    prompts::{MenuAction, MenuInput},
+    stats::RunStats,
+    text_entry::no_text_entry,
+    toast::ShowToast,
+    weekly::WeeklyPlaylist,
+};
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::{
+            RenderLayers,
+            screenshot::{Screenshot, save_to_disk},
+        },
+    },
+};
+use std::path::PathBuf;
+
+const CARD_WIDTH: u32 = 800;
+const CARD_HEIGHT: u32 = 420;
+/// Nothing in the world is on this layer, so the card's camera only draws the
+/// card.
+const CARD_RENDER_LAYER: usize = 7;
+/// Frames to let the card lay out and render before capturing it.
+const FRAMES_BEFORE_CAPTURE: u32 = 2;
+
+/// Pressing P after a run draws a card with how it went (score, survival
+/// time, biggest number, seed and the playlist's mutators, if any) to an
+/// off-screen texture and saves it as a PNG to post, instead of a screenshot
+/// of the whole screen. Web builds download it.
+pub struct ShareCardPlugin;
+
+/// A card being drawn, captured and cleaned up once it's had time to render.
+#[derive(Component)]
+struct ShareCard {
+    image: Handle<Image>,
+    path: PathBuf,
+    frames_left: u32,
+    /// The card's UI, drawn by this entity's camera.
+    root: Entity,
+}
+
+impl Plugin for ShareCardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            draw_share_card.run_if(
+                (in_state(GameState::DeathScreen).or(in_state(GameState::Victory)))
+                    .and(no_text_entry),
+            ),
+        )
+        .add_systems(Update, capture_share_card);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn card_path(file_name: String) -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("shape-eater")
+        .join("cards")
+        .join(file_name)
+}
+
+/// Downloaded under just its name.
+#[cfg(target_arch = "wasm32")]
+fn card_path(file_name: String) -> PathBuf {
+    PathBuf::from(file_name)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_share_card(
+    mut commands: Commands,
+    menu_input: MenuInput,
+    card_query: Query<(), With<ShareCard>>,
+    current_score: Res<CurrentScore>,
+    stats: Res<RunStats>,
+    game_rng: Res<GameRng>,
+    weekly_playlist: Res<WeeklyPlaylist>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if !menu_input.just_pressed(MenuAction::ShareCard) || !card_query.is_empty() {
+        return;
+    }
+
+    let size = Extent3d {
+        width: CARD_WIDTH,
+        height: CARD_HEIGHT,
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+        | TextureUsages::COPY_SRC
+        | TextureUsages::COPY_DST
+        | TextureUsages::RENDER_ATTACHMENT;
+    let image = images.add(image);
+
+    let camera = commands
+        .spawn((
+            Camera2d,
+            Camera {
+                target: RenderTarget::Image(image.clone()),
+                // Ahead of the main camera, which clears the screen.
+                order: -1,
+                clear_color: ClearColorConfig::Custom(BACKGROUND_COLOR),
+                ..default()
+            },
+            RenderLayers::layer(CARD_RENDER_LAYER),
+        ))
+        .id();
+
+    let mutators = if weekly_playlist.active {
+        weekly_playlist.name()
+    } else {
+        "no mutators".to_string()
+    };
+    let lines = [
+        format!("score - {}", current_score.0),
+        format!("survived - {:.1}s", stats.duration),
+        format!("biggest number - {}", stats.peak_number),
+        format!("seed - {}", game_rng.seed),
+        mutators,
+    ];
+
+    let root = commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(10.),
+                ..default()
+            },
+            TargetCamera(camera),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new("shape-eater"),
+                TextFont {
+                    font_size: 70.,
+                    ..default()
+                },
+            ));
+            for line in lines {
+                builder.spawn((
+                    Text::new(line),
+                    TextFont {
+                        font_size: 30.,
+                        ..default()
+                    },
+                ));
+            }
+        })
+        .id();
+
+    commands.entity(camera).insert(ShareCard {
+        image,
+        path: card_path(format!("run-{}-{}.png", game_rng.seed, current_score.0)),
+        frames_left: FRAMES_BEFORE_CAPTURE,
+        root,
+    });
+}
+
+fn capture_share_card(
+    mut commands: Commands,
+    mut card_query: Query<(Entity, &mut ShareCard)>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    for (camera, mut card) in card_query.iter_mut() {
+        if card.frames_left > 0 {
+            card.frames_left -= 1;
+            continue;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Err(error) = card.path.parent().map_or(Ok(()), std::fs::create_dir_all) {
+            warn!("failed to create the cards directory: {error}");
+        }
+        commands
+            .spawn(Screenshot::image(card.image.clone()))
+            .observe(save_to_disk(card.path.clone()));
+        toast_writer.send(ShowToast(format!("Saved {}", card.path.display())));
+
+        commands.entity(card.root).despawn_recursive();
+        commands.entity(camera).despawn_recursive();
+    }
+}
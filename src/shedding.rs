@@ -0,0 +1,124 @@
+use crate::{
+    GameRng, InGameSet, Numbered, Player, SIZE_FACTOR,
+    prompts::{MenuAction, MenuInput},
+    spawn_numbered_ball,
+};
+use bevy::prelude::*;
+use rand::Rng;
+
+const SHED_FRACTION: f32 = 0.1;
+const FRAGMENT_NUMBER: i32 = 1;
+const FRAGMENT_SPEED: f32 = 300.;
+const FRAGMENT_LIFETIME: f32 = 3.;
+/// Fragments can't be eaten straight away, or the player would swallow
+/// them again the moment they're shed.
+const FRAGMENT_GRACE: f32 = 0.25;
+const FRAGMENT_COLOR: Color = Color::srgb(1., 0.6, 0.6);
+
+/// Lets the player shed a tenth of their number as a burst of small
+/// fragments, which can be eaten back for a few seconds before they vanish.
+pub struct SheddingPlugin;
+
+/// The player shed some of their number.
+#[derive(Event)]
+pub struct MassShed;
+
+#[derive(Component)]
+pub struct Fragment {
+    age: f32,
+}
+
+impl Fragment {
+    pub fn is_collectible(&self) -> bool {
+        self.age >= FRAGMENT_GRACE
+    }
+
+    /// In seconds.
+    pub fn age(&self) -> f32 {
+        self.age
+    }
+}
+
+impl Plugin for SheddingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MassShed>()
+            .add_systems(Update, (shed_mass, age_fragments).in_set(InGameSet));
+    }
+}
+
+fn shed_mass(
+    menu_input: MenuInput,
+    mut player_query: Query<(&Transform, &mut Numbered), With<Player>>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut game_rng: ResMut<GameRng>,
+    mut shed_writer: EventWriter<MassShed>,
+) {
+    if !menu_input.just_pressed(MenuAction::Shed) {
+        return;
+    }
+    let Ok((player_transform, mut player_number)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    let shed = ((player_number.0 as f32 * SHED_FRACTION) as i32).max(1);
+    if player_number.0 - shed < 1 {
+        return;
+    }
+    player_number.0 -= shed;
+    shed_writer.send(MassShed);
+
+    let player_position = player_transform.translation.truncate();
+    let clearance = player_number.0 as f32 * SIZE_FACTOR;
+
+    for _ in 0..shed {
+        let direction = Vec2::from_angle(game_rng.rng.random_range(0.0..std::f32::consts::TAU));
+        spawn_fragment(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            player_position + direction * clearance,
+            direction * FRAGMENT_SPEED,
+            0.,
+        );
+    }
+}
+
+/// A fragment `age` seconds after it was shed.
+pub fn spawn_fragment(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    position: Vec2,
+    velocity: Vec2,
+    age: f32,
+) -> Entity {
+    let fragment = spawn_numbered_ball(
+        commands,
+        meshes,
+        materials,
+        FRAGMENT_NUMBER,
+        position,
+        velocity,
+    );
+    commands.entity(fragment).insert((
+        Fragment { age },
+        MeshMaterial2d(materials.add(FRAGMENT_COLOR)),
+    ));
+    fragment
+}
+
+fn age_fragments(
+    mut commands: Commands,
+    mut fragment_query: Query<(Entity, &mut Fragment)>,
+    time: Res<Time>,
+) {
+    for (entity, mut fragment) in fragment_query.iter_mut() {
+        fragment.age += time.delta_secs();
+
+        if fragment.age >= FRAGMENT_LIFETIME {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
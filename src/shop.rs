@@ -0,0 +1,471 @@
+use crate::{
+    BallEaten, GameState, InGameEntity, InGameSet, Numbered, Player, RunState, SIZE_FACTOR,
+    config::GameConfig,
+    demo::Demo,
+    layers::RenderLayer,
+    menu::spawn_menu_button,
+    prompts::{MenuAction, MenuInput, Prompt},
+    safe_area::KeepInSafeArea,
+    toast::ShowToast,
+    tutorial::Tutorial,
+};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+const COIN_RADIUS: f32 = 6.;
+const COIN_COLOR: Color = Color::srgb(1., 0.8, 0.2);
+/// How fast coins burst out of an eaten ball, slowing to a stop.
+const COIN_SPEED: f32 = 200.;
+const COIN_DRAG: f32 = 3.;
+/// Coins nobody picks up are gone after this many seconds.
+const COIN_LIFETIME: f32 = 6.;
+/// Every this many of an eaten ball's number drops one more coin.
+const NUMBER_PER_COIN: i32 = 20;
+const SLOW_SPAWNS_MULTIPLIER: f32 = 1.3;
+const SIZE_BOOST: i32 = 5;
+
+/// With [`GameConfig::shop_wave_length`] set, runs come in waves of that many
+/// seconds. Eaten balls drop coins for the player to pick up, and between
+/// waves the run stops on a shop where they buy slower spawns for the next
+/// wave, a bigger number, or a shield against the next fatal hit. Demo and
+/// tutorial runs have no waves.
+pub struct ShopPlugin;
+
+#[derive(Resource, Default)]
+pub struct Coins(pub u32);
+
+/// What's been bought and not used up yet.
+#[derive(Resource, Default)]
+pub struct ShopEffects {
+    pub slow_spawns: bool,
+    pub shield: bool,
+}
+
+#[derive(Resource, Default)]
+pub struct Wave {
+    pub number: u32,
+    /// Seconds into it.
+    pub elapsed: f32,
+}
+
+#[derive(Component)]
+struct Coin {
+    age: f32,
+    velocity: Vec2,
+}
+
+#[derive(Component)]
+struct CoinCounter;
+
+#[derive(Component)]
+struct CoinText;
+
+#[derive(Clone, Copy, PartialEq)]
+enum ShopItem {
+    SlowSpawns,
+    SizeBoost,
+    Shield,
+}
+
+#[derive(Component, Clone, Copy)]
+enum ShopButton {
+    Buy(ShopItem),
+    NextWave,
+}
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Coins>()
+            .init_resource::<ShopEffects>()
+            .init_resource::<Wave>()
+            .add_systems(OnEnter(GameState::InGame), start_waves)
+            .add_systems(
+                Update,
+                (drop_coins, move_coins, collect_coins, end_wave)
+                    .chain()
+                    .in_set(InGameSet)
+                    .run_if(has_waves),
+            )
+            .add_systems(Update, update_coin_counter.run_if(has_waves))
+            .add_systems(OnEnter(RunState::Shop), (pause_physics, setup_shop_screen))
+            .add_systems(OnExit(RunState::Shop), resume_physics)
+            .add_systems(
+                Update,
+                (buy_items, show_shop)
+                    .chain()
+                    .run_if(in_state(RunState::Shop)),
+            );
+    }
+}
+
+/// Run condition for a run split into waves.
+fn has_waves(config: Res<GameConfig>, demo: Res<Demo>, tutorial: Res<Tutorial>) -> bool {
+    config.shop_wave_length.is_some() && !demo.running && !tutorial.running
+}
+
+impl ShopEffects {
+    pub fn spawn_interval_multiplier(&self) -> f32 {
+        if self.slow_spawns {
+            SLOW_SPAWNS_MULTIPLIER
+        } else {
+            1.
+        }
+    }
+}
+
+impl ShopItem {
+    const ALL: [ShopItem; 3] = [ShopItem::SlowSpawns, ShopItem::SizeBoost, ShopItem::Shield];
+
+    fn name(self) -> &'static str {
+        match self {
+            ShopItem::SlowSpawns => "Slow down",
+            ShopItem::SizeBoost => "Grow",
+            ShopItem::Shield => "Shield",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            ShopItem::SlowSpawns => "balls come slower all next wave",
+            ShopItem::SizeBoost => "+5 to your number",
+            ShopItem::Shield => "blocks the next hit from a bigger ball",
+        }
+    }
+
+    fn cost(self) -> u32 {
+        match self {
+            ShopItem::SlowSpawns => 5,
+            ShopItem::SizeBoost => 8,
+            ShopItem::Shield => 12,
+        }
+    }
+
+    /// Whether buying it again would be a waste.
+    fn is_owned(self, effects: &ShopEffects) -> bool {
+        match self {
+            ShopItem::SlowSpawns => effects.slow_spawns,
+            ShopItem::SizeBoost => false,
+            ShopItem::Shield => effects.shield,
+        }
+    }
+}
+
+impl ShopButton {
+    fn label(self, effects: &ShopEffects) -> String {
+        match self {
+            ShopButton::Buy(item) if item.is_owned(effects) => format!("{} - owned", item.name()),
+            ShopButton::Buy(item) => format!("{} - {}", item.name(), item.cost()),
+            ShopButton::NextWave => "Next wave".to_string(),
+        }
+    }
+}
+
+fn start_waves(
+    mut commands: Commands,
+    mut coins: ResMut<Coins>,
+    mut effects: ResMut<ShopEffects>,
+    mut wave: ResMut<Wave>,
+    config: Res<GameConfig>,
+) {
+    coins.0 = 0;
+    *effects = ShopEffects::default();
+    *wave = Wave {
+        number: 1,
+        elapsed: 0.,
+    };
+    if config.shop_wave_length.is_none() {
+        return;
+    }
+
+    commands.spawn((
+        CoinCounter,
+        InGameEntity,
+        Text::default(),
+        TextFont {
+            font_size: 20.,
+            ..default()
+        },
+        TextColor(COIN_COLOR),
+        KeepInSafeArea,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(90.),
+            right: Val::Px(20.),
+            ..default()
+        },
+    ));
+}
+
+/// Coins burst out of an eaten ball away from the player, so they have to be
+/// gone after.
+fn drop_coins(
+    mut commands: Commands,
+    mut ball_eaten_reader: EventReader<BallEaten>,
+    player_query: Query<&Transform, With<Player>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let player_position = player_query
+        .get_single()
+        .map_or(Vec2::ZERO, |transform| transform.translation.truncate());
+
+    for BallEaten {
+        number, position, ..
+    } in ball_eaten_reader.read()
+    {
+        let away = (*position - player_position).normalize_or(Vec2::Y);
+        let count = 1 + number / NUMBER_PER_COIN;
+        let mesh = meshes.add(Circle::new(COIN_RADIUS));
+        let material = materials.add(COIN_COLOR);
+        for index in 0..count {
+            let spread = (index as f32 - (count - 1) as f32 / 2.) * 0.4;
+            commands.spawn((
+                Coin {
+                    age: 0.,
+                    velocity: Vec2::from_angle(spread).rotate(away) * COIN_SPEED,
+                },
+                InGameEntity,
+                Mesh2d(mesh.clone()),
+                MeshMaterial2d(material.clone()),
+                Transform::from_translation(RenderLayer::Popups.at(*position)),
+            ));
+        }
+    }
+}
+
+fn move_coins(
+    mut commands: Commands,
+    mut coin_query: Query<(Entity, &mut Coin, &mut Transform)>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, mut coin, mut transform) in coin_query.iter_mut() {
+        coin.age += delta;
+        if coin.age >= COIN_LIFETIME {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        transform.translation += (coin.velocity * delta).extend(0.);
+        coin.velocity *= (1. - COIN_DRAG * delta).max(0.);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn collect_coins(
+    mut commands: Commands,
+    player_query: Query<(&Numbered, &Transform), With<Player>>,
+    coin_query: Query<(Entity, &Transform), (With<Coin>, Without<Player>)>,
+    mut coins: ResMut<Coins>,
+) {
+    let Ok((Numbered(player_number), player_transform)) = player_query.get_single() else {
+        return;
+    };
+    let player_position = player_transform.translation.truncate();
+    let reach = *player_number as f32 * SIZE_FACTOR / 2. + COIN_RADIUS;
+
+    for (entity, transform) in coin_query.iter() {
+        let offset = (transform.translation.truncate() - player_position).abs();
+        if offset.max_element() <= reach {
+            coins.0 += 1;
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn end_wave(
+    config: Res<GameConfig>,
+    mut wave: ResMut<Wave>,
+    mut effects: ResMut<ShopEffects>,
+    time: Res<Time>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+) {
+    let Some(wave_length) = config.shop_wave_length else {
+        return;
+    };
+
+    wave.elapsed += time.delta_secs();
+    if wave.elapsed >= wave_length {
+        wave.elapsed = 0.;
+        effects.slow_spawns = false;
+        next_run_state.set(RunState::Shop);
+    }
+}
+
+fn update_coin_counter(
+    coins: Res<Coins>,
+    wave: Res<Wave>,
+    mut counter_query: Query<&mut Text, With<CoinCounter>>,
+) {
+    if !coins.is_changed() && !wave.is_changed() {
+        return;
+    }
+
+    for mut text in counter_query.iter_mut() {
+        text.0 = format!("wave {} - {} coins", wave.number, coins.0);
+    }
+}
+
+fn pause_physics(mut physics_time: ResMut<Time<Physics>>) {
+    physics_time.pause();
+}
+
+fn resume_physics(mut physics_time: ResMut<Time<Physics>>) {
+    physics_time.unpause();
+}
+
+fn setup_shop_screen(
+    mut commands: Commands,
+    wave: Res<Wave>,
+    coins: Res<Coins>,
+    effects: Res<ShopEffects>,
+) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(12.),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0., 0., 0., 0.5)),
+            StateScoped(RunState::Shop),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new(format!("Wave {} cleared", wave.number)),
+                TextFont {
+                    font_size: 60.,
+                    ..default()
+                },
+            ));
+            builder.spawn((
+                CoinText,
+                Text::new(format!("{} coins", coins.0)),
+                TextFont {
+                    font_size: 24.,
+                    ..default()
+                },
+                TextColor(COIN_COLOR),
+            ));
+            for item in ShopItem::ALL {
+                builder
+                    .spawn(Node {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(12.),
+                        ..default()
+                    })
+                    .with_children(|builder| {
+                        let button = ShopButton::Buy(item);
+                        spawn_menu_button(builder, button, &button.label(&effects));
+                        builder.spawn((
+                            Text::new(item.description()),
+                            TextFont {
+                                font_size: 20.,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                        ));
+                    });
+            }
+            spawn_menu_button(
+                builder,
+                ShopButton::NextWave,
+                &ShopButton::NextWave.label(&effects),
+            );
+            builder.spawn((
+                Text::default(),
+                Prompt(vec![(MenuAction::Continue, "next wave")]),
+                TextFont {
+                    font_size: 20.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn buy_items(
+    button_query: Query<(&Interaction, &ShopButton), Changed<Interaction>>,
+    menu_input: MenuInput,
+    mut coins: ResMut<Coins>,
+    mut effects: ResMut<ShopEffects>,
+    mut wave: ResMut<Wave>,
+    mut player_query: Query<&mut Numbered, With<Player>>,
+    config: Res<GameConfig>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    let clicked = button_query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| *button);
+    let chosen = if menu_input.just_pressed(MenuAction::Continue) {
+        Some(ShopButton::NextWave)
+    } else {
+        clicked
+    };
+
+    let item = match chosen {
+        Some(ShopButton::Buy(item)) => item,
+        Some(ShopButton::NextWave) => {
+            wave.number += 1;
+            next_run_state.set(RunState::Playing);
+            return;
+        }
+        None => return,
+    };
+    if item.is_owned(&effects) {
+        toast_writer.send(ShowToast(format!("{} is already owned", item.name())));
+        return;
+    }
+    if coins.0 < item.cost() {
+        toast_writer.send(ShowToast(format!(
+            "{} needs {} more coins",
+            item.name(),
+            item.cost() - coins.0
+        )));
+        return;
+    }
+
+    coins.0 -= item.cost();
+    match item {
+        ShopItem::SlowSpawns => effects.slow_spawns = true,
+        ShopItem::SizeBoost => {
+            for mut player_number in player_query.iter_mut() {
+                let boosted = player_number.0 + SIZE_BOOST;
+                player_number.0 = config.max_number.map_or(boosted, |max_number| {
+                    boosted.min(max_number.max(player_number.0))
+                });
+            }
+        }
+        ShopItem::Shield => effects.shield = true,
+    }
+}
+
+fn show_shop(
+    coins: Res<Coins>,
+    effects: Res<ShopEffects>,
+    button_query: Query<(&ShopButton, &Children)>,
+    mut coin_text_query: Query<&mut Text, With<CoinText>>,
+    mut text_query: Query<&mut Text, Without<CoinText>>,
+) {
+    if !coins.is_changed() && !effects.is_changed() {
+        return;
+    }
+
+    for mut text in coin_text_query.iter_mut() {
+        text.0 = format!("{} coins", coins.0);
+    }
+    for (button, children) in button_query.iter() {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.0 = button.label(&effects);
+            }
+        }
+    }
+}
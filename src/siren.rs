@@ -0,0 +1,117 @@
+use crate::{
+    Ball, InGameEntity, InGameSet, Numbered, Player,
+    config::GameConfig,
+    spatial::{BallGrid, BallGridSet},
+};
+use bevy::{audio::Volume, prelude::*};
+use std::time::Duration;
+
+const BEEP_INTERVAL: f32 = 1.;
+const BEEP_FREQUENCY: f32 = 110.;
+const EDGE_WIDTH: f32 = 24.;
+
+/// When too many balls bigger than the player are alive at once, or close in
+/// on them with [`GameConfig::siren_radius`] set, tint the screen edges red
+/// and sound a low beep until the crowd thins out.
+pub struct SirenPlugin;
+
+#[derive(Component)]
+struct SirenOverlay {
+    elapsed: f32,
+}
+
+#[derive(Resource)]
+struct SirenTone(Handle<Pitch>);
+
+impl FromWorld for SirenTone {
+    fn from_world(world: &mut World) -> Self {
+        let mut pitches = world.resource_mut::<Assets<Pitch>>();
+        SirenTone(pitches.add(Pitch::new(BEEP_FREQUENCY, Duration::from_millis(300))))
+    }
+}
+
+impl Plugin for SirenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SirenTone>().add_systems(
+            Update,
+            (toggle_siren, pulse_siren)
+                .chain()
+                .after(BallGridSet)
+                .in_set(InGameSet),
+        );
+    }
+}
+
+fn toggle_siren(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    grid: Res<BallGrid>,
+    player_query: Query<(&Transform, &Numbered), With<Player>>,
+    ball_query: Query<&Numbered, (With<Ball>, Without<Player>)>,
+    overlay_query: Query<Entity, With<SirenOverlay>>,
+) {
+    let Ok((player_transform, Numbered(player_number))) = player_query.get_single() else {
+        return;
+    };
+
+    let threats = match config.siren_radius {
+        Some(radius) => grid
+            .within(player_transform.translation.truncate(), radius)
+            .filter(|ball| ball.number > *player_number)
+            .count(),
+        None => ball_query
+            .iter()
+            .filter(|Numbered(number)| number > player_number)
+            .count(),
+    };
+    let overwhelmed = threats > config.siren_threshold;
+
+    match (overwhelmed, overlay_query.get_single()) {
+        (true, Err(_)) => {
+            commands.spawn((
+                SirenOverlay { elapsed: 0. },
+                InGameEntity,
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    border: UiRect::all(Val::Px(EDGE_WIDTH)),
+                    ..default()
+                },
+                BorderColor(Color::srgba(1., 0., 0., 0.5)),
+            ));
+        }
+        (false, Ok(overlay)) => commands.entity(overlay).despawn_recursive(),
+        _ => {}
+    }
+}
+
+fn pulse_siren(
+    mut commands: Commands,
+    mut overlay_query: Query<(&mut SirenOverlay, &mut BorderColor)>,
+    config: Res<GameConfig>,
+    tone: Res<SirenTone>,
+    time: Res<Time>,
+) {
+    for (mut overlay, mut border_color) in overlay_query.iter_mut() {
+        overlay.elapsed += time.delta_secs();
+
+        if config.siren_sound && overlay.elapsed % BEEP_INTERVAL < time.delta_secs() {
+            commands.spawn((
+                AudioPlayer(tone.0.clone()),
+                PlaybackSettings::DESPAWN.with_volume(Volume::new(0.4)),
+                InGameEntity,
+            ));
+        }
+
+        let alpha = if config.reduced_motion {
+            0.5
+        } else {
+            0.3 + 0.3
+                * (overlay.elapsed * std::f32::consts::PI / BEEP_INTERVAL)
+                    .cos()
+                    .abs()
+        };
+        border_color.0 = Color::srgba(1., 0., 0., alpha);
+    }
+}
@@ -0,0 +1,338 @@
+use crate::{
+    GameState, PLAYER_COLOR,
+    achievements::{Achievements, FIRST_EAT, REACH_FIFTY, SURVIVE_FIVE_MINUTES},
+    menu::spawn_menu_button,
+    prompts::{MenuAction, MenuInput, Prompt},
+    storage::Storage,
+    toast::ShowToast,
+    xp::Experience,
+};
+use bevy::prelude::*;
+use std::{fmt, ops::RangeInclusive, str::FromStr};
+
+const SKIN_KEY: &str = "skin";
+
+/// Other looks for the player, each unlocked by a profile level or an
+/// achievement and picked on the cosmetics screen from the main menu. A skin
+/// sets the player's color and the shape their jelly outline rests in; the
+/// collider stays square whatever the player looks like.
+pub struct SkinPlugin;
+
+#[derive(Resource, Clone, Copy, Default, PartialEq)]
+pub enum Skin {
+    #[default]
+    Classic,
+    Mint,
+    Gold,
+    Violet,
+    Bubble,
+    Diamond,
+    Glass,
+}
+
+/// What the player's outline is drawn as, see [`crate::jelly`].
+#[derive(Component, Clone, Copy, Default, PartialEq)]
+pub enum SkinShape {
+    #[default]
+    Square,
+    Round,
+    Diamond,
+}
+
+enum Unlock {
+    Always,
+    Level(u32),
+    /// By its id.
+    Achievement(&'static str),
+}
+
+#[derive(Component, Clone, Copy)]
+enum SkinButton {
+    Choose(Skin),
+    Back,
+}
+
+impl Plugin for SkinPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Skin>()
+            .add_systems(Startup, load_skin)
+            .add_systems(OnEnter(GameState::Cosmetics), setup_cosmetics_screen)
+            .add_systems(
+                Update,
+                (choose_skin, show_skin)
+                    .chain()
+                    .run_if(in_state(GameState::Cosmetics)),
+            );
+    }
+}
+
+impl Skin {
+    const ALL: [Skin; 7] = [
+        Skin::Classic,
+        Skin::Mint,
+        Skin::Gold,
+        Skin::Violet,
+        Skin::Bubble,
+        Skin::Diamond,
+        Skin::Glass,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Skin::Classic => "Classic",
+            Skin::Mint => "Mint",
+            Skin::Gold => "Gold",
+            Skin::Violet => "Violet",
+            Skin::Bubble => "Bubble",
+            Skin::Diamond => "Diamond",
+            Skin::Glass => "Glass",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Skin::Classic => PLAYER_COLOR,
+            Skin::Mint => Color::srgb(0.4, 1., 0.7),
+            Skin::Gold => Color::srgb(1., 0.8, 0.2),
+            Skin::Violet => Color::srgb(0.7, 0.4, 1.),
+            Skin::Bubble => Color::srgb(0.5, 0.8, 1.),
+            Skin::Diamond => Color::srgb(0.85, 0.9, 0.95),
+            Skin::Glass => Color::srgba(1., 1., 1., 0.35),
+        }
+    }
+
+    pub fn shape(self) -> SkinShape {
+        match self {
+            Skin::Bubble => SkinShape::Round,
+            Skin::Diamond => SkinShape::Diamond,
+            _ => SkinShape::Square,
+        }
+    }
+
+    fn unlock(self) -> Unlock {
+        match self {
+            Skin::Classic => Unlock::Always,
+            Skin::Mint => Unlock::Level(2),
+            Skin::Gold => Unlock::Level(5),
+            Skin::Violet => Unlock::Level(10),
+            Skin::Bubble => Unlock::Achievement(FIRST_EAT),
+            Skin::Diamond => Unlock::Achievement(REACH_FIFTY),
+            Skin::Glass => Unlock::Achievement(SURVIVE_FIVE_MINUTES),
+        }
+    }
+
+    fn is_unlocked(self, experience: &Experience, achievements: &Achievements) -> bool {
+        match self.unlock() {
+            Unlock::Always => true,
+            Unlock::Level(level) => experience.level() >= level,
+            Unlock::Achievement(id) => achievements.is_unlocked(id),
+        }
+    }
+
+    /// Like `level 5` or `achievement Stayer`, for locked skins.
+    fn requirement(self, achievements: &Achievements) -> String {
+        match self.unlock() {
+            Unlock::Always => String::new(),
+            Unlock::Level(level) => format!("level {level}"),
+            Unlock::Achievement(id) => format!(
+                "achievement {}",
+                achievements
+                    .get(id)
+                    .map_or(id, |achievement| achievement.name)
+            ),
+        }
+    }
+
+    /// The skins reaching one of `levels` unlocks.
+    pub fn unlocked_by_levels(levels: RangeInclusive<u32>) -> impl Iterator<Item = Skin> {
+        Skin::ALL.into_iter().filter(
+            move |skin| matches!(skin.unlock(), Unlock::Level(level) if levels.contains(&level)),
+        )
+    }
+}
+
+impl fmt::Display for Skin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Skin {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Skin::ALL
+            .into_iter()
+            .find(|skin| skin.name() == s)
+            .ok_or(())
+    }
+}
+
+impl SkinButton {
+    fn label(self, current: Skin, experience: &Experience, achievements: &Achievements) -> String {
+        match self {
+            SkinButton::Choose(skin) if !skin.is_unlocked(experience, achievements) => {
+                format!("locked - {}", skin.requirement(achievements))
+            }
+            SkinButton::Choose(skin) if skin == current => format!("> {} <", skin.name()),
+            SkinButton::Choose(skin) => skin.name().to_string(),
+            SkinButton::Back => "Back".to_string(),
+        }
+    }
+}
+
+fn load_skin(storage: Res<Storage>, mut skin: ResMut<Skin>) {
+    *skin = storage.load(SKIN_KEY).unwrap_or_default();
+}
+
+fn setup_cosmetics_screen(
+    mut commands: Commands,
+    skin: Res<Skin>,
+    experience: Res<Experience>,
+    achievements: Res<Achievements>,
+) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(12.),
+                ..default()
+            },
+            StateScoped(GameState::Cosmetics),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Text::new("Cosmetics"),
+                TextFont {
+                    font_size: 60.,
+                    ..default()
+                },
+            ));
+            builder.spawn((
+                Text::new(format!("level {}", experience.level())),
+                TextFont {
+                    font_size: 20.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+            for button in Skin::ALL
+                .map(SkinButton::Choose)
+                .into_iter()
+                .chain([SkinButton::Back])
+            {
+                builder
+                    .spawn(Node {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(12.),
+                        ..default()
+                    })
+                    .with_children(|builder| {
+                        let swatch = match button {
+                            SkinButton::Choose(skin) => skin.color(),
+                            SkinButton::Back => Color::NONE,
+                        };
+                        builder.spawn((
+                            Node {
+                                width: Val::Px(24.),
+                                height: Val::Px(24.),
+                                ..default()
+                            },
+                            BackgroundColor(swatch),
+                        ));
+                        spawn_menu_button(
+                            builder,
+                            button,
+                            &button.label(*skin, &experience, &achievements),
+                        );
+                    });
+            }
+            builder.spawn((
+                Text::default(),
+                Prompt(vec![
+                    (MenuAction::Cosmetics, "next skin"),
+                    (MenuAction::Quit, "back"),
+                ]),
+                TextFont {
+                    font_size: 20.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+/// Clicking an unlocked skin picks it and goes back to the menu.
+#[allow(clippy::too_many_arguments)]
+fn choose_skin(
+    button_query: Query<(&Interaction, &SkinButton), Changed<Interaction>>,
+    menu_input: MenuInput,
+    experience: Res<Experience>,
+    achievements: Res<Achievements>,
+    mut skin: ResMut<Skin>,
+    mut storage: ResMut<Storage>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    let clicked = button_query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| *button);
+    let unlocked = |skin: &Skin| skin.is_unlocked(&experience, &achievements);
+
+    if menu_input.just_pressed(MenuAction::Cosmetics) {
+        let current = Skin::ALL.iter().position(|other| *other == *skin);
+        let next = Skin::ALL
+            .iter()
+            .cycle()
+            .skip(current.map_or(0, |index| index + 1))
+            .take(Skin::ALL.len())
+            .find(|other| unlocked(other));
+        if let Some(next) = next {
+            *skin = *next;
+        }
+    }
+    match clicked {
+        Some(SkinButton::Choose(chosen)) if !unlocked(&chosen) => {
+            toast_writer.send(ShowToast(format!(
+                "{} unlocks at {}",
+                chosen.name(),
+                chosen.requirement(&achievements)
+            )));
+            return;
+        }
+        Some(SkinButton::Choose(chosen)) => *skin = chosen,
+        _ => {}
+    }
+    if skin.is_changed() {
+        storage.save(SKIN_KEY, *skin);
+    }
+
+    if menu_input.just_pressed(MenuAction::Quit) || clicked.is_some() {
+        next_game_state.set(GameState::Menu);
+    }
+}
+
+fn show_skin(
+    skin: Res<Skin>,
+    experience: Res<Experience>,
+    achievements: Res<Achievements>,
+    button_query: Query<(&SkinButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !skin.is_changed() {
+        return;
+    }
+
+    for (button, children) in button_query.iter() {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.0 = button.label(*skin, &experience, &achievements);
+            }
+        }
+    }
+}
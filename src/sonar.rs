@@ -0,0 +1,98 @@
+use crate::{
+    GameState, InGameEntity, InGameSet, Numbered, Player,
+    config::GameConfig,
+    spatial::{BallGrid, BallGridSet},
+};
+use bevy::{audio::Volume, prelude::*};
+use std::time::Duration;
+
+const SONAR_RADIUS: f32 = 500.;
+const TICK_INTERVAL: std::ops::Range<f32> = 0.25..1.;
+const BASE_FREQUENCY: f32 = 220.;
+const MAX_FREQUENCY: f32 = 1760.;
+/// The listener's ears are this far apart and the tick plays this far from
+/// the listener, so only the direction of the ball carries into the pan.
+const EAR_GAP: f32 = 2.;
+const EMITTER_DISTANCE: f32 = 1.;
+
+/// An optional sonar for low-vision players: a soft tick that comes faster as
+/// the nearest ball bigger than the player gets closer, pitched higher the
+/// bigger it is relative to the player and panned toward it.
+pub struct ThreatSonarPlugin;
+
+#[derive(Resource, Default)]
+struct SonarTick {
+    since_last: f32,
+}
+
+impl Plugin for ThreatSonarPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SonarTick>()
+            .add_systems(OnEnter(GameState::InGame), spawn_sonar_listener)
+            .add_systems(
+                Update,
+                play_sonar
+                    .after(BallGridSet)
+                    .in_set(InGameSet)
+                    .run_if(|config: Res<GameConfig>| config.threat_sonar),
+            );
+    }
+}
+
+fn spawn_sonar_listener(mut commands: Commands, config: Res<GameConfig>) {
+    if config.threat_sonar {
+        commands.spawn((
+            SpatialListener::new(EAR_GAP),
+            Transform::default(),
+            InGameEntity,
+        ));
+    }
+}
+
+fn play_sonar(
+    mut commands: Commands,
+    mut tick: ResMut<SonarTick>,
+    mut pitches: ResMut<Assets<Pitch>>,
+    grid: Res<BallGrid>,
+    player_query: Query<(&Transform, &Numbered), With<Player>>,
+    time: Res<Time>,
+) {
+    let Ok((player_transform, Numbered(player_number))) = player_query.get_single() else {
+        return;
+    };
+    let player_position = player_transform.translation.truncate();
+    tick.since_last += time.delta_secs();
+
+    let nearest_threat = grid
+        .within(player_position, SONAR_RADIUS)
+        .filter(|ball| ball.number > *player_number)
+        .min_by(|a, b| {
+            a.position
+                .distance_squared(player_position)
+                .total_cmp(&b.position.distance_squared(player_position))
+        });
+    let Some(threat) = nearest_threat else {
+        return;
+    };
+
+    let offset = threat.position - player_position;
+    let distance = offset.length() / SONAR_RADIUS;
+    let interval = TICK_INTERVAL.start + (TICK_INTERVAL.end - TICK_INTERVAL.start) * distance;
+    if tick.since_last < interval {
+        return;
+    }
+    tick.since_last = 0.;
+
+    let size_ratio = threat.number as f32 / *player_number as f32;
+    let frequency = (BASE_FREQUENCY * size_ratio).min(MAX_FREQUENCY);
+    let direction = offset.normalize_or_zero();
+
+    commands.spawn((
+        AudioPlayer(pitches.add(Pitch::new(frequency, Duration::from_millis(40)))),
+        PlaybackSettings::DESPAWN
+            .with_spatial(true)
+            .with_volume(Volume::new(0.3)),
+        Transform::from_translation((direction * EMITTER_DISTANCE).extend(0.)),
+        InGameEntity,
+    ));
+}
@@ -0,0 +1,174 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    audio::Volume,
+    prelude::*,
+    utils::HashMap,
+};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Which sound plays for each gameplay event comes from a sound bank: a
+/// `.sounds.ron` asset mapping [`SoundEvent`]s to sound files and how to
+/// play them. The config's `sound_bank` picks the bank, so a sound pack is
+/// just a bank and the files it names. Events a bank leaves out are silent,
+/// except gravity flips, which fall back to their generated tone.
+pub struct SoundBankPlugin;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum SoundEvent {
+    Eat,
+    /// Eating a ball close to the player's own number.
+    BigEat,
+    /// A bigger ball running into the player.
+    Hit,
+    Flip,
+    WallBounce,
+    GameOver,
+}
+
+#[derive(Event)]
+pub struct PlaySound(pub SoundEvent);
+
+#[derive(Asset, TypePath)]
+pub struct SoundBank(HashMap<SoundEvent, Cue>);
+
+/// The bank being played from.
+#[derive(Resource)]
+pub struct GameSounds(pub Handle<SoundBank>);
+
+struct Cue {
+    sound: Handle<AudioSource>,
+    volume: f32,
+    speed: f32,
+}
+
+/// A [`Cue`] as written in a bank.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CueFile {
+    /// Relative to the assets folder.
+    file: String,
+    #[serde(default = "unchanged")]
+    volume: f32,
+    #[serde(default = "unchanged")]
+    speed: f32,
+}
+
+#[derive(Default)]
+struct SoundBankLoader;
+
+impl Plugin for SoundBankPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<SoundBank>()
+            .init_asset_loader::<SoundBankLoader>()
+            .add_event::<PlaySound>()
+            .add_systems(PostUpdate, play_sounds);
+    }
+}
+
+fn unchanged() -> f32 {
+    1.
+}
+
+impl SoundBank {
+    /// Everything wrong with the bank at `path` in the `assets` folder, for
+    /// `--validate`.
+    pub fn problems(assets: &Path, path: &str) -> Vec<String> {
+        let contents = match std::fs::read_to_string(assets.join(path)) {
+            Ok(contents) => contents,
+            Err(error) => return vec![format!("couldn't read it: {error}")],
+        };
+        let cues: HashMap<SoundEvent, CueFile> = match ron::from_str(&contents) {
+            Ok(cues) => cues,
+            Err(error) => return vec![format!("invalid: {error}")],
+        };
+
+        let mut cues: Vec<_> = cues.into_iter().collect();
+        cues.sort_by_key(|(event, _)| format!("{event:?}"));
+        let mut problems = Vec::new();
+        for (event, cue) in cues {
+            if !assets.join(&cue.file).is_file() {
+                problems.push(format!("{event:?} plays {}, which doesn't exist", cue.file));
+            }
+            if cue.volume < 0. {
+                problems.push(format!("{event:?} has a negative volume"));
+            }
+            if cue.speed <= 0. {
+                problems.push(format!("{event:?} has a speed that isn't above 0"));
+            }
+        }
+
+        problems
+    }
+
+    pub fn has(&self, event: SoundEvent) -> bool {
+        self.0.contains_key(&event)
+    }
+
+    /// Every sound the bank plays, for waiting on them to load.
+    pub fn sounds(&self) -> impl Iterator<Item = &Handle<AudioSource>> {
+        self.0.values().map(|cue| &cue.sound)
+    }
+}
+
+impl AssetLoader for SoundBankLoader {
+    type Asset = SoundBank;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<SoundBank, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let cues: HashMap<SoundEvent, CueFile> = ron::de::from_bytes(&bytes)?;
+
+        Ok(SoundBank(
+            cues.into_iter()
+                .map(|(event, cue)| {
+                    let sound = load_context.load(cue.file);
+                    let (volume, speed) = (cue.volume, cue.speed);
+                    (
+                        event,
+                        Cue {
+                            sound,
+                            volume,
+                            speed,
+                        },
+                    )
+                })
+                .collect(),
+        ))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["sounds.ron"]
+    }
+}
+
+fn play_sounds(
+    mut commands: Commands,
+    mut play_reader: EventReader<PlaySound>,
+    sounds: Option<Res<GameSounds>>,
+    banks: Res<Assets<SoundBank>>,
+) {
+    let Some(bank) = sounds.and_then(|sounds| banks.get(&sounds.0)) else {
+        play_reader.clear();
+        return;
+    };
+
+    for PlaySound(event) in play_reader.read() {
+        let Some(cue) = bank.0.get(event) else {
+            continue;
+        };
+        commands.spawn((
+            AudioPlayer(cue.sound.clone()),
+            PlaybackSettings::DESPAWN
+                .with_volume(Volume::new(cue.volume))
+                .with_speed(cue.speed),
+        ));
+    }
+}
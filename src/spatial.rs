@@ -0,0 +1,116 @@
+use crate::{Ball, InGameSet, Numbered, Player};
+use bevy::{prelude::*, utils::HashMap};
+
+const CELL_SIZE: f32 = 100.;
+const DEBUG_RADIUS: f32 = 250.;
+
+/// Buckets every live ball into a uniform grid once per frame, so features
+/// asking "which balls are near this point" don't each scan every ball.
+pub struct SpatialGridPlugin;
+
+/// Systems that read [`BallGrid`] should run after this set.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BallGridSet;
+
+#[derive(Clone, Copy)]
+pub struct GridBall {
+    pub position: Vec2,
+    pub number: i32,
+}
+
+#[derive(Resource, Default)]
+pub struct BallGrid {
+    cells: HashMap<IVec2, Vec<GridBall>>,
+}
+
+#[derive(Resource, Default)]
+struct ProximityOverlay(bool);
+
+impl Plugin for SpatialGridPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BallGrid>()
+            .init_resource::<ProximityOverlay>()
+            .configure_sets(Update, BallGridSet.in_set(InGameSet))
+            .add_systems(Update, rebuild_ball_grid.in_set(BallGridSet))
+            .add_systems(
+                Update,
+                (toggle_proximity_overlay, draw_proximity_overlay)
+                    .chain()
+                    .after(BallGridSet)
+                    .in_set(InGameSet),
+            );
+    }
+}
+
+impl BallGrid {
+    fn cell(point: Vec2) -> IVec2 {
+        (point / CELL_SIZE).floor().as_ivec2()
+    }
+
+    /// Every ball whose center is within `radius` of `point`.
+    pub fn within(&self, point: Vec2, radius: f32) -> impl Iterator<Item = &GridBall> {
+        let min = Self::cell(point - Vec2::splat(radius));
+        let max = Self::cell(point + Vec2::splat(radius));
+
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .filter(move |ball| ball.position.distance_squared(point) <= radius * radius)
+    }
+}
+
+fn rebuild_ball_grid(
+    mut grid: ResMut<BallGrid>,
+    ball_query: Query<(&Transform, &Numbered), With<Ball>>,
+) {
+    for cell in grid.cells.values_mut() {
+        cell.clear();
+    }
+
+    for (transform, Numbered(number)) in ball_query.iter() {
+        let position = transform.translation.truncate();
+        grid.cells
+            .entry(BallGrid::cell(position))
+            .or_default()
+            .push(GridBall {
+                position,
+                number: *number,
+            });
+    }
+}
+
+fn toggle_proximity_overlay(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<ProximityOverlay>,
+) {
+    if keys.just_pressed(KeyCode::F3) {
+        overlay.0 = !overlay.0;
+    }
+}
+
+fn draw_proximity_overlay(
+    overlay: Res<ProximityOverlay>,
+    grid: Res<BallGrid>,
+    player_query: Query<(&Transform, &Numbered), With<Player>>,
+    mut gizmos: Gizmos,
+) {
+    let Ok((player_transform, Numbered(player_number))) = player_query.get_single() else {
+        return;
+    };
+    if !overlay.0 {
+        return;
+    }
+
+    let player_position = player_transform.translation.truncate();
+    gizmos.circle_2d(player_position, DEBUG_RADIUS, Color::WHITE);
+
+    for ball in grid.within(player_position, DEBUG_RADIUS) {
+        let color = if ball.number > *player_number {
+            Color::srgb(1., 0.3, 0.3)
+        } else {
+            Color::srgb(0.3, 1., 0.3)
+        };
+        gizmos.line_2d(player_position, ball.position, color);
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{
+    GameState, InGameEntity, InGameSet, MovementAction, Numbered, Player, RunState,
+    config::GameConfig, gravity::GravityFlip, safe_area::KeepInSafeArea,
+};
+use bevy::{prelude::*, utils::Instant};
+use std::time::Duration;
+
+/// Numbers that get a split time when first reached.
+const SPLITS: [i32; 3] = [25, 50, 75];
+
+/// With `speedrun_timer` on in the config, a timer at the top of the screen
+/// starts on the run's first input and stops on reaching the victory number,
+/// with a split at each of [`SPLITS`]. It goes by the wall clock rather than
+/// the frame time, so dropped or slowed frames don't skew it, and leaves out
+/// time spent paused, in photo mode, at the shop or frozen for a bug report.
+pub struct SpeedrunPlugin;
+
+#[derive(Resource, Default)]
+struct SpeedrunTimer {
+    started: Option<Instant>,
+    /// Taken off the time, from every pause so far.
+    paused_for: Duration,
+    paused_at: Option<Instant>,
+    splits: Vec<(i32, Duration)>,
+    finished: Option<Duration>,
+}
+
+#[derive(Component)]
+struct SpeedrunDisplay;
+
+impl Plugin for SpeedrunPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpeedrunTimer>()
+            .add_systems(OnEnter(GameState::InGame), start_speedrun)
+            .add_systems(
+                Update,
+                stop_speedrun_while_stopped.run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                (start_on_first_input, take_splits)
+                    .chain()
+                    .in_set(InGameSet)
+                    .run_if(speedrun_timer_on),
+            )
+            .add_systems(
+                Update,
+                show_speedrun_timer
+                    .after(take_splits)
+                    .run_if(in_state(GameState::InGame).and(speedrun_timer_on)),
+            );
+    }
+}
+
+fn speedrun_timer_on(config: Res<GameConfig>) -> bool {
+    config.speedrun_timer
+}
+
+impl SpeedrunTimer {
+    fn elapsed(&self) -> Duration {
+        if let Some(finished) = self.finished {
+            return finished;
+        }
+        let Some(started) = self.started else {
+            return Duration::ZERO;
+        };
+
+        let until = self.paused_at.unwrap_or_else(Instant::now);
+        until
+            .duration_since(started)
+            .saturating_sub(self.paused_for)
+    }
+}
+
+/// Like `1:02.345`.
+fn format_time(time: Duration) -> String {
+    let millis = time.as_millis();
+    format!(
+        "{}:{:02}.{:03}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+fn start_speedrun(
+    mut commands: Commands,
+    mut timer: ResMut<SpeedrunTimer>,
+    config: Res<GameConfig>,
+) {
+    *timer = SpeedrunTimer::default();
+    if !config.speedrun_timer {
+        return;
+    }
+
+    commands.spawn((
+        SpeedrunDisplay,
+        InGameEntity,
+        Text::default(),
+        TextFont {
+            font_size: 30.,
+            ..default()
+        },
+        KeepInSafeArea,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.),
+            width: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+    ));
+}
+
+/// Photo mode is entered from the pause menu and goes back to it, so the
+/// clock goes by whether the run is stopped at all rather than by entering
+/// and leaving any one state.
+fn stop_speedrun_while_stopped(
+    mut timer: ResMut<SpeedrunTimer>,
+    run_state: Res<State<RunState>>,
+    virtual_time: Res<Time<Virtual>>,
+) {
+    let stopped = matches!(
+        run_state.get(),
+        RunState::Paused | RunState::Photo | RunState::Shop
+    ) || virtual_time.is_paused();
+
+    if stopped {
+        if timer.started.is_some() && timer.paused_at.is_none() {
+            timer.paused_at = Some(Instant::now());
+        }
+    } else if let Some(paused_at) = timer.paused_at.take() {
+        timer.paused_for += paused_at.elapsed();
+    }
+}
+
+fn start_on_first_input(
+    mut timer: ResMut<SpeedrunTimer>,
+    mut movement_event_reader: EventReader<MovementAction>,
+    mut flip_reader: EventReader<GravityFlip>,
+) {
+    let had_input = movement_event_reader.read().count() > 0 || flip_reader.read().count() > 0;
+    if timer.started.is_none() && had_input {
+        timer.started = Some(Instant::now());
+    }
+}
+
+fn take_splits(
+    mut timer: ResMut<SpeedrunTimer>,
+    player_query: Query<&Numbered, (With<Player>, Changed<Numbered>)>,
+    config: Res<GameConfig>,
+) {
+    let Ok(Numbered(player_number)) = player_query.get_single() else {
+        return;
+    };
+    if timer.started.is_none() || timer.finished.is_some() {
+        return;
+    }
+
+    let elapsed = timer.elapsed();
+    for split in SPLITS {
+        let taken = timer.splits.iter().any(|(number, _)| *number == split);
+        if *player_number >= split && !taken {
+            timer.splits.push((split, elapsed));
+        }
+    }
+    if config
+        .victory_number
+        .is_some_and(|victory_number| *player_number >= victory_number)
+    {
+        timer.finished = Some(elapsed);
+    }
+}
+
+fn show_speedrun_timer(
+    timer: Res<SpeedrunTimer>,
+    mut display_query: Query<&mut Text, With<SpeedrunDisplay>>,
+) {
+    let mut lines = vec![format_time(timer.elapsed())];
+    for (number, time) in &timer.splits {
+        lines.push(format!("{number} - {}", format_time(*time)));
+    }
+
+    for mut text in display_query.iter_mut() {
+        text.0 = lines.join("\n");
+    }
+}
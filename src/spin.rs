@@ -0,0 +1,114 @@
+use crate::{Ball, GameRng, InGameSet, config::GameConfig, shedding::Fragment};
+use avian2d::prelude::*;
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+use rand::Rng;
+
+/// In rad/s, either way.
+const MAX_SPIN: f32 = 6.;
+/// How sharply a ball's path bends for how fast it spins.
+const MAGNUS_STRENGTH: f32 = 0.04;
+/// Spin dies down, so no ball curves by much more than a radian in all and
+/// none ends up circling the arena forever.
+const SPIN_DECAY: f32 = 0.2;
+const STRIPE_TEXTURE_SIZE: u32 = 32;
+const STRIPE_WIDTH: u32 = 8;
+
+/// With `ball_spin` on, every ball spawns spinning and the spin curves its
+/// path, like a ball struck with side spin. Spinning balls are striped so
+/// which way they're turning, and so which way they'll curve, can be read.
+pub struct BallSpinPlugin;
+
+#[derive(Resource)]
+struct StripeTexture(Handle<Image>);
+
+impl Plugin for BallSpinPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, create_stripe_texture)
+            .add_systems(Update, spin_new_balls.run_if(spin_enabled))
+            .add_systems(FixedUpdate, curve_spinning_balls.in_set(InGameSet));
+    }
+}
+
+fn spin_enabled(config: Res<GameConfig>) -> bool {
+    config.ball_spin
+}
+
+fn create_stripe_texture(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let light = Color::WHITE.to_srgba().to_u8_array();
+    let dark = Color::srgb(0.65, 0.65, 0.65).to_srgba().to_u8_array();
+    let data = (0..STRIPE_TEXTURE_SIZE * STRIPE_TEXTURE_SIZE)
+        .flat_map(|index| {
+            let y = index / STRIPE_TEXTURE_SIZE;
+            if (y / STRIPE_WIDTH).is_multiple_of(2) {
+                light
+            } else {
+                dark
+            }
+        })
+        .collect();
+
+    let image = Image::new(
+        Extent3d {
+            width: STRIPE_TEXTURE_SIZE,
+            height: STRIPE_TEXTURE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    commands.insert_resource(StripeTexture(images.add(image)));
+}
+
+/// Fragments are left alone, they don't last long enough to curve. Balls
+/// that already spin, like ones from a continued run, keep their spin.
+#[allow(clippy::type_complexity)]
+fn spin_new_balls(
+    mut commands: Commands,
+    new_ball_query: Query<
+        (
+            Entity,
+            &MeshMaterial2d<ColorMaterial>,
+            Option<&AngularVelocity>,
+        ),
+        (Added<Ball>, Without<Fragment>),
+    >,
+    stripe_texture: Res<StripeTexture>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    for (ball, material, spin) in new_ball_query.iter() {
+        if spin.is_none_or(|spin| spin.0 == 0.) {
+            let spin = game_rng.rng.random_range(-MAX_SPIN..MAX_SPIN);
+            commands.entity(ball).insert(AngularVelocity(spin));
+        }
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.texture = Some(stripe_texture.0.clone());
+        }
+    }
+}
+
+/// Avian doesn't push kinematic balls around, so the Magnus effect turns
+/// their velocity by hand, towards the side they're spinning to.
+fn curve_spinning_balls(
+    mut ball_query: Query<(&mut AngularVelocity, &mut LinearVelocity), With<Ball>>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+    for (mut spin, mut velocity) in ball_query.iter_mut() {
+        if spin.0 == 0. {
+            continue;
+        }
+
+        velocity.0 = Vec2::from_angle(MAGNUS_STRENGTH * spin.0 * delta).rotate(velocity.0);
+        spin.0 *= 1. / (1. + delta * SPIN_DECAY);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{
+    BallEaten, GameRng, GameState, InGameSet, Numbered, Player, WallHit, config::GameConfig,
+    difficulty::Difficulty, gravity::GravityFlip, modes::GameModes, new_game_plus::NewGamePlus,
+    seed_run, weekly::WeeklyPlaylist,
+};
+use bevy::prelude::*;
+
+const SUMMARY_LOG_LINES: usize = 8;
+
+/// What happened during the current (or most recent) run, for the screens
+/// shown when it ends: running totals in [`RunStats`], and in [`RunLog`]
+/// every modifier and event that changed the rules along the way, so scores
+/// from differently-modified runs can be told apart.
+pub struct RunStatsPlugin;
+
+/// Something that changed how the current run plays, to be listed in the
+/// [`RunLog`].
+#[derive(Event)]
+pub struct RunModifier(pub String);
+
+pub struct RunLogEntry {
+    pub time: f32,
+    pub text: String,
+}
+
+#[derive(Resource, Default)]
+pub struct RunLog(pub Vec<RunLogEntry>);
+
+#[derive(Resource, Default)]
+pub struct RunStats {
+    pub duration: f32,
+    pub balls_eaten: u32,
+    pub largest_ball_eaten: i32,
+    pub peak_number: i32,
+    pub final_number: i32,
+    pub gravity_flips: u32,
+    pub wall_bounces: u32,
+    /// A bounce lasts as many ticks as the player stays against the wall, so
+    /// only the first one counts.
+    touching_wall: bool,
+}
+
+impl Plugin for RunStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .init_resource::<RunLog>()
+            .add_event::<RunModifier>()
+            .add_systems(OnEnter(GameState::InGame), reset_run_stats.after(seed_run))
+            .add_systems(
+                FixedUpdate,
+                (track_run_stats, log_run_modifiers).in_set(InGameSet),
+            );
+    }
+}
+
+impl RunStats {
+    /// The totals as lines for an end-of-run screen.
+    pub fn breakdown(&self) -> Vec<String> {
+        vec![
+            format!("survived - {:.1}s", self.duration),
+            format!("balls eaten - {}", self.balls_eaten),
+            format!("largest ball eaten - {}", self.largest_ball_eaten),
+            format!("peak size - {}", self.peak_number),
+            format!("gravity flips - {}", self.gravity_flips),
+            format!("wall bounces - {}", self.wall_bounces),
+        ]
+    }
+}
+
+impl RunLog {
+    /// The log as lines for an end-of-run screen, newest last, with older
+    /// entries summarized once there are too many to list.
+    pub fn summary_lines(&self) -> Vec<String> {
+        let hidden = self.0.len().saturating_sub(SUMMARY_LOG_LINES);
+        let mut lines = Vec::new();
+
+        if hidden > 0 {
+            lines.push(format!("... {hidden} earlier"));
+        }
+        for entry in &self.0[hidden..] {
+            let minutes = (entry.time / 60.) as u32;
+            let seconds = entry.time as u32 % 60;
+            lines.push(format!("{minutes}:{seconds:02} {}", entry.text));
+        }
+
+        lines
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn reset_run_stats(
+    mut stats: ResMut<RunStats>,
+    mut log: ResMut<RunLog>,
+    config: Res<GameConfig>,
+    new_game_plus: Res<NewGamePlus>,
+    weekly_playlist: Res<WeeklyPlaylist>,
+    difficulty: Res<Difficulty>,
+    modes: Res<GameModes>,
+    game_rng: Res<GameRng>,
+) {
+    *stats = RunStats::default();
+    log.0.clear();
+
+    let mut log_at_start = |text: String| log.0.push(RunLogEntry { time: 0., text });
+    log_at_start(format!("arena {:?}", config.arena));
+    if *difficulty != Difficulty::Normal {
+        log_at_start(format!("difficulty {}", *difficulty));
+    }
+    if modes.selected().name() != "Classic" {
+        log_at_start(format!("mode {}", modes.selected().name()));
+    }
+    match new_game_plus.loop_count {
+        0 => {}
+        1 => log_at_start("New Game+".to_string()),
+        loop_count => log_at_start(format!("New Game+ loop {loop_count}")),
+    }
+    if weekly_playlist.active {
+        log_at_start(format!("weekly {}", weekly_playlist.name()));
+    }
+    log_at_start(format!("seed {}", game_rng.seed));
+}
+
+fn log_run_modifiers(
+    mut modifier_reader: EventReader<RunModifier>,
+    mut log: ResMut<RunLog>,
+    stats: Res<RunStats>,
+) {
+    for RunModifier(text) in modifier_reader.read() {
+        log.0.push(RunLogEntry {
+            time: stats.duration,
+            text: text.clone(),
+        });
+    }
+}
+
+fn track_run_stats(
+    mut stats: ResMut<RunStats>,
+    mut ball_eaten_reader: EventReader<BallEaten>,
+    mut flip_reader: EventReader<GravityFlip>,
+    mut wall_hit_reader: EventReader<WallHit>,
+    player_query: Query<&Numbered, With<Player>>,
+    time: Res<Time>,
+) {
+    stats.duration += time.delta_secs();
+    for Numbered(player_number) in player_query.iter() {
+        stats.peak_number = stats.peak_number.max(*player_number);
+        stats.final_number = *player_number;
+    }
+
+    for BallEaten { number, .. } in ball_eaten_reader.read() {
+        stats.balls_eaten += 1;
+        stats.largest_ball_eaten = stats.largest_ball_eaten.max(*number);
+    }
+
+    stats.gravity_flips += flip_reader.read().filter(|flip| !flip.blocked).count() as u32;
+
+    let touching_wall = wall_hit_reader.read().count() > 0;
+    if touching_wall && !stats.touching_wall {
+        stats.wall_bounces += 1;
+    }
+    stats.touching_wall = touching_wall;
+}
@@ -0,0 +1,256 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::io;
+use std::str::FromStr;
+
+const VERSION_TAG: &str = "version ";
+
+/// Somewhere to keep small pieces of persistent data (high scores, settings,
+/// profiles) as strings under a key, independent of the platform.
+pub trait StorageBackend: Send + Sync {
+    fn load(&self, key: &str) -> Option<String>;
+
+    fn save(&mut self, key: &str, value: &str) -> io::Result<()>;
+}
+
+#[derive(Resource)]
+pub struct Storage(pub Box<dyn StorageBackend>);
+
+/// A format saved with [`Storage::save_versioned`], so changing it doesn't
+/// throw away what players already have saved. Saves are tagged with the
+/// version they were written in and brought up to date one version at a time
+/// when they're loaded.
+pub trait Versioned: FromStr + ToString {
+    /// Goes up on every change to the format, with a matching step in
+    /// [`Versioned::migrate`].
+    const VERSION: u32;
+
+    /// Rewrites `saved` from `version` into `version + 1`, or `None` if it
+    /// can't be. Version 0 is anything saved before versions were tagged.
+    fn migrate(version: u32, saved: &str) -> Option<String>;
+}
+
+impl Storage {
+    /// Picks the backend for the current platform, keeping `profile`'s data
+    /// apart from everyone else's. Without a profile it's the data shared
+    /// between profiles, which is also where the default profile lives.
+    /// Setting `SHAPE_EATER_IN_MEMORY_STORAGE` forces the in-memory backend
+    /// so CI runs never touch the real save data.
+    pub fn for_platform(profile: Option<&str>) -> Self {
+        if std::env::var_os("SHAPE_EATER_IN_MEMORY_STORAGE").is_some() {
+            return Storage::in_memory();
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        return Storage(Box::new(LocalStorage::for_profile(profile)));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        Storage(Box::new(FileStorage::in_data_dir(profile)))
+    }
+
+    /// Starts out empty and is gone when the game closes.
+    pub fn in_memory() -> Self {
+        Storage(Box::new(MemoryStorage::default()))
+    }
+
+    pub fn load<T: std::str::FromStr>(&self, key: &str) -> Option<T> {
+        self.0.load(key)?.trim().parse().ok()
+    }
+
+    pub fn save(&mut self, key: &str, value: impl ToString) {
+        if let Err(error) = self.0.save(key, &value.to_string()) {
+            warn!("failed to save {key}: {error}");
+        }
+    }
+
+    pub fn load_versioned<T: Versioned>(&self, key: &str) -> Option<T> {
+        let saved = self.0.load(key)?;
+        let (mut version, body) = split_version(&saved);
+        if version > T::VERSION {
+            warn!("{key} was saved by a newer version of the game, ignoring it");
+            return None;
+        }
+
+        let mut body = body.to_string();
+        while version < T::VERSION {
+            let Some(migrated) = T::migrate(version, &body) else {
+                warn!("couldn't migrate {key} from version {version}, ignoring it");
+                return None;
+            };
+            body = migrated;
+            version += 1;
+        }
+
+        body.trim().parse().ok()
+    }
+
+    pub fn save_versioned<T: Versioned>(&mut self, key: &str, value: &T) {
+        self.save(
+            key,
+            format!("{VERSION_TAG}{}\n{}", T::VERSION, value.to_string()),
+        );
+    }
+}
+
+/// The version a save was tagged with, and the rest of it.
+fn split_version(saved: &str) -> (u32, &str) {
+    saved
+        .split_once('\n')
+        .and_then(|(tag, body)| Some((tag.strip_prefix(VERSION_TAG)?.parse().ok()?, body)))
+        .unwrap_or((0, saved))
+}
+
+#[derive(Default)]
+pub struct MemoryStorage(HashMap<String, String>);
+
+impl StorageBackend for MemoryStorage {
+    fn load(&self, key: &str) -> Option<String> {
+        self.0.get(key).cloned()
+    }
+
+    fn save(&mut self, key: &str, value: &str) -> io::Result<()> {
+        self.0.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStorage {
+    root: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStorage {
+    fn in_data_dir(profile: Option<&str>) -> Self {
+        let data_dir = dirs::data_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
+        Self::in_dir(data_dir.join("shape-eater"), profile)
+    }
+
+    /// Named profiles keep their data under `profile_data/`, clear of the
+    /// files the shared data is saved in.
+    pub fn in_dir(root: std::path::PathBuf, profile: Option<&str>) -> Self {
+        let root = match profile {
+            Some(profile) => root.join("profile_data").join(profile),
+            None => root,
+        };
+
+        FileStorage { root }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl StorageBackend for FileStorage {
+    fn load(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.root.join(key)).ok()
+    }
+
+    fn save(&mut self, key: &str, value: &str) -> io::Result<()> {
+        std::fs::create_dir_all(&self.root)?;
+        std::fs::write(self.root.join(key), value)
+    }
+}
+
+/// Keys are prefixed rather than kept in files, like
+/// `shape-eater/profiles/<profile>/<key>`.
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage {
+    prefix: String,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    fn for_profile(profile: Option<&str>) -> Self {
+        let prefix = match profile {
+            Some(profile) => format!("shape-eater/profiles/{profile}/"),
+            None => "shape-eater/".to_string(),
+        };
+        LocalStorage { prefix }
+    }
+
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl StorageBackend for LocalStorage {
+    fn load(&self, key: &str) -> Option<String> {
+        Self::storage()?
+            .get_item(&format!("{}{key}", self.prefix))
+            .ok()?
+    }
+
+    fn save(&mut self, key: &str, value: &str) -> io::Result<()> {
+        let storage =
+            Self::storage().ok_or_else(|| io::Error::other("localStorage is unavailable"))?;
+
+        storage
+            .set_item(&format!("{}{key}", self.prefix), value)
+            .map_err(|_| io::Error::other("localStorage rejected the write"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts how many times it's been migrated, one `+` per step.
+    struct Steps(String);
+
+    impl FromStr for Steps {
+        type Err = ();
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(Steps(s.to_string()))
+        }
+    }
+
+    impl std::fmt::Display for Steps {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+
+    impl Versioned for Steps {
+        const VERSION: u32 = 2;
+
+        fn migrate(_version: u32, saved: &str) -> Option<String> {
+            Some(format!("{saved}+"))
+        }
+    }
+
+    #[test]
+    fn untagged_saves_migrate_from_version_0() {
+        let mut storage = Storage::in_memory();
+        storage.save("steps", "old");
+
+        let loaded: Steps = storage.load_versioned("steps").unwrap();
+        assert_eq!(loaded.0, "old++");
+    }
+
+    #[test]
+    fn tagged_saves_migrate_from_their_version() {
+        let mut storage = Storage::in_memory();
+        storage.save("steps", "version 1\nold");
+
+        let loaded: Steps = storage.load_versioned("steps").unwrap();
+        assert_eq!(loaded.0, "old+");
+    }
+
+    #[test]
+    fn current_saves_round_trip() {
+        let mut storage = Storage::in_memory();
+        storage.save_versioned("steps", &Steps("new".to_string()));
+
+        let loaded: Steps = storage.load_versioned("steps").unwrap();
+        assert_eq!(loaded.0, "new");
+    }
+
+    #[test]
+    fn saves_from_newer_versions_are_ignored() {
+        let mut storage = Storage::in_memory();
+        storage.save("steps", "version 3\nfuture");
+
+        assert!(storage.load_versioned::<Steps>("steps").is_none());
+    }
+}
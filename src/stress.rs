@@ -0,0 +1,92 @@
+use crate::{Bound, GameRng, GameState, seed_run, spawn_numbered_ball};
+use bevy::{prelude::*, utils::Instant};
+use rand::prelude::*;
+use std::time::Duration;
+
+/// Floods the arena with this many balls at the start of every run and
+/// times each frame of it by the wall clock, for a scenario's `stress_balls`
+/// and `FrameTime` check to hold hit resolution to a budget under load.
+/// Scenario frames are a fixed length of game time, so only the wall clock
+/// says how long one actually took.
+pub struct StressTestPlugin(pub usize);
+
+#[derive(Resource)]
+struct StressBallCount(usize);
+
+/// How long the frames of the current run have taken by the wall clock.
+#[derive(Resource, Default)]
+pub struct FrameTimes {
+    frames: u32,
+    total: Duration,
+    worst: Duration,
+    last_frame: Option<Instant>,
+}
+
+impl Plugin for StressTestPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(StressBallCount(self.0))
+            .init_resource::<FrameTimes>()
+            .add_systems(
+                OnEnter(GameState::InGame),
+                (reset_frame_times, spawn_stress_balls.after(seed_run)),
+            )
+            .add_systems(First, time_frames.run_if(in_state(GameState::InGame)));
+    }
+}
+
+impl FrameTimes {
+    /// In milliseconds.
+    pub fn mean(&self) -> f32 {
+        if self.frames == 0 {
+            return 0.;
+        }
+        self.total.as_secs_f32() * 1000. / self.frames as f32
+    }
+
+    /// In milliseconds.
+    pub fn worst(&self) -> f32 {
+        self.worst.as_secs_f32() * 1000.
+    }
+}
+
+fn reset_frame_times(mut frame_times: ResMut<FrameTimes>) {
+    *frame_times = FrameTimes::default();
+}
+
+fn time_frames(mut frame_times: ResMut<FrameTimes>) {
+    let now = Instant::now();
+    if let Some(last_frame) = frame_times.last_frame {
+        let frame = now - last_frame;
+        frame_times.frames += 1;
+        frame_times.total += frame;
+        frame_times.worst = frame_times.worst.max(frame);
+    }
+    frame_times.last_frame = Some(now);
+}
+
+fn spawn_stress_balls(
+    count: Res<StressBallCount>,
+    mut game_rng: ResMut<GameRng>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let rng = &mut game_rng.rng;
+
+    for _ in 0..count.0 {
+        let position = Vec2::new(
+            rng.random_range(Bound::LeftBound.value()..Bound::RightBound.value()),
+            rng.random_range(Bound::LowerBound.value()..Bound::UpperBound.value()),
+        );
+        let direction = Vec2::from_angle(rng.random_range(0.0..std::f32::consts::TAU));
+
+        spawn_numbered_ball(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            rng.random_range(1..100),
+            position,
+            direction * 100.,
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{GameState, InGameEntity, InGameSet, arena::ArenaLayout, layers::RenderLayer};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+const CONVEYOR_GRIP: f32 = 8.;
+const ARROW_SPACING: f32 = 60.;
+const ARROW_SIZE: f32 = 12.;
+
+/// What a wall is made of. Besides changing how things bounce off it, every
+/// surface has its own color so players can read an arena at a glance.
+#[derive(Clone, Copy, Default, Deserialize)]
+pub enum Surface {
+    #[default]
+    Standard,
+    Bouncy,
+    Dampening,
+    Sticky,
+    /// Drags anything touching it along the wall at this speed. Positive
+    /// values move right along horizontal walls and up along vertical ones.
+    Conveyor(f32),
+}
+
+/// Applies conveyor drag to dynamic bodies touching a [`Surface::Conveyor`]
+/// wall or overlapping one of the layout's conveyor zones.
+pub struct SurfacePlugin;
+
+#[derive(Component)]
+pub struct Conveyor {
+    pub velocity: Vec2,
+}
+
+/// One of the arrows scrolling along a conveyor zone, in the zone's local
+/// space, wrapping around at the zone's ends.
+#[derive(Component)]
+struct ConveyorArrow {
+    half_length: f32,
+    velocity: Vec2,
+}
+
+impl Plugin for SurfacePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::InGame), spawn_conveyor_zones)
+            .add_systems(FixedUpdate, drag_along_conveyors.in_set(InGameSet))
+            .add_systems(Update, scroll_conveyor_arrows.in_set(InGameSet));
+    }
+}
+
+impl Surface {
+    pub fn color(&self) -> Color {
+        match self {
+            Surface::Standard => Color::srgb(0.0, 0.4, 0.7),
+            Surface::Bouncy => Color::srgb(0.9, 0.3, 0.8),
+            Surface::Dampening => Color::srgb(0.4, 0.4, 0.45),
+            Surface::Sticky => Color::srgb(0.5, 0.8, 0.1),
+            Surface::Conveyor(_) => Color::srgb(0.9, 0.6, 0.1),
+        }
+    }
+
+    pub fn restitution(&self) -> Restitution {
+        match self {
+            Surface::Standard | Surface::Conveyor(_) => Restitution::PERFECTLY_ELASTIC,
+            Surface::Bouncy => Restitution::new(1.15).with_combine_rule(CoefficientCombine::Max),
+            Surface::Dampening => Restitution::new(0.1).with_combine_rule(CoefficientCombine::Min),
+            Surface::Sticky => Restitution::ZERO.with_combine_rule(CoefficientCombine::Min),
+        }
+    }
+
+    pub fn friction(&self) -> Friction {
+        match self {
+            Surface::Sticky => Friction::new(2.).with_combine_rule(CoefficientCombine::Max),
+            _ => Friction::default(),
+        }
+    }
+
+    /// The conveyor component for a wall of this surface and size, whose
+    /// drag runs along the wall's long side.
+    pub fn conveyor(&self, wall_size: Vec2) -> Option<Conveyor> {
+        let Surface::Conveyor(speed) = self else {
+            return None;
+        };
+        let along = if wall_size.x >= wall_size.y {
+            Vec2::X
+        } else {
+            Vec2::Y
+        };
+
+        Some(Conveyor {
+            velocity: along * *speed,
+        })
+    }
+}
+
+fn drag_along_conveyors(
+    conveyor_query: Query<(&Conveyor, &CollidingEntities)>,
+    mut body_query: Query<(&RigidBody, &mut LinearVelocity)>,
+    time: Res<Time>,
+) {
+    let blend = 1. - (-CONVEYOR_GRIP * time.delta_secs()).exp();
+
+    for (conveyor, touching) in conveyor_query.iter() {
+        let direction = conveyor.velocity.normalize_or_zero();
+
+        for entity in touching.iter() {
+            let Ok((RigidBody::Dynamic, mut velocity)) = body_query.get_mut(*entity) else {
+                continue;
+            };
+
+            let along = velocity.dot(direction);
+            let target = conveyor.velocity.dot(direction);
+            velocity.0 += direction * (target - along) * blend;
+        }
+    }
+}
+
+fn spawn_conveyor_zones(
+    mut commands: Commands,
+    layout: Res<ArenaLayout>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let arrow_mesh = meshes.add(Triangle2d::new(
+        Vec2::new(ARROW_SIZE, 0.),
+        Vec2::new(-ARROW_SIZE, ARROW_SIZE),
+        Vec2::new(-ARROW_SIZE, -ARROW_SIZE),
+    ));
+    let arrow_material = materials.add(Surface::Conveyor(0.).color().with_alpha(0.6));
+
+    for zone in &layout.conveyor_zones {
+        let half_length = zone.size.max_element() / 2.;
+        let along = if zone.size.x >= zone.size.y {
+            Vec2::X
+        } else {
+            Vec2::Y
+        };
+        let arrow_rotation = Quat::from_rotation_arc_2d(Vec2::X, zone.velocity.normalize_or(along));
+
+        commands
+            .spawn((
+                Conveyor {
+                    velocity: zone.velocity,
+                },
+                InGameEntity,
+                Sprite {
+                    color: Surface::Conveyor(0.).color().with_alpha(0.15),
+                    custom_size: Some(zone.size),
+                    ..default()
+                },
+                Transform::from_translation(RenderLayer::Zones.at(zone.position)),
+                RigidBody::Static,
+                Collider::rectangle(zone.size.x, zone.size.y),
+                Sensor,
+                CollidingEntities::default(),
+            ))
+            .with_children(|builder| {
+                let arrow_count = (half_length * 2. / ARROW_SPACING) as i32;
+
+                for index in 0..arrow_count {
+                    let offset = -half_length + (index as f32 + 0.5) * ARROW_SPACING;
+
+                    builder.spawn((
+                        ConveyorArrow {
+                            half_length,
+                            velocity: zone.velocity,
+                        },
+                        Mesh2d(arrow_mesh.clone()),
+                        MeshMaterial2d(arrow_material.clone()),
+                        Transform::from_translation((along * offset).extend(0.1))
+                            .with_rotation(arrow_rotation),
+                    ));
+                }
+            });
+    }
+}
+
+fn scroll_conveyor_arrows(
+    mut arrow_query: Query<(&ConveyorArrow, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (arrow, mut transform) in arrow_query.iter_mut() {
+        let mut position = transform.translation.truncate() + arrow.velocity * time.delta_secs();
+        let along = arrow.velocity.normalize_or_zero();
+        let distance = position.dot(along);
+
+        if distance > arrow.half_length {
+            position -= along * arrow.half_length * 2.;
+        } else if distance < -arrow.half_length {
+            position += along * arrow.half_length * 2.;
+        }
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
@@ -0,0 +1,353 @@
+use crate::prompts::{MenuAction, MenuInput};
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+
+const COLUMNS: usize = 10;
+const KEY_SIZE: f32 = 44.;
+const KEY_COLOR: Color = Color::srgb(0.3, 0.3, 0.3);
+const SELECTED_KEY_COLOR: Color = Color::srgb(0.6, 0.6, 0.3);
+/// How far the left stick has to be pushed to move the selection, and come
+/// back before it moves again.
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// An on-screen keyboard for typing short text with a gamepad: a grid of
+/// characters navigated with the d-pad or left stick, where South types the
+/// selected key, East deletes and Start finishes. Typing, the arrow keys and
+/// clicking work on it too. Finishing sends [`TextEntered`], and the entry
+/// despawns itself at the start of the next frame.
+pub struct TextEntryPlugin;
+
+/// Systems reading [`TextEntered`] go after this, while the entry (and the
+/// marker it was spawned with) is still around.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextEntrySet;
+
+#[derive(Component)]
+pub struct TextEntry {
+    charset: &'static str,
+    max_len: usize,
+    text: String,
+    selected: usize,
+    finished: bool,
+}
+
+#[derive(Event)]
+pub struct TextEntered {
+    pub entry: Entity,
+    pub text: String,
+}
+
+#[derive(Component)]
+struct TextEntryValue {
+    entry: Entity,
+}
+
+#[derive(Component)]
+struct TextEntryKey {
+    entry: Entity,
+    index: usize,
+}
+
+#[derive(Clone, Copy)]
+enum EntryKey {
+    Char(char),
+    Delete,
+    Done,
+}
+
+impl Plugin for TextEntryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TextEntered>()
+            .add_systems(PreUpdate, despawn_finished_text_entries)
+            .add_systems(
+                Update,
+                (
+                    navigate_text_entries,
+                    press_text_entry_keys,
+                    show_text_entries,
+                )
+                    .chain()
+                    .in_set(TextEntrySet),
+            );
+    }
+}
+
+impl TextEntry {
+    fn key_count(&self) -> usize {
+        self.charset.chars().count() + 2
+    }
+
+    fn key(&self, index: usize) -> EntryKey {
+        match self.charset.chars().nth(index) {
+            Some(character) => EntryKey::Char(character),
+            None if index == self.key_count() - 2 => EntryKey::Delete,
+            None => EntryKey::Done,
+        }
+    }
+
+    /// Applies `key`, returning whether it finished the entry.
+    fn press(&mut self, key: EntryKey) -> bool {
+        match key {
+            EntryKey::Char(character) => {
+                if self.text.chars().count() < self.max_len {
+                    self.text.push(character);
+                }
+                false
+            }
+            EntryKey::Delete => {
+                self.text.pop();
+                false
+            }
+            EntryKey::Done => true,
+        }
+    }
+}
+
+impl EntryKey {
+    fn label(self) -> String {
+        match self {
+            EntryKey::Char(character) => character.to_string(),
+            EntryKey::Delete => "DEL".to_string(),
+            EntryKey::Done => "OK".to_string(),
+        }
+    }
+}
+
+/// Run condition for screens whose own controls would clash with typing.
+pub fn no_text_entry(entry_query: Query<(), With<TextEntry>>) -> bool {
+    entry_query.is_empty()
+}
+
+/// A text entry accepting up to `max_len` characters from `charset`.
+/// `marker` tells apart whose [`TextEntered`] is whose.
+pub fn spawn_text_entry(
+    builder: &mut ChildBuilder,
+    marker: impl Component,
+    charset: &'static str,
+    max_len: usize,
+) {
+    let text_entry = TextEntry {
+        charset,
+        max_len,
+        text: String::new(),
+        selected: 0,
+        finished: false,
+    };
+    let keys: Vec<EntryKey> = (0..text_entry.key_count())
+        .map(|index| text_entry.key(index))
+        .collect();
+
+    let mut entry = builder.spawn((
+        marker,
+        text_entry,
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(10.),
+            ..default()
+        },
+    ));
+    let entry_id = entry.id();
+
+    entry.with_children(|builder| {
+        builder.spawn((
+            TextEntryValue { entry: entry_id },
+            Text::new("_"),
+            TextFont {
+                font_size: 40.,
+                ..default()
+            },
+        ));
+        builder
+            .spawn(Node {
+                display: Display::Grid,
+                grid_template_columns: RepeatedGridTrack::px(COLUMNS as u16, KEY_SIZE),
+                column_gap: Val::Px(4.),
+                row_gap: Val::Px(4.),
+                ..default()
+            })
+            .with_children(|builder| {
+                for (index, key) in keys.into_iter().enumerate() {
+                    builder
+                        .spawn((
+                            TextEntryKey {
+                                entry: entry_id,
+                                index,
+                            },
+                            Button,
+                            Node {
+                                height: Val::Px(KEY_SIZE),
+                                align_items: AlignItems::Center,
+                                justify_content: JustifyContent::Center,
+                                ..default()
+                            },
+                            BackgroundColor(KEY_COLOR),
+                        ))
+                        .with_children(|builder| {
+                            builder.spawn((
+                                Text::new(key.label()),
+                                TextFont {
+                                    font_size: 20.,
+                                    ..default()
+                                },
+                            ));
+                        });
+                }
+            });
+    });
+}
+
+fn navigate_text_entries(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut stick_pushed: Local<bool>,
+    mut entry_query: Query<&mut TextEntry>,
+) {
+    let stick = gamepads
+        .iter()
+        .map(|gamepad| gamepad.left_stick())
+        .find(|stick| stick.length() >= STICK_THRESHOLD);
+    let stick_step = match stick {
+        Some(stick) if !*stick_pushed => {
+            if stick.x.abs() > stick.y.abs() {
+                IVec2::new(stick.x.signum() as i32, 0)
+            } else {
+                // Up on the stick means up the grid, to a lower row.
+                IVec2::new(0, -stick.y.signum() as i32)
+            }
+        }
+        _ => IVec2::ZERO,
+    };
+    *stick_pushed = stick.is_some();
+
+    let pressed = |key: KeyCode, button: GamepadButton| {
+        keys.just_pressed(key) || gamepads.iter().any(|gamepad| gamepad.just_pressed(button))
+    };
+    let step = stick_step
+        + IVec2::new(
+            pressed(KeyCode::ArrowRight, GamepadButton::DPadRight) as i32
+                - pressed(KeyCode::ArrowLeft, GamepadButton::DPadLeft) as i32,
+            pressed(KeyCode::ArrowDown, GamepadButton::DPadDown) as i32
+                - pressed(KeyCode::ArrowUp, GamepadButton::DPadUp) as i32,
+        );
+    if step == IVec2::ZERO {
+        return;
+    }
+
+    for mut entry in entry_query.iter_mut() {
+        let key_count = entry.key_count() as i32;
+        let moved = entry.selected as i32 + step.x + step.y * COLUMNS as i32;
+        entry.selected = moved.rem_euclid(key_count) as usize;
+    }
+}
+
+fn press_text_entry_keys(
+    menu_input: MenuInput,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut keyboard_reader: EventReader<KeyboardInput>,
+    key_query: Query<(&Interaction, &TextEntryKey), Changed<Interaction>>,
+    mut entry_query: Query<(Entity, &mut TextEntry)>,
+    mut entered_writer: EventWriter<TextEntered>,
+) {
+    let typed: Vec<char> = keyboard_reader
+        .read()
+        .filter(|input| input.state == ButtonState::Pressed)
+        .filter_map(|input| match &input.logical_key {
+            Key::Character(typed) => typed.chars().next(),
+            _ => None,
+        })
+        .collect();
+    let gamepad_pressed =
+        |button: GamepadButton| gamepads.iter().any(|gamepad| gamepad.just_pressed(button));
+    let deleting = keys.just_pressed(KeyCode::Backspace) || gamepad_pressed(GamepadButton::East);
+    let finishing = gamepad_pressed(GamepadButton::Start);
+
+    for (entity, mut entry) in entry_query.iter_mut() {
+        if entry.finished {
+            continue;
+        }
+        let mut done = false;
+
+        for character in &typed {
+            let character = if entry.charset.contains(*character) {
+                *character
+            } else {
+                character.to_ascii_uppercase()
+            };
+            if entry.charset.contains(character) {
+                entry.press(EntryKey::Char(character));
+                // So Enter finishes straight after typing.
+                entry.selected = entry.key_count() - 1;
+            }
+        }
+        if deleting {
+            entry.press(EntryKey::Delete);
+        }
+        if menu_input.just_pressed(MenuAction::Play) {
+            let selected = entry.key(entry.selected);
+            done |= entry.press(selected);
+        }
+        for (interaction, key) in key_query.iter() {
+            if key.entry != entity || *interaction == Interaction::None {
+                continue;
+            }
+            entry.selected = key.index;
+            if *interaction == Interaction::Pressed {
+                let clicked = entry.key(key.index);
+                done |= entry.press(clicked);
+            }
+        }
+
+        if done || finishing {
+            entry.finished = true;
+            entered_writer.send(TextEntered {
+                entry: entity,
+                text: entry.text.clone(),
+            });
+        }
+    }
+}
+
+/// Left until the frame after finishing, so the input that finished an entry
+/// can't also reach whatever [`no_text_entry`] was holding back.
+fn despawn_finished_text_entries(mut commands: Commands, entry_query: Query<(Entity, &TextEntry)>) {
+    for (entity, entry) in entry_query.iter() {
+        if entry.finished {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Key colors are set every frame, since the keys are also buttons and the
+/// menu button highlighting would otherwise leave them the wrong color.
+fn show_text_entries(
+    entry_query: Query<Ref<TextEntry>>,
+    mut value_query: Query<(&TextEntryValue, &mut Text)>,
+    mut key_query: Query<(&TextEntryKey, &mut BackgroundColor)>,
+) {
+    for (value, mut text) in value_query.iter_mut() {
+        if let Some(entry) = entry_query
+            .get(value.entry)
+            .ok()
+            .filter(|entry| entry.is_changed())
+        {
+            text.0 = format!("{}_", entry.text);
+        }
+    }
+
+    for (key, mut background) in key_query.iter_mut() {
+        if let Ok(entry) = entry_query.get(key.entry) {
+            background.set_if_neq(BackgroundColor(if entry.selected == key.index {
+                SELECTED_KEY_COLOR
+            } else {
+                KEY_COLOR
+            }));
+        }
+    }
+}
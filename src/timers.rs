@@ -0,0 +1,133 @@
+use crate::{GameState, RunState};
+use bevy::{prelude::*, time::Stopwatch, utils::HashMap};
+
+/// Named gameplay timers and stopwatches, registered with
+/// [`AppGameTimersExt`]. They're only ticked while a run is being played, so
+/// pausing (or leaving) the run freezes them, and a saved run keeps how far
+/// they got.
+pub struct GameTimersPlugin;
+
+#[derive(Resource, Default)]
+pub struct GameTimers {
+    timers: HashMap<&'static str, Timer>,
+    stopwatches: HashMap<&'static str, Stopwatch>,
+}
+
+pub trait AppGameTimersExt {
+    fn add_game_timer(&mut self, name: &'static str, timer: Timer) -> &mut Self;
+
+    fn add_game_stopwatch(&mut self, name: &'static str) -> &mut Self;
+}
+
+impl Plugin for GameTimersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameTimers>()
+            .add_systems(OnEnter(GameState::InGame), reset_game_timers)
+            .add_systems(
+                FixedPreUpdate,
+                tick_game_timers.run_if(in_state(RunState::Playing)),
+            );
+    }
+}
+
+impl AppGameTimersExt for App {
+    fn add_game_timer(&mut self, name: &'static str, timer: Timer) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<GameTimers>()
+            .timers
+            .insert(name, timer);
+        self
+    }
+
+    fn add_game_stopwatch(&mut self, name: &'static str) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<GameTimers>()
+            .stopwatches
+            .insert(name, Stopwatch::new());
+        self
+    }
+}
+
+impl GameTimers {
+    /// Whether the named timer finished during the latest tick. Unknown names
+    /// never finish.
+    pub fn just_finished(&self, name: &str) -> bool {
+        self.timers.get(name).is_some_and(Timer::just_finished)
+    }
+
+    pub fn remaining_secs(&self, name: &str) -> f32 {
+        self.timers.get(name).map_or(0., Timer::remaining_secs)
+    }
+
+    pub fn elapsed_secs(&self, name: &str) -> f32 {
+        self.stopwatches
+            .get(name)
+            .map_or(0., Stopwatch::elapsed_secs)
+    }
+
+    /// Changes how long the named timer runs for, keeping how far it's got.
+    pub fn set_duration(&mut self, name: &str, secs: f32) {
+        if let Some(timer) = self.timers.get_mut(name) {
+            timer.set_duration(std::time::Duration::from_secs_f32(secs));
+        }
+    }
+
+    /// Starts the named timer over, running for `secs` this time.
+    pub fn restart(&mut self, name: &str, secs: f32) {
+        if let Some(timer) = self.timers.get_mut(name) {
+            timer.set_duration(std::time::Duration::from_secs_f32(secs));
+            timer.reset();
+        }
+    }
+
+    /// How far every timer and stopwatch has got, by name, for picking up
+    /// later with [`GameTimers::restore_progress`].
+    pub fn progress(&self) -> Vec<(String, f32)> {
+        let timers = self
+            .timers
+            .iter()
+            .map(|(name, timer)| (name.to_string(), timer.elapsed_secs()));
+        let stopwatches = self
+            .stopwatches
+            .iter()
+            .map(|(name, stopwatch)| (name.to_string(), stopwatch.elapsed_secs()));
+        timers.chain(stopwatches).collect()
+    }
+
+    /// Unknown names are skipped.
+    pub fn restore_progress(&mut self, progress: &[(String, f32)]) {
+        for (name, elapsed) in progress {
+            let elapsed = std::time::Duration::from_secs_f32(*elapsed);
+            if let Some(timer) = self.timers.get_mut(name.as_str()) {
+                timer.set_elapsed(elapsed);
+            }
+            if let Some(stopwatch) = self.stopwatches.get_mut(name.as_str()) {
+                stopwatch.set_elapsed(elapsed);
+            }
+        }
+    }
+
+    pub fn reset_stopwatch(&mut self, name: &str) {
+        if let Some(stopwatch) = self.stopwatches.get_mut(name) {
+            stopwatch.reset();
+        }
+    }
+}
+
+fn reset_game_timers(mut game_timers: ResMut<GameTimers>) {
+    for timer in game_timers.timers.values_mut() {
+        timer.reset();
+    }
+    for stopwatch in game_timers.stopwatches.values_mut() {
+        stopwatch.reset();
+    }
+}
+
+fn tick_game_timers(mut game_timers: ResMut<GameTimers>, time: Res<Time>) {
+    for timer in game_timers.timers.values_mut() {
+        timer.tick(time.delta());
+    }
+    for stopwatch in game_timers.stopwatches.values_mut() {
+        stopwatch.tick(time.delta());
+    }
+}
@@ -0,0 +1,164 @@
+use crate::safe_area::KeepInSafeArea;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+const MAX_VISIBLE_TOASTS: usize = 3;
+const TOAST_LIFETIME: f32 = 3.;
+const SLIDE_DURATION: f32 = 0.25;
+const SLIDE_DISTANCE: f32 = 400.;
+const BADGE_SIZE: f32 = 24.;
+
+/// Short messages that slide in at the top right of the screen and dismiss
+/// themselves. Anything can show one by sending [`ShowToast`], or
+/// [`ShowBadgeToast`] for one with a badge; at most [`MAX_VISIBLE_TOASTS`]
+/// are on screen and the rest wait their turn.
+pub struct ToastPlugin;
+
+#[derive(Event)]
+pub struct ShowToast(pub String);
+
+/// A toast with a round badge in `color` ahead of the text.
+#[derive(Event)]
+pub struct ShowBadgeToast {
+    pub text: String,
+    pub color: Color,
+}
+
+struct QueuedToast {
+    text: String,
+    badge: Option<Color>,
+}
+
+#[derive(Resource, Default)]
+struct ToastQueue(VecDeque<QueuedToast>);
+
+#[derive(Component)]
+struct ToastContainer;
+
+#[derive(Component)]
+struct Toast {
+    age: f32,
+}
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShowToast>()
+            .add_event::<ShowBadgeToast>()
+            .init_resource::<ToastQueue>()
+            .add_systems(Startup, spawn_toast_container)
+            .add_systems(
+                Update,
+                (queue_toasts, show_queued_toasts, animate_toasts).chain(),
+            );
+    }
+}
+
+fn spawn_toast_container(mut commands: Commands) {
+    commands.spawn((
+        ToastContainer,
+        KeepInSafeArea,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.),
+            right: Val::Px(20.),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
+            row_gap: Val::Px(10.),
+            ..default()
+        },
+        GlobalZIndex(10),
+    ));
+}
+
+fn queue_toasts(
+    mut toast_reader: EventReader<ShowToast>,
+    mut badge_toast_reader: EventReader<ShowBadgeToast>,
+    mut queue: ResMut<ToastQueue>,
+) {
+    for ShowToast(text) in toast_reader.read() {
+        queue.0.push_back(QueuedToast {
+            text: text.clone(),
+            badge: None,
+        });
+    }
+    for ShowBadgeToast { text, color } in badge_toast_reader.read() {
+        queue.0.push_back(QueuedToast {
+            text: text.clone(),
+            badge: Some(*color),
+        });
+    }
+}
+
+fn show_queued_toasts(
+    mut commands: Commands,
+    mut queue: ResMut<ToastQueue>,
+    container_query: Query<Entity, With<ToastContainer>>,
+    toast_query: Query<&Toast>,
+) {
+    let Ok(container) = container_query.get_single() else {
+        return;
+    };
+
+    for _ in toast_query.iter().count()..MAX_VISIBLE_TOASTS {
+        let Some(QueuedToast { text, badge }) = queue.0.pop_front() else {
+            return;
+        };
+
+        commands.entity(container).with_children(|builder| {
+            builder
+                .spawn((
+                    Toast { age: 0. },
+                    Node {
+                        left: Val::Px(SLIDE_DISTANCE),
+                        padding: UiRect::axes(Val::Px(16.), Val::Px(8.)),
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(10.),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+                ))
+                .with_children(|builder| {
+                    if let Some(color) = badge {
+                        builder.spawn((
+                            Node {
+                                width: Val::Px(BADGE_SIZE),
+                                height: Val::Px(BADGE_SIZE),
+                                border: UiRect::all(Val::Px(3.)),
+                                ..default()
+                            },
+                            BackgroundColor(color),
+                            BorderColor(Color::WHITE),
+                            BorderRadius::MAX,
+                        ));
+                    }
+                    builder.spawn((
+                        Text::new(text),
+                        TextFont {
+                            font_size: 24.,
+                            ..default()
+                        },
+                    ));
+                });
+        });
+    }
+}
+
+fn animate_toasts(
+    mut commands: Commands,
+    mut toast_query: Query<(Entity, &mut Toast, &mut Node)>,
+    time: Res<Time<Real>>,
+) {
+    for (entity, mut toast, mut node) in toast_query.iter_mut() {
+        toast.age += time.delta_secs();
+
+        if toast.age >= TOAST_LIFETIME {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let slide_in = (toast.age / SLIDE_DURATION).min(1.);
+        let slide_out = ((TOAST_LIFETIME - toast.age) / SLIDE_DURATION).min(1.);
+        let visible = slide_in.min(slide_out);
+        node.left = Val::Px(SLIDE_DISTANCE * (1. - visible));
+    }
+}
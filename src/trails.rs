@@ -0,0 +1,193 @@
+use crate::{
+    Ball, InGameEntity, InGameSet, Numbered, Player, SIZE_FACTOR, config::GameConfig,
+    governor::PerformanceGovernor, layers::RenderLayer,
+};
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+/// In seconds, at full quality.
+const TRAIL_LIFETIME: f32 = 0.5;
+/// In seconds, at full quality.
+const DOT_INTERVAL: f32 = 0.03;
+const DOT_TEXTURE_SIZE: u32 = 32;
+const DOT_ALPHA: f32 = 0.35;
+/// Of a ball's diameter.
+const BALL_TRAIL_SIZE: f32 = 0.6;
+const EATABLE_COLOR: Color = Color::srgb(0.3, 0.9, 0.4);
+const DANGEROUS_COLOR: Color = Color::srgb(0.95, 0.3, 0.3);
+
+/// Anything with a [`Trail`] leaves a line of dots behind it that shrink and
+/// fade out over half a second. With `ball_trails` on every ball has one,
+/// green while the player could eat it and red while it's bigger, so paths
+/// can still be told apart where lots of balls cross. While the performance
+/// governor is cutting back, trails drop dots less often and fade sooner, or
+/// stop leaving dots at all.
+pub struct TrailPlugin;
+
+#[derive(Component)]
+pub struct Trail {
+    pub color: Color,
+    /// The width of a fresh dot.
+    pub size: f32,
+    since_dot: f32,
+}
+
+#[derive(Component)]
+struct TrailDot {
+    age: f32,
+    size: f32,
+}
+
+#[derive(Resource)]
+struct DotTexture(Handle<Image>);
+
+impl Plugin for TrailPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, create_dot_texture)
+            .add_systems(Update, add_ball_trails.run_if(ball_trails_enabled))
+            .add_systems(
+                Update,
+                (color_ball_trails, drop_trail_dots, fade_trail_dots)
+                    .chain()
+                    .in_set(InGameSet),
+            );
+    }
+}
+
+impl Trail {
+    pub fn new(color: Color, size: f32) -> Self {
+        Trail {
+            color,
+            size,
+            since_dot: 0.,
+        }
+    }
+}
+
+fn ball_trails_enabled(config: Res<GameConfig>) -> bool {
+    config.ball_trails
+}
+
+/// A white disc with a soft edge, tinted per dot.
+fn create_dot_texture(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let radius = DOT_TEXTURE_SIZE as f32 / 2.;
+    let data = (0..DOT_TEXTURE_SIZE * DOT_TEXTURE_SIZE)
+        .flat_map(|index| {
+            let x = (index % DOT_TEXTURE_SIZE) as f32 + 0.5 - radius;
+            let y = (index / DOT_TEXTURE_SIZE) as f32 + 0.5 - radius;
+            let distance = Vec2::new(x, y).length() / radius;
+            let alpha = ((1. - distance) * 4.).clamp(0., 1.);
+            [255, 255, 255, (alpha * 255.) as u8]
+        })
+        .collect();
+
+    let image = Image::new(
+        Extent3d {
+            width: DOT_TEXTURE_SIZE,
+            height: DOT_TEXTURE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    commands.insert_resource(DotTexture(images.add(image)));
+}
+
+fn add_ball_trails(
+    mut commands: Commands,
+    new_ball_query: Query<(Entity, &Numbered), Added<Ball>>,
+) {
+    for (ball, Numbered(number)) in new_ball_query.iter() {
+        commands.entity(ball).insert(Trail::new(
+            DANGEROUS_COLOR,
+            *number as f32 * SIZE_FACTOR * BALL_TRAIL_SIZE,
+        ));
+    }
+}
+
+fn color_ball_trails(
+    player_query: Query<&Numbered, With<Player>>,
+    mut trail_query: Query<(&Numbered, &mut Trail), With<Ball>>,
+) {
+    let Ok(Numbered(player_number)) = player_query.get_single() else {
+        return;
+    };
+
+    for (Numbered(number), mut trail) in trail_query.iter_mut() {
+        trail.color = if number <= player_number {
+            EATABLE_COLOR
+        } else {
+            DANGEROUS_COLOR
+        };
+        trail.size = *number as f32 * SIZE_FACTOR * BALL_TRAIL_SIZE;
+    }
+}
+
+fn drop_trail_dots(
+    mut commands: Commands,
+    mut trail_query: Query<(&mut Trail, &GlobalTransform)>,
+    dot_texture: Res<DotTexture>,
+    governor: Res<PerformanceGovernor>,
+    time: Res<Time>,
+) {
+    let quality = governor.trail_quality();
+    if quality <= 0. {
+        return;
+    }
+    let interval = DOT_INTERVAL / quality;
+
+    for (mut trail, transform) in trail_query.iter_mut() {
+        trail.since_dot += time.delta_secs();
+        if trail.since_dot < interval {
+            continue;
+        }
+        trail.since_dot = 0.;
+
+        commands.spawn((
+            TrailDot {
+                age: 0.,
+                size: trail.size,
+            },
+            InGameEntity,
+            Sprite {
+                image: dot_texture.0.clone(),
+                color: trail.color.with_alpha(DOT_ALPHA),
+                custom_size: Some(Vec2::splat(trail.size)),
+                ..default()
+            },
+            Transform::from_translation(RenderLayer::Trails.at(transform.translation().truncate())),
+        ));
+    }
+}
+
+fn fade_trail_dots(
+    mut commands: Commands,
+    mut dot_query: Query<(Entity, &mut TrailDot, &mut Sprite)>,
+    governor: Res<PerformanceGovernor>,
+    time: Res<Time>,
+) {
+    let lifetime = TRAIL_LIFETIME * governor.trail_quality();
+
+    for (dot, mut trail_dot, mut sprite) in dot_query.iter_mut() {
+        trail_dot.age += time.delta_secs();
+        let left = if lifetime > 0. {
+            1. - trail_dot.age / lifetime
+        } else {
+            0.
+        };
+        if left <= 0. {
+            commands.entity(dot).despawn();
+            continue;
+        }
+
+        sprite.color.set_alpha(DOT_ALPHA * left);
+        sprite.custom_size = Some(Vec2::splat(trail_dot.size * left));
+    }
+}
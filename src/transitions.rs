@@ -0,0 +1,192 @@
+use crate::{GameState, config::GameConfig};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// How long the screen takes to cover, and again to uncover.
+const TRANSITION_TIME: f32 = 0.3;
+/// How far in the camera has zoomed once the screen is covered.
+const ZOOM_AMOUNT: f32 = 0.4;
+
+/// Moving between the main menu, a run and the death screen covers the
+/// screen before switching and uncovers it afterwards, instead of popping
+/// straight from one to the other. A state change asked for in the middle of
+/// that is held back until the screen is covered, and the world is frozen
+/// meanwhile so nothing happens that the player can't see.
+pub struct TransitionPlugin;
+
+/// How the screen is covered, set with `transition` in the config.
+/// Reduced motion always fades.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+pub enum TransitionKind {
+    #[default]
+    Fade,
+    /// A curtain pulled across from the left, and on off to the right.
+    Wipe,
+    /// Fades while the camera zooms in, and back out again.
+    Zoom,
+}
+
+#[derive(Resource, Default)]
+pub enum Transition {
+    #[default]
+    Idle,
+    Covering {
+        target: GameState,
+        elapsed: f32,
+    },
+    Uncovering {
+        elapsed: f32,
+    },
+}
+
+#[derive(Component)]
+struct TransitionCover;
+
+impl Plugin for TransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Transition>()
+            .add_systems(Startup, spawn_transition_cover)
+            // Right before states change, so a change can be held back before
+            // it's applied.
+            .add_systems(
+                PreUpdate,
+                (hold_back_state_change, advance_transition).chain(),
+            )
+            .add_systems(Update, draw_transition);
+    }
+}
+
+impl Transition {
+    /// How much of the screen is covered, from 0 to 1.
+    fn cover(&self) -> f32 {
+        match self {
+            Transition::Idle => 0.,
+            Transition::Covering { elapsed, .. } => (elapsed / TRANSITION_TIME).min(1.),
+            Transition::Uncovering { elapsed } => 1. - (elapsed / TRANSITION_TIME).min(1.),
+        }
+    }
+}
+
+fn animated(state: &GameState) -> bool {
+    matches!(
+        state,
+        GameState::Menu | GameState::InGame | GameState::DeathScreen
+    )
+}
+
+/// Run condition for the screen not being on its way to covered, so a run
+/// that's ending stays frozen behind the cover.
+pub fn not_covering(transition: Res<Transition>) -> bool {
+    !matches!(*transition, Transition::Covering { .. })
+}
+
+fn spawn_transition_cover(mut commands: Commands) {
+    commands.spawn((
+        TransitionCover,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            ..default()
+        },
+        BackgroundColor(Color::NONE),
+        GlobalZIndex(20),
+    ));
+}
+
+fn hold_back_state_change(
+    mut next_game_state: ResMut<NextState<GameState>>,
+    game_state: Res<State<GameState>>,
+    mut transition: ResMut<Transition>,
+    mut physics_time: ResMut<Time<Physics>>,
+) {
+    let NextState::Pending(target) = next_game_state.clone() else {
+        return;
+    };
+    let current = game_state.get();
+    if !animated(current) || !animated(&target) || target == *current {
+        return;
+    }
+
+    next_game_state.reset();
+    match *transition {
+        // Already on its way somewhere else.
+        Transition::Covering { .. } => {}
+        // Covers again from however far it had got uncovering.
+        Transition::Idle | Transition::Uncovering { .. } => {
+            *transition = Transition::Covering {
+                target,
+                elapsed: transition.cover() * TRANSITION_TIME,
+            };
+            physics_time.pause();
+        }
+    }
+}
+
+/// Real time, so a slowed-down run doesn't slow the transition out of it.
+fn advance_transition(
+    mut transition: ResMut<Transition>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut physics_time: ResMut<Time<Physics>>,
+    time: Res<Time<Real>>,
+) {
+    match &mut *transition {
+        Transition::Idle => {}
+        Transition::Covering { target, elapsed } => {
+            *elapsed += time.delta_secs();
+            if *elapsed >= TRANSITION_TIME {
+                next_game_state.set(target.clone());
+                physics_time.unpause();
+                *transition = Transition::Uncovering { elapsed: 0. };
+            }
+        }
+        Transition::Uncovering { elapsed } => {
+            *elapsed += time.delta_secs();
+            if *elapsed >= TRANSITION_TIME {
+                *transition = Transition::Idle;
+            }
+        }
+    }
+}
+
+fn draw_transition(
+    transition: Res<Transition>,
+    config: Res<GameConfig>,
+    mut cover_query: Query<(&mut Node, &mut BackgroundColor), With<TransitionCover>>,
+    mut projection_query: Query<&mut OrthographicProjection, With<Camera2d>>,
+) {
+    if !transition.is_changed() {
+        return;
+    }
+
+    let cover = transition.cover();
+    let kind = if config.reduced_motion {
+        TransitionKind::Fade
+    } else {
+        config.transition
+    };
+    let uncovering = matches!(*transition, Transition::Uncovering { .. });
+
+    for (mut node, mut background) in cover_query.iter_mut() {
+        match kind {
+            TransitionKind::Fade | TransitionKind::Zoom => {
+                node.left = Val::Px(0.);
+                node.width = Val::Percent(100.);
+                background.0 = Color::srgba(0., 0., 0., cover);
+            }
+            TransitionKind::Wipe => {
+                node.left = Val::Percent(if uncovering { 100. * (1. - cover) } else { 0. });
+                node.width = Val::Percent(100. * cover);
+                background.0 = Color::BLACK;
+            }
+        }
+    }
+
+    for mut projection in projection_query.iter_mut() {
+        projection.scale = match kind {
+            TransitionKind::Zoom => 1. - ZOOM_AMOUNT * cover,
+            TransitionKind::Fade | TransitionKind::Wipe => 1.,
+        };
+    }
+}
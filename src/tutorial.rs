@@ -0,0 +1,272 @@
+use crate::{
+    Ball, BallEaten, Bound, FatalHit, GameState, InGameEntity, InGameSet, MovementAction, Numbered,
+    Player,
+    config::GameConfig,
+    gravity::GravityFlip,
+    prompts::{MenuAction, Prompt},
+    safe_area::KeepInSafeArea,
+    spawn_numbered_ball,
+    storage::Storage,
+    toast::ShowToast,
+};
+use bevy::prelude::*;
+
+const DONE_KEY: &str = "tutorial_done";
+/// How many times smaller than the starting number the ball to eat is.
+const SMALL_BALL_DIVISOR: i32 = 3;
+/// How much bigger than the player the ball to avoid is.
+const BIG_BALL_MARGIN: i32 = 20;
+const TUTORIAL_BALL_SPEED: f32 = 150.;
+
+/// A guided run for new players, started from the main menu. Instead of the
+/// usual stream of balls it sends them in one at a time, each with a prompt
+/// for what to do about it, and only moves on once that's been done. Getting
+/// hit doesn't end the run; the ball just comes again. Like demo runs,
+/// tutorial runs don't count for scores, hints or unlocks.
+pub struct TutorialPlugin;
+
+#[derive(Resource, Default)]
+pub struct Tutorial {
+    pub running: bool,
+    step: TutorialStep,
+    /// The ball sent in for the current step, if it's still around.
+    ball: Option<Entity>,
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+enum TutorialStep {
+    #[default]
+    Move,
+    EatSmaller,
+    AvoidBigger,
+    FlipGravity,
+}
+
+#[derive(Component)]
+struct TutorialInstruction;
+
+/// The [`Prompt`] under the instruction.
+#[derive(Component)]
+struct TutorialInputs;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tutorial>()
+            .add_systems(OnEnter(GameState::Menu), end_tutorial)
+            .add_systems(OnEnter(GameState::InGame), start_tutorial)
+            .add_systems(
+                Update,
+                (
+                    send_tutorial_ball,
+                    retry_after_hit,
+                    advance_tutorial,
+                    update_tutorial_prompt,
+                )
+                    .chain()
+                    .in_set(InGameSet)
+                    .run_if(in_tutorial),
+            );
+    }
+}
+
+/// Run condition for a tutorial run being played.
+pub fn in_tutorial(tutorial: Res<Tutorial>) -> bool {
+    tutorial.running
+}
+
+impl TutorialStep {
+    fn instruction(self) -> &'static str {
+        match self {
+            TutorialStep::Move => "move",
+            TutorialStep::EatSmaller => "eat the smaller number",
+            TutorialStep::AvoidBigger => "avoid bigger ones",
+            TutorialStep::FlipGravity => "flip gravity",
+        }
+    }
+
+    /// The inputs for it, shown for whichever device was used last.
+    fn prompt(self) -> Vec<(MenuAction, &'static str)> {
+        match self {
+            TutorialStep::Move => vec![
+                (MenuAction::MoveLeft, "left"),
+                (MenuAction::MoveRight, "right"),
+            ],
+            TutorialStep::EatSmaller | TutorialStep::AvoidBigger => Vec::new(),
+            TutorialStep::FlipGravity => vec![(MenuAction::FlipGravity, "flip")],
+        }
+    }
+
+    fn next(self) -> Option<Self> {
+        match self {
+            TutorialStep::Move => Some(TutorialStep::EatSmaller),
+            TutorialStep::EatSmaller => Some(TutorialStep::AvoidBigger),
+            TutorialStep::AvoidBigger => Some(TutorialStep::FlipGravity),
+            TutorialStep::FlipGravity => None,
+        }
+    }
+}
+
+/// Whether the tutorial has ever been finished.
+pub fn tutorial_done(storage: &Storage) -> bool {
+    storage.load(DONE_KEY).unwrap_or(false)
+}
+
+fn end_tutorial(mut tutorial: ResMut<Tutorial>) {
+    tutorial.running = false;
+}
+
+fn start_tutorial(mut commands: Commands, mut tutorial: ResMut<Tutorial>) {
+    if !tutorial.running {
+        return;
+    }
+    tutorial.step = TutorialStep::default();
+    tutorial.ball = None;
+
+    commands
+        .spawn((
+            InGameEntity,
+            KeepInSafeArea,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(120.),
+                width: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(8.),
+                ..default()
+            },
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                TutorialInstruction,
+                Text::default(),
+                TextFont {
+                    font_size: 40.,
+                    ..default()
+                },
+            ));
+            builder.spawn((
+                TutorialInputs,
+                Text::default(),
+                Prompt(Vec::new()),
+                TextFont {
+                    font_size: 24.,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+/// The steps with a ball send one across from the side of the arena away
+/// from the player, right at them, and again whenever it's gone.
+fn send_tutorial_ball(
+    mut commands: Commands,
+    mut tutorial: ResMut<Tutorial>,
+    player_query: Query<(&Numbered, &Transform), With<Player>>,
+    ball_query: Query<(), With<Ball>>,
+    config: Res<GameConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let number_for_player = |player_number: i32| match tutorial.step {
+        TutorialStep::EatSmaller => Some((config.starting_number / SMALL_BALL_DIVISOR).max(1)),
+        TutorialStep::AvoidBigger => Some(player_number + BIG_BALL_MARGIN),
+        TutorialStep::Move | TutorialStep::FlipGravity => None,
+    };
+    if tutorial.ball.is_some_and(|ball| ball_query.contains(ball)) {
+        return;
+    }
+    let Ok((Numbered(player_number), player_transform)) = player_query.get_single() else {
+        return;
+    };
+    let Some(number) = number_for_player(*player_number) else {
+        return;
+    };
+
+    let player_position = player_transform.translation.truncate();
+    let (bound, direction) = if player_position.y < 0. {
+        (Bound::UpperBound, Vec2::NEG_Y)
+    } else {
+        (Bound::LowerBound, Vec2::Y)
+    };
+    tutorial.ball = Some(spawn_numbered_ball(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        number,
+        Vec2::new(player_position.x, bound.value()),
+        direction * TUTORIAL_BALL_SPEED,
+    ));
+}
+
+/// Stands in for ending the run: the ball that hit goes, and the step's ball
+/// comes round again.
+fn retry_after_hit(
+    mut commands: Commands,
+    mut fatal_hit_reader: EventReader<FatalHit>,
+    mut tutorial: ResMut<Tutorial>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    for FatalHit { ball } in fatal_hit_reader.read() {
+        if let Some(ball) = ball {
+            commands.entity(*ball).despawn_recursive();
+        }
+        tutorial.ball = None;
+        toast_writer.send(ShowToast("Too big! Try again".to_string()));
+    }
+}
+
+/// The ball to avoid counts as avoided once it has left the arena.
+#[allow(clippy::too_many_arguments)]
+fn advance_tutorial(
+    mut tutorial: ResMut<Tutorial>,
+    mut movement_event_reader: EventReader<MovementAction>,
+    mut ball_eaten_reader: EventReader<BallEaten>,
+    mut flip_reader: EventReader<GravityFlip>,
+    ball_query: Query<(), With<Ball>>,
+    mut storage: ResMut<Storage>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut toast_writer: EventWriter<ShowToast>,
+) {
+    let moved = movement_event_reader.read().count() > 0;
+    let ate = ball_eaten_reader.read().count() > 0;
+    let flipped = flip_reader.read().any(|flip| !flip.blocked);
+
+    let done = match tutorial.step {
+        TutorialStep::Move => moved,
+        TutorialStep::EatSmaller => ate,
+        TutorialStep::AvoidBigger => tutorial.ball.is_some_and(|ball| !ball_query.contains(ball)),
+        TutorialStep::FlipGravity => flipped,
+    };
+    if !done {
+        return;
+    }
+
+    tutorial.ball = None;
+    match tutorial.step.next() {
+        Some(step) => tutorial.step = step,
+        None => {
+            storage.save(DONE_KEY, true);
+            toast_writer.send(ShowToast("Tutorial complete!".to_string()));
+            next_game_state.set(GameState::Menu);
+        }
+    }
+}
+
+fn update_tutorial_prompt(
+    tutorial: Res<Tutorial>,
+    mut instruction_query: Query<&mut Text, With<TutorialInstruction>>,
+    mut prompt_query: Query<&mut Prompt, With<TutorialInputs>>,
+) {
+    if !tutorial.is_changed() {
+        return;
+    }
+
+    for mut text in instruction_query.iter_mut() {
+        text.0 = tutorial.step.instruction().to_string();
+    }
+    for mut prompt in prompt_query.iter_mut() {
+        prompt.0 = tutorial.step.prompt();
+    }
+}
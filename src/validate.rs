@@ -0,0 +1,90 @@
+use crate::{
+    arena::{ArenaKind, ArenaLayout},
+    ball_kinds::BallKinds,
+    config::GameConfig,
+    parallax,
+    sound_bank::SoundBank,
+};
+use std::path::Path;
+
+/// `--validate` checks the content the game ships with against the config it
+/// would be played with, without opening a window: the config itself, the
+/// ball kinds in `assets/balls.ron`, the config's sound bank and every arena
+/// layout and its parallax layers. Each is listed
+/// with what's wrong with it, so bad data is caught before it breaks a run.
+/// Returns the exit code, 1 if anything is wrong.
+pub fn run(config_path: Option<&Path>) -> i32 {
+    let mut report = Report::default();
+
+    let config = match config_path {
+        Some(path) => match GameConfig::load(path) {
+            Ok(config) => {
+                report.section(&format!("config {}", path.display()), config.problems());
+                config
+            }
+            Err(error) => {
+                report.section(&format!("config {}", path.display()), vec![error]);
+                GameConfig::default()
+            }
+        },
+        None => {
+            let config = GameConfig::default();
+            report.section("default config", config.problems());
+            config
+        }
+    };
+
+    let assets = Path::new("assets");
+    let ball_problems = match BallKinds::load(&assets.join("balls.ron")) {
+        Ok(kinds) => kinds.problems(),
+        Err(error) => vec![error],
+    };
+    report.section("ball kinds assets/balls.ron", ball_problems);
+    report.section(
+        &format!("sound bank assets/{}", config.sound_bank),
+        SoundBank::problems(assets, &config.sound_bank),
+    );
+
+    for kind in ArenaKind::ALL {
+        report.section(
+            &format!("arena {kind:?}"),
+            ArenaLayout::new(kind).problems(config.starting_number),
+        );
+        report.section(&format!("parallax {kind:?}"), parallax::problems(kind));
+    }
+
+    match report.problems {
+        0 => {
+            println!("everything is valid");
+            0
+        }
+        1 => {
+            println!("1 problem found");
+            1
+        }
+        count => {
+            println!("{count} problems found");
+            1
+        }
+    }
+}
+
+#[derive(Default)]
+struct Report {
+    problems: usize,
+}
+
+impl Report {
+    fn section(&mut self, name: &str, problems: Vec<String>) {
+        if problems.is_empty() {
+            println!("{name}: ok");
+            return;
+        }
+
+        println!("{name}:");
+        for problem in &problems {
+            println!("  - {problem}");
+        }
+        self.problems += problems.len();
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{
+    CurrentScore, GameState, HighScore, HighScoreNameEntry, InGameSet, MAX_NAME_LENGTH,
+    NAME_CHARSET, Numbered, Player, SIZE_FACTOR,
+    config::GameConfig,
+    new_game_plus::NewGamePlus,
+    prompts::{MenuAction, MenuInput, Prompt},
+    record_high_score, recorded_run,
+    stats::{RunLog, RunStats},
+    storage::Storage,
+    text_entry::{no_text_entry, spawn_text_entry},
+    xp::{Experience, spawn_xp_summary},
+};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+const CINEMATIC_DURATION: f32 = 2.5;
+/// Comfortably bigger than the arena, so the square swallows all of it.
+const SCREEN_COVER_SIZE: f32 = 4000.;
+
+/// Reaching the victory number ends a run with a win: the player's square
+/// grows to fill the arena, then a victory screen shows the run's stats.
+/// Winning once unlocks New Game+, and winning in it goes on to the next
+/// loop.
+pub struct VictoryPlugin;
+
+#[derive(Resource)]
+struct VictoryCinematic {
+    elapsed: f32,
+}
+
+impl Plugin for VictoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, check_victory.in_set(InGameSet).run_if(recorded_run))
+            .add_systems(OnEnter(GameState::Victory), start_victory_cinematic)
+            .add_systems(
+                Update,
+                (
+                    play_victory_cinematic,
+                    restart_after_victory.run_if(no_text_entry),
+                )
+                    .run_if(in_state(GameState::Victory)),
+            )
+            .add_systems(OnExit(GameState::Victory), leave_victory);
+    }
+}
+
+fn check_victory(
+    player_query: Query<&Numbered, (With<Player>, Changed<Numbered>)>,
+    config: Res<GameConfig>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    let Ok(Numbered(player_number)) = player_query.get_single() else {
+        return;
+    };
+
+    if config
+        .victory_number
+        .is_some_and(|victory_number| *player_number >= victory_number)
+    {
+        next_game_state.set(GameState::Victory);
+    }
+}
+
+fn start_victory_cinematic(
+    mut commands: Commands,
+    mut physics_time: ResMut<Time<Physics>>,
+    mut new_game_plus: ResMut<NewGamePlus>,
+    mut storage: ResMut<Storage>,
+) {
+    physics_time.pause();
+    commands.insert_resource(VictoryCinematic { elapsed: 0. });
+
+    new_game_plus.unlock(&mut storage);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn play_victory_cinematic(
+    mut commands: Commands,
+    mut cinematic: ResMut<VictoryCinematic>,
+    mut player_query: Query<(&Numbered, &mut Transform), With<Player>>,
+    time: Res<Time>,
+    stats: Res<RunStats>,
+    run_log: Res<RunLog>,
+    current_score: Res<CurrentScore>,
+    mut high_score: ResMut<HighScore>,
+    mut storage: ResMut<Storage>,
+    experience: Res<Experience>,
+) {
+    if cinematic.elapsed >= CINEMATIC_DURATION {
+        return;
+    }
+    cinematic.elapsed += time.delta_secs();
+
+    let progress = (cinematic.elapsed / CINEMATIC_DURATION).min(1.);
+    for (Numbered(player_number), mut transform) in player_query.iter_mut() {
+        let cover_scale = SCREEN_COVER_SIZE / (*player_number as f32 * SIZE_FACTOR);
+        let eased = progress * progress * (3. - 2. * progress);
+        transform.scale = Vec3::splat(1. + (cover_scale - 1.) * eased);
+    }
+
+    if progress < 1. {
+        return;
+    }
+
+    let new_high_score = record_high_score(current_score.0, &mut high_score, &mut storage);
+    let high_score_text = if new_high_score {
+        "new high score! enter your name".to_string()
+    } else {
+        high_score.describe()
+    };
+    let mut lines = vec![format!("score - {}", current_score.0)];
+    lines.extend(stats.breakdown());
+    lines.push(high_score_text);
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            StateScoped(GameState::Victory),
+        ))
+        .with_children(|builder| {
+            builder
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder.spawn((
+                        Text::new("You win!"),
+                        TextFont {
+                            font_size: 100.,
+                            ..default()
+                        },
+                    ));
+                    for line in lines {
+                        builder.spawn((
+                            Text::new(line),
+                            TextFont {
+                                font_size: 30.,
+                                ..default()
+                            },
+                        ));
+                    }
+                    if new_high_score {
+                        spawn_text_entry(
+                            builder,
+                            HighScoreNameEntry,
+                            NAME_CHARSET,
+                            MAX_NAME_LENGTH,
+                        );
+                    }
+                    spawn_xp_summary(builder, &experience);
+                    builder.spawn((
+                        Text::default(),
+                        Prompt(vec![
+                            (MenuAction::PlayAgain, "play again"),
+                            (MenuAction::NewGamePlus, "New Game+"),
+                            (MenuAction::HighScores, "high scores"),
+                            (MenuAction::ShareCard, "share card"),
+                        ]),
+                        TextFont {
+                            font_size: 30.,
+                            ..default()
+                        },
+                    ));
+                    for line in run_log.summary_lines() {
+                        builder.spawn((
+                            Text::new(line),
+                            TextFont {
+                                font_size: 18.,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                        ));
+                    }
+                });
+        });
+}
+
+fn restart_after_victory(
+    menu_input: MenuInput,
+    cinematic: Res<VictoryCinematic>,
+    mut new_game_plus: ResMut<NewGamePlus>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if cinematic.elapsed < CINEMATIC_DURATION {
+        return;
+    }
+
+    if menu_input.just_pressed(MenuAction::NewGamePlus) {
+        new_game_plus.start_next_loop();
+        next_game_state.set(GameState::InGame);
+    } else if menu_input.just_pressed(MenuAction::PlayAgain) {
+        next_game_state.set(GameState::InGame);
+    } else if menu_input.just_pressed(MenuAction::HighScores) {
+        next_game_state.set(GameState::HighScores);
+    }
+}
+
+fn leave_victory(mut commands: Commands, mut physics_time: ResMut<Time<Physics>>) {
+    physics_time.unpause();
+    commands.remove_resource::<VictoryCinematic>();
+}
@@ -0,0 +1,166 @@
+use crate::{GameRng, GameState, config::GameConfig};
+use bevy::{prelude::*, utils::SystemTime};
+use rand::{prelude::*, rngs::StdRng};
+use std::ops::RangeInclusive;
+
+const MUTATORS_PER_PLAYLIST: RangeInclusive<usize> = 2..=3;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Every ISO week has its own playlist of two or three mutators, picked with
+/// a seed taken from the week so everyone gets the same one. Playing it from
+/// the main menu layers the mutators over the config and starts every run
+/// with the week's seed until the player goes back to the menu. Its runs keep
+/// a high score of their own and are filed under the week in the score table.
+pub struct WeeklyPlaylistPlugin;
+
+#[derive(Clone, Copy)]
+pub enum Mutator {
+    FastBalls,
+    Swarms,
+    Checkpoints,
+    Fragile,
+    HeadStart,
+    Spin,
+}
+
+#[derive(Resource)]
+pub struct WeeklyPlaylist {
+    /// Like `2026-W42`.
+    pub week: String,
+    seed: u64,
+    pub mutators: Vec<Mutator>,
+    pub active: bool,
+    /// The config from before the mutators were layered on, for going back
+    /// to once the player is done with the playlist.
+    base_config: Option<GameConfig>,
+}
+
+impl Plugin for WeeklyPlaylistPlugin {
+    fn build(&self, app: &mut App) {
+        let days_since_epoch = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs() / SECONDS_PER_DAY);
+
+        app.insert_resource(WeeklyPlaylist::for_day(days_since_epoch as i64))
+            .add_systems(
+                OnExit(GameState::Menu),
+                (apply_playlist, reseed_for_playlist).chain(),
+            )
+            .add_systems(OnExit(GameState::InGame), reseed_for_playlist)
+            .add_systems(OnEnter(GameState::Menu), leave_playlist);
+    }
+}
+
+impl Mutator {
+    const ALL: [Mutator; 6] = [
+        Mutator::FastBalls,
+        Mutator::Swarms,
+        Mutator::Checkpoints,
+        Mutator::Fragile,
+        Mutator::HeadStart,
+        Mutator::Spin,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Mutator::FastBalls => "fast balls",
+            Mutator::Swarms => "swarms",
+            Mutator::Checkpoints => "checkpoints",
+            Mutator::Fragile => "fragile",
+            Mutator::HeadStart => "head start",
+            Mutator::Spin => "spin",
+        }
+    }
+
+    fn apply(self, config: &mut GameConfig) {
+        match self {
+            Mutator::FastBalls => config.ball_speed *= 1.5,
+            Mutator::Swarms => config.swarm_chance = 0.25,
+            Mutator::Checkpoints => config.checkpoints = vec![25, 50, 100],
+            Mutator::Fragile => config.lose_number = Some(config.starting_number - 5),
+            Mutator::HeadStart => config.starting_number += 10,
+            Mutator::Spin => config.ball_spin = true,
+        }
+    }
+}
+
+impl WeeklyPlaylist {
+    fn for_day(days_since_epoch: i64) -> Self {
+        let (year, week) = iso_week(days_since_epoch);
+        let seed = (year * 100 + week) as u64;
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let count = rng.random_range(MUTATORS_PER_PLAYLIST);
+        let mutators = Mutator::ALL
+            .choose_multiple(&mut rng, count)
+            .copied()
+            .collect();
+
+        WeeklyPlaylist {
+            week: format!("{year}-W{week:02}"),
+            seed,
+            mutators,
+            active: false,
+            base_config: None,
+        }
+    }
+
+    /// Like `2026-W42: fast balls, swarms`.
+    pub fn name(&self) -> String {
+        let mutators: Vec<&str> = self.mutators.iter().map(|mutator| mutator.name()).collect();
+        format!("{}: {}", self.week, mutators.join(", "))
+    }
+
+    /// Where this week's high score is saved, while the playlist is being
+    /// played.
+    pub fn high_score_key(&self) -> Option<String> {
+        self.active
+            .then(|| format!("high_score_weekly_{}", self.week))
+    }
+}
+
+/// Days since the epoch that 1 January of `year` falls on.
+fn new_year(year: i64) -> i64 {
+    let before = year - 1;
+    before * 365 + before.div_euclid(4) - before.div_euclid(100) + before.div_euclid(400) - 719_162
+}
+
+/// The ISO 8601 year and week a day falls in. Weeks start on Monday and
+/// belong to the year their Thursday is in.
+fn iso_week(days_since_epoch: i64) -> (i64, i64) {
+    // 1 January 1970 was a Thursday.
+    let weekday = (days_since_epoch + 3).rem_euclid(7);
+    let thursday = days_since_epoch - weekday + 3;
+
+    let mut year = 1970 + thursday.div_euclid(366);
+    while new_year(year + 1) <= thursday {
+        year += 1;
+    }
+
+    (year, (thursday - new_year(year)) / 7 + 1)
+}
+
+fn apply_playlist(mut playlist: ResMut<WeeklyPlaylist>, mut config: ResMut<GameConfig>) {
+    if !playlist.active || playlist.base_config.is_some() {
+        return;
+    }
+
+    playlist.base_config = Some(config.clone());
+    for mutator in playlist.mutators.clone() {
+        mutator.apply(&mut config);
+    }
+}
+
+/// Every run of the playlist starts from the same seed.
+fn reseed_for_playlist(playlist: Res<WeeklyPlaylist>, mut game_rng: ResMut<GameRng>) {
+    if playlist.active {
+        *game_rng = GameRng::new(playlist.seed);
+    }
+}
+
+fn leave_playlist(mut playlist: ResMut<WeeklyPlaylist>, mut config: ResMut<GameConfig>) {
+    playlist.active = false;
+    if let Some(base_config) = playlist.base_config.take() {
+        *config = base_config;
+    }
+}
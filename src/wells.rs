@@ -0,0 +1,111 @@
+use crate::{Ball, InGameSet, Numbered, Player, config::GameConfig};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+/// Balls numbered above this pull the player in.
+const WELL_MIN_NUMBER: i32 = 80;
+const WELL_RADIUS: f32 = 250.;
+/// In px/s², felt right at the ball and fading to nothing at the edge.
+const WELL_STRENGTH: f32 = 400.;
+const RING_WIDTH: f32 = 6.;
+const RING_PULSE_PERIOD: f32 = 1.2;
+
+/// The biggest balls are gravity wells: within a radius around them the
+/// player is pulled towards them, harder the closer they get. A faint ring
+/// marks how far each one reaches, wobbling unless reduced motion is on.
+pub struct GravityWellPlugin;
+
+#[derive(Component)]
+struct WellRing {
+    elapsed: f32,
+}
+
+#[derive(Resource)]
+struct WellRingAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+impl FromWorld for WellRingAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Annulus::new(WELL_RADIUS - RING_WIDTH, WELL_RADIUS));
+        let material = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(Color::srgba(0.8, 0.6, 1., 0.12));
+        WellRingAssets { mesh, material }
+    }
+}
+
+impl Plugin for GravityWellPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WellRingAssets>()
+            .add_systems(Update, (mark_gravity_wells, wobble_well_rings))
+            .add_systems(FixedUpdate, pull_player_into_wells.in_set(InGameSet));
+    }
+}
+
+fn mark_gravity_wells(
+    mut commands: Commands,
+    ring_assets: Res<WellRingAssets>,
+    new_ball_query: Query<(Entity, &Numbered), Added<Ball>>,
+) {
+    for (ball, Numbered(number)) in new_ball_query.iter() {
+        if *number <= WELL_MIN_NUMBER {
+            continue;
+        }
+
+        commands.entity(ball).with_children(|builder| {
+            builder.spawn((
+                WellRing { elapsed: 0. },
+                Mesh2d(ring_assets.mesh.clone()),
+                MeshMaterial2d(ring_assets.material.clone()),
+                // Just behind the ball it belongs to.
+                Transform::from_xyz(0., 0., -1.),
+            ));
+        });
+    }
+}
+
+fn wobble_well_rings(
+    mut ring_query: Query<(&mut WellRing, &mut Transform)>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+) {
+    if config.reduced_motion {
+        return;
+    }
+
+    for (mut ring, mut transform) in ring_query.iter_mut() {
+        ring.elapsed += time.delta_secs();
+        let phase = ring.elapsed * std::f32::consts::TAU / RING_PULSE_PERIOD;
+        transform.scale = Vec3::new(1. + 0.03 * phase.sin(), 1. + 0.03 * phase.cos(), 1.);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn pull_player_into_wells(
+    mut player_query: Query<(&Transform, &mut LinearVelocity), With<Player>>,
+    ball_query: Query<(&Numbered, &Transform), (With<Ball>, Without<Player>)>,
+    time: Res<Time>,
+) {
+    for (player_transform, mut velocity) in player_query.iter_mut() {
+        let player_position = player_transform.translation.truncate();
+
+        for (Numbered(number), ball_transform) in ball_query.iter() {
+            if *number <= WELL_MIN_NUMBER {
+                continue;
+            }
+
+            let offset = ball_transform.translation.truncate() - player_position;
+            let distance = offset.length();
+            if distance >= WELL_RADIUS || distance == 0. {
+                continue;
+            }
+
+            let strength = WELL_STRENGTH * (1. - distance / WELL_RADIUS);
+            velocity.0 += offset / distance * strength * time.delta_secs();
+        }
+    }
+}
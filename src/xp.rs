@@ -0,0 +1,146 @@
+use crate::{
+    CurrentScore, GameState, finished_run, record_final_score, recorded_run, skins::Skin,
+    stats::RunStats, storage::Storage,
+};
+use bevy::prelude::*;
+
+const XP_KEY: &str = "xp";
+/// Every this many points scored in a run is worth one XP.
+const POINTS_PER_XP: i32 = 10;
+/// Getting from level 1 to 2 takes this much XP, and every level after takes
+/// more than the one before: level `n` is reached at `XP_CURVE * (n - 1)²`.
+const XP_CURVE: u32 = 100;
+const BAR_WIDTH: f32 = 300.;
+const BAR_HEIGHT: f32 = 8.;
+const BAR_COLOR: Color = Color::srgb(0.4, 0.6, 1.);
+
+/// Every recorded run earns the profile XP, one per ten points scored and one
+/// per second survived, and enough of it levels the profile up. Some levels
+/// unlock a skin, see [`crate::skins`]. The death and victory screens show
+/// what the run earned and how far it is to the next level, and call out any
+/// level reached.
+pub struct XpPlugin;
+
+#[derive(Resource, Default)]
+pub struct Experience {
+    pub xp: u32,
+    /// What the last run earned, and the level it started at.
+    last_run: Option<(u32, u32)>,
+}
+
+impl Plugin for XpPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Experience>()
+            .add_systems(Startup, load_experience)
+            .add_systems(
+                OnExit(GameState::InGame),
+                award_xp
+                    .after(record_final_score)
+                    .run_if(recorded_run.and(finished_run)),
+            )
+            .add_systems(OnEnter(GameState::InGame), forget_last_run);
+    }
+}
+
+/// The total XP a level is reached at.
+fn xp_for_level(level: u32) -> u32 {
+    XP_CURVE * (level - 1).pow(2)
+}
+
+impl Experience {
+    pub fn level(&self) -> u32 {
+        let mut level = 1;
+        while xp_for_level(level + 1) <= self.xp {
+            level += 1;
+        }
+        level
+    }
+}
+
+fn load_experience(storage: Res<Storage>, mut experience: ResMut<Experience>) {
+    experience.xp = storage.load(XP_KEY).unwrap_or(0);
+}
+
+fn forget_last_run(mut experience: ResMut<Experience>) {
+    experience.last_run = None;
+}
+
+fn award_xp(
+    current_score: Res<CurrentScore>,
+    stats: Res<RunStats>,
+    mut experience: ResMut<Experience>,
+    mut storage: ResMut<Storage>,
+) {
+    let earned = (current_score.0 / POINTS_PER_XP).max(0) as u32 + stats.duration as u32;
+    let level = experience.level();
+
+    experience.last_run = Some((earned, level));
+    experience.xp += earned;
+    storage.save(XP_KEY, experience.xp);
+}
+
+/// What the last run earned, the bar up to the next level, and the levels
+/// and skins it reached.
+pub fn spawn_xp_summary(builder: &mut ChildBuilder, experience: &Experience) {
+    let Some((earned, start_level)) = experience.last_run else {
+        return;
+    };
+    let level = experience.level();
+    let level_xp = xp_for_level(level);
+    let next_level_xp = xp_for_level(level + 1);
+    let progress = (experience.xp - level_xp) as f32 / (next_level_xp - level_xp) as f32;
+
+    builder.spawn((
+        Text::new(format!(
+            "+{earned} XP - level {level}, {}/{} to the next",
+            experience.xp - level_xp,
+            next_level_xp - level_xp
+        )),
+        TextFont {
+            font_size: 24.,
+            ..default()
+        },
+    ));
+    builder
+        .spawn((
+            Node {
+                width: Val::Px(BAR_WIDTH),
+                height: Val::Px(BAR_HEIGHT),
+                margin: UiRect::vertical(Val::Px(6.)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(1., 1., 1., 0.15)),
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Node {
+                    width: Val::Percent(progress * 100.),
+                    height: Val::Percent(100.),
+                    ..default()
+                },
+                BackgroundColor(BAR_COLOR),
+            ));
+        });
+
+    if level == start_level {
+        return;
+    }
+    builder.spawn((
+        Text::new(format!("level up! now level {level}")),
+        TextFont {
+            font_size: 30.,
+            ..default()
+        },
+        TextColor(Color::srgb(1., 0.8, 0.2)),
+    ));
+    for skin in Skin::unlocked_by_levels(start_level + 1..=level) {
+        builder.spawn((
+            Text::new(format!("unlocked the {skin} skin")),
+            TextFont {
+                font_size: 24.,
+                ..default()
+            },
+            TextColor(skin.color()),
+        ));
+    }
+}
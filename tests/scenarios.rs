@@ -0,0 +1,39 @@
+//! Plays every scenario in `scenarios/` with the game binary, failing with
+//! its report if any of its checks do.
+
+use std::{fs, path::Path, process::Command};
+
+#[test]
+fn scenarios_pass() {
+    let mut paths: Vec<_> = fs::read_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("scenarios"))
+        .expect("scenarios/ should be readable")
+        .map(|entry| entry.expect("scenarios/ should be readable").path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "ron"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "there should be scenarios to play");
+
+    let mut failed = Vec::new();
+    for path in paths {
+        // Frame time budgets are for release builds.
+        let contents = fs::read_to_string(&path).expect("scenario should be readable");
+        if cfg!(debug_assertions) && contents.contains("FrameTime(") {
+            println!("skipping {} in a debug build", path.display());
+            continue;
+        }
+
+        let output = Command::new(env!("CARGO_BIN_EXE_shape-eater"))
+            .arg("--scenario")
+            .arg(&path)
+            .output()
+            .expect("the game should start");
+        if !output.status.success() {
+            failed.push(format!(
+                "{}:\n{}",
+                path.display(),
+                String::from_utf8_lossy(&output.stdout)
+            ));
+        }
+    }
+    assert!(failed.is_empty(), "{}", failed.join("\n"));
+}